@@ -0,0 +1,226 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tokio_util::bytes::BytesMut;
+use tokio_util::codec::{Decoder as _, Encoder as _};
+use wasm_tokio::cm::{
+    F32Codec, F32ListCodec, F64Codec, F64ListCodec, Leb128ListDecoder, S8Codec, S8ListCodec,
+    U32Codec, U8Codec, U8ListCodec,
+};
+use wasm_tokio::{CoreVecDecoder, CoreVecEncoder};
+
+const LEN: u16 = 1 << 14;
+
+fn f32_list(c: &mut Criterion) {
+    let values: Vec<f32> = (0..LEN).map(|i| f32::from(i) * 0.25).collect();
+    let mut encoded = BytesMut::default();
+    F32ListCodec::default()
+        .encode(values.as_slice(), &mut encoded)
+        .expect("failed to encode list");
+
+    let mut g = c.benchmark_group("list<f32>");
+    g.bench_function("encode/per-element", |b| {
+        b.iter(|| {
+            let mut buf = BytesMut::default();
+            CoreVecEncoder(F32Codec)
+                .encode(black_box(values.as_slice()), &mut buf)
+                .unwrap();
+            buf
+        });
+    });
+    g.bench_function("encode/bulk", |b| {
+        b.iter(|| {
+            let mut buf = BytesMut::default();
+            F32ListCodec::default()
+                .encode(black_box(values.as_slice()), &mut buf)
+                .unwrap();
+            buf
+        });
+    });
+    g.bench_function("decode/per-element", |b| {
+        b.iter(|| {
+            CoreVecDecoder::<F32Codec>::default()
+                .decode(&mut black_box(encoded.clone()))
+                .unwrap()
+        });
+    });
+    g.bench_function("decode/bulk", |b| {
+        b.iter(|| {
+            F32ListCodec::default()
+                .decode(&mut black_box(encoded.clone()))
+                .unwrap()
+        });
+    });
+    g.finish();
+}
+
+fn f64_list(c: &mut Criterion) {
+    let values: Vec<f64> = (0..LEN).map(|i| f64::from(i) * 0.25).collect();
+    let mut encoded = BytesMut::default();
+    F64ListCodec::default()
+        .encode(values.as_slice(), &mut encoded)
+        .expect("failed to encode list");
+
+    let mut g = c.benchmark_group("list<f64>");
+    g.bench_function("encode/per-element", |b| {
+        b.iter(|| {
+            let mut buf = BytesMut::default();
+            CoreVecEncoder(F64Codec)
+                .encode(black_box(values.as_slice()), &mut buf)
+                .unwrap();
+            buf
+        });
+    });
+    g.bench_function("encode/bulk", |b| {
+        b.iter(|| {
+            let mut buf = BytesMut::default();
+            F64ListCodec::default()
+                .encode(black_box(values.as_slice()), &mut buf)
+                .unwrap();
+            buf
+        });
+    });
+    g.bench_function("decode/per-element", |b| {
+        b.iter(|| {
+            CoreVecDecoder::<F64Codec>::default()
+                .decode(&mut black_box(encoded.clone()))
+                .unwrap()
+        });
+    });
+    g.bench_function("decode/bulk", |b| {
+        b.iter(|| {
+            F64ListCodec::default()
+                .decode(&mut black_box(encoded.clone()))
+                .unwrap()
+        });
+    });
+    g.finish();
+}
+
+fn u8_list(c: &mut Criterion) {
+    let values: Vec<u8> = (0..LEN).map(|i| i as u8).collect();
+    let mut encoded = BytesMut::default();
+    U8ListCodec::default()
+        .encode(values.as_slice(), &mut encoded)
+        .expect("failed to encode list");
+
+    let mut g = c.benchmark_group("list<u8>");
+    g.bench_function("encode/per-element", |b| {
+        b.iter(|| {
+            let mut buf = BytesMut::default();
+            CoreVecEncoder(U8Codec)
+                .encode(black_box(values.as_slice()), &mut buf)
+                .unwrap();
+            buf
+        });
+    });
+    g.bench_function("encode/bulk", |b| {
+        b.iter(|| {
+            let mut buf = BytesMut::default();
+            U8ListCodec::default()
+                .encode(black_box(values.as_slice()), &mut buf)
+                .unwrap();
+            buf
+        });
+    });
+    g.bench_function("decode/per-element", |b| {
+        b.iter(|| {
+            CoreVecDecoder::<U8Codec>::default()
+                .decode(&mut black_box(encoded.clone()))
+                .unwrap()
+        });
+    });
+    g.bench_function("decode/bulk", |b| {
+        b.iter(|| {
+            U8ListCodec::default()
+                .decode(&mut black_box(encoded.clone()))
+                .unwrap()
+        });
+    });
+    g.finish();
+}
+
+fn s8_list(c: &mut Criterion) {
+    let values: Vec<i8> = (0..LEN).map(|i| i as i8).collect();
+    let mut encoded = BytesMut::default();
+    S8ListCodec::default()
+        .encode(values.as_slice(), &mut encoded)
+        .expect("failed to encode list");
+
+    let mut g = c.benchmark_group("list<s8>");
+    g.bench_function("encode/per-element", |b| {
+        b.iter(|| {
+            let mut buf = BytesMut::default();
+            CoreVecEncoder(S8Codec)
+                .encode(black_box(values.as_slice()), &mut buf)
+                .unwrap();
+            buf
+        });
+    });
+    g.bench_function("encode/bulk", |b| {
+        b.iter(|| {
+            let mut buf = BytesMut::default();
+            S8ListCodec::default()
+                .encode(black_box(values.as_slice()), &mut buf)
+                .unwrap();
+            buf
+        });
+    });
+    g.bench_function("decode/per-element", |b| {
+        b.iter(|| {
+            CoreVecDecoder::<S8Codec>::default()
+                .decode(&mut black_box(encoded.clone()))
+                .unwrap()
+        });
+    });
+    g.bench_function("decode/bulk", |b| {
+        b.iter(|| {
+            S8ListCodec::default()
+                .decode(&mut black_box(encoded.clone()))
+                .unwrap()
+        });
+    });
+    g.finish();
+}
+
+fn u32_list(c: &mut Criterion) {
+    for (name, values) in [
+        (
+            "list<u32>/single-byte",
+            (0..LEN).map(|i| u32::from(i) % 0x80).collect::<Vec<_>>(),
+        ),
+        (
+            "list<u32>/mixed-width",
+            (0..LEN)
+                .map(|i| match i % 4 {
+                    0 => u32::from(i) << 14,
+                    1 => u32::from(i) << 7,
+                    _ => u32::from(i) % 0x80,
+                })
+                .collect(),
+        ),
+    ] {
+        let mut encoded = BytesMut::default();
+        CoreVecEncoder(U32Codec)
+            .encode(values.as_slice(), &mut encoded)
+            .expect("failed to encode list");
+
+        let mut g = c.benchmark_group(name);
+        g.bench_function("decode/per-element", |b| {
+            b.iter(|| {
+                CoreVecDecoder::<U32Codec>::default()
+                    .decode(&mut black_box(encoded.clone()))
+                    .unwrap()
+            });
+        });
+        g.bench_function("decode/bulk", |b| {
+            b.iter(|| {
+                Leb128ListDecoder::<u32>::default()
+                    .decode(&mut black_box(encoded.clone()))
+                    .unwrap()
+            });
+        });
+        g.finish();
+    }
+}
+
+criterion_group!(benches, f32_list, f64_list, u8_list, s8_list, u32_list);
+criterion_main!(benches);
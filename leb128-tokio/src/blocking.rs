@@ -0,0 +1,202 @@
+//! Blocking extensions for [`std::io`] readers and writers
+
+use crate::{
+    check_var_bits, decode_var_i128_leb128, decode_var_i16_leb128, decode_var_i32_leb128,
+    decode_var_i64_leb128, decode_var_i8_leb128, decode_var_u128_leb128, decode_var_u16_leb128,
+    decode_var_u32_leb128, decode_var_u64_leb128, decode_var_u8_leb128, invalid_data,
+    put_i128_leb128, put_i16_leb128, put_i32_leb128, put_i64_leb128, put_i8_leb128,
+    put_u128_leb128, put_u16_leb128, put_u32_leb128, put_u64_leb128, put_u8_leb128,
+    put_zigzag_i128_leb128, put_zigzag_i16_leb128, put_zigzag_i32_leb128, put_zigzag_i64_leb128,
+    put_zigzag_i8_leb128, BufLeb128Ext as _, Overflow, OverflowVar,
+};
+
+macro_rules! read_leb128 {
+    ($($f:ident, $peek:ident, $t:ty, $bits:literal, $max:literal;)+) => {
+        $(
+            #[doc = concat!("Reads LEB128-encoded [`", stringify!($t), "`]")]
+            fn $f(&mut self) -> std::io::Result<$t> {
+                let mut buf = [0; $max];
+                for i in 0..$max {
+                    self.read_exact(&mut buf[i..=i])?;
+                    if let Some((x, _)) = (&buf[..=i]).$peek()? {
+                        return Ok(x);
+                    }
+                }
+                Err(invalid_data(Overflow::<$bits>))
+            }
+        )+
+    };
+}
+
+macro_rules! read_var_leb128 {
+    ($($f:ident, $decode:ident, $t:ty, $max:literal;)+) => {
+        $(
+            #[doc = concat!("Reads LEB128-encoded [`", stringify!($t), "`] of at most `n` bits")]
+            fn $f(&mut self, n: u8) -> std::io::Result<$t> {
+                check_var_bits(n, <$t>::BITS as u8)?;
+                let mut buf = [0; $max];
+                for i in 0..$max {
+                    self.read_exact(&mut buf[i..=i])?;
+                    if let Some((x, _)) = $decode(&buf[..=i], n)? {
+                        return Ok(x);
+                    }
+                }
+                Err(invalid_data(OverflowVar(n)))
+            }
+        )+
+    };
+}
+
+macro_rules! read_zigzag_leb128 {
+    ($($f:ident, $read:ident, $t:ty;)+) => {
+        $(
+            #[doc = concat!("Reads ZigZag LEB128-encoded [`", stringify!($t), "`]")]
+            fn $f(&mut self) -> std::io::Result<$t> {
+                let x = self.$read()?;
+                Ok((x >> 1) as $t ^ -((x & 1) as $t))
+            }
+        )+
+    };
+}
+
+/// Blocking LEB128 extensions for [`std::io::Read`], producing values identical to
+/// [`AsyncReadLeb128`](crate::AsyncReadLeb128)
+pub trait ReadLeb128: std::io::Read {
+    read_leb128! {
+        read_u8_leb128, peek_u8_leb128, u8, 8, 2;
+        read_u16_leb128, peek_u16_leb128, u16, 16, 3;
+        read_u32_leb128, peek_u32_leb128, u32, 32, 5;
+        read_u64_leb128, peek_u64_leb128, u64, 64, 10;
+        read_u128_leb128, peek_u128_leb128, u128, 128, 19;
+        read_i8_leb128, peek_i8_leb128, i8, 8, 2;
+        read_i16_leb128, peek_i16_leb128, i16, 16, 3;
+        read_i32_leb128, peek_i32_leb128, i32, 32, 5;
+        read_i64_leb128, peek_i64_leb128, i64, 64, 10;
+        read_i128_leb128, peek_i128_leb128, i128, 128, 19;
+    }
+
+    read_var_leb128! {
+        read_var_u8_leb128, decode_var_u8_leb128, u8, 2;
+        read_var_u16_leb128, decode_var_u16_leb128, u16, 3;
+        read_var_u32_leb128, decode_var_u32_leb128, u32, 5;
+        read_var_u64_leb128, decode_var_u64_leb128, u64, 10;
+        read_var_u128_leb128, decode_var_u128_leb128, u128, 19;
+        read_var_i8_leb128, decode_var_i8_leb128, i8, 2;
+        read_var_i16_leb128, decode_var_i16_leb128, i16, 3;
+        read_var_i32_leb128, decode_var_i32_leb128, i32, 5;
+        read_var_i64_leb128, decode_var_i64_leb128, i64, 10;
+        read_var_i128_leb128, decode_var_i128_leb128, i128, 19;
+    }
+
+    read_zigzag_leb128! {
+        read_zigzag_i8_leb128, read_u8_leb128, i8;
+        read_zigzag_i16_leb128, read_u16_leb128, i16;
+        read_zigzag_i32_leb128, read_u32_leb128, i32;
+        read_zigzag_i64_leb128, read_u64_leb128, i64;
+        read_zigzag_i128_leb128, read_u128_leb128, i128;
+    }
+}
+
+impl<T: std::io::Read + ?Sized> ReadLeb128 for T {}
+
+macro_rules! write_leb128 {
+    ($($f:ident, $put:ident, $t:ty;)+) => {
+        $(
+            #[doc = concat!("Writes [`", stringify!($t), "`] using [`", stringify!($put), "`]")]
+            fn $f(&mut self, x: $t) -> std::io::Result<()> {
+                self.write_all($put(&mut Default::default(), x))
+            }
+        )+
+    };
+}
+
+/// Blocking LEB128 extensions for [`std::io::Write`], producing bytes identical to
+/// [`AsyncWriteLeb128`](crate::AsyncWriteLeb128)
+pub trait WriteLeb128: std::io::Write {
+    write_leb128! {
+        write_u8_leb128, put_u8_leb128, u8;
+        write_u16_leb128, put_u16_leb128, u16;
+        write_u32_leb128, put_u32_leb128, u32;
+        write_u64_leb128, put_u64_leb128, u64;
+        write_u128_leb128, put_u128_leb128, u128;
+        write_i8_leb128, put_i8_leb128, i8;
+        write_i16_leb128, put_i16_leb128, i16;
+        write_i32_leb128, put_i32_leb128, i32;
+        write_i64_leb128, put_i64_leb128, i64;
+        write_i128_leb128, put_i128_leb128, i128;
+        write_zigzag_i8_leb128, put_zigzag_i8_leb128, i8;
+        write_zigzag_i16_leb128, put_zigzag_i16_leb128, i16;
+        write_zigzag_i32_leb128, put_zigzag_i32_leb128, i32;
+        write_zigzag_i64_leb128, put_zigzag_i64_leb128, i64;
+        write_zigzag_i128_leb128, put_zigzag_i128_leb128, i128;
+    }
+}
+
+impl<T: std::io::Write + ?Sized> WriteLeb128 for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read() {
+        let mut buf = vec![];
+        buf.write_u32_leb128(300).expect("failed to write u32");
+        buf.write_i64_leb128(-300).expect("failed to write i64");
+        buf.write_zigzag_i16_leb128(-3)
+            .expect("failed to write i16");
+        buf.write_u8_leb128(0x7f).expect("failed to write u8");
+        buf.write_i16_leb128(-2).expect("failed to write i16");
+
+        let mut r = buf.as_slice();
+        assert_eq!(r.read_u32_leb128().expect("failed to read u32"), 300);
+        assert_eq!(r.read_i64_leb128().expect("failed to read i64"), -300);
+        assert_eq!(r.read_zigzag_i16_leb128().expect("failed to read i16"), -3);
+        assert_eq!(r.read_var_u8_leb128(7).expect("failed to read u7"), 0x7f);
+        assert_eq!(r.read_var_i16_leb128(9).expect("failed to read s9"), -2);
+        assert!(r.is_empty());
+
+        [0x80, 0x80]
+            .as_slice()
+            .read_u32_leb128()
+            .expect_err("reading truncated u32 should have failed");
+        [0xff, 0xff, 0xff, 0xff, 0x1f]
+            .as_slice()
+            .read_u32_leb128()
+            .expect_err("u32 decode should have failed, since it encoded 33 bits");
+        [0xff, 0x04]
+            .as_slice()
+            .read_var_u16_leb128(9)
+            .expect_err("u9 read should have failed, since it encoded 10 bits");
+        [0xff]
+            .as_slice()
+            .read_var_u16_leb128(9)
+            .expect_err("reading truncated u9 should have failed");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn blocking() {
+        let mut buf = vec![];
+        buf.write_u32_leb128(300).expect("failed to write u32");
+        buf.write_i64_leb128(-300).expect("failed to write i64");
+        buf.write_zigzag_i16_leb128(-3)
+            .expect("failed to write i16");
+        buf.write_u8_leb128(0x7f).expect("failed to write u8");
+
+        let mut expected = vec![];
+        crate::AsyncWriteLeb128::write_u32_leb128(&mut expected, 300)
+            .await
+            .expect("failed to write u32");
+        crate::AsyncWriteLeb128::write_i64_leb128(&mut expected, -300)
+            .await
+            .expect("failed to write i64");
+        crate::AsyncWriteLeb128::write_zigzag_i16_leb128(&mut expected, -3)
+            .await
+            .expect("failed to write i16");
+        crate::AsyncWriteLeb128::write_u8_leb128(&mut expected, 0x7f)
+            .await
+            .expect("failed to write u8");
+        assert_eq!(buf, expected);
+    }
+}
@@ -0,0 +1,298 @@
+use alloc::vec::Vec;
+
+use crate::{
+    decode_i128_leb128, decode_i16_leb128, decode_i32_leb128, decode_i64_leb128, decode_i8_leb128,
+    decode_u128_leb128, decode_u16_leb128, decode_u32_leb128, decode_u64_leb128, decode_u8_leb128,
+    invalid_data, Error,
+};
+
+/// Integers, which can be decoded from contiguous LEB128 input in bulk
+///
+/// Only runs of single-byte values are scanned in bulk, using SIMD on `x86_64` and `aarch64` and
+/// 8 bytes at a time on other targets. Multi-byte values are decoded one at a time using the
+/// scalar decoders, e.g. [`decode_u32_leb128`](crate::decode_u32_leb128), so input with many
+/// multi-byte values, e.g. most [`u128`] values, benefits considerably less than mostly
+/// single-byte input.
+pub trait DecodeLeb128Bulk: Sized {
+    /// Decodes up to `n` LEB128 values from `src`, appending them to `dst`.
+    /// Returns the number of bytes consumed. Fewer than `n` values are decoded
+    /// if `src` ends before the `n`-th value is complete.
+    fn decode_leb128_bulk(src: &[u8], n: usize, dst: &mut Vec<Self>) -> Result<usize, Error>;
+}
+
+fn decode_bulk<T>(
+    src: &[u8],
+    n: usize,
+    dst: &mut Vec<T>,
+    single: impl Fn(u8) -> T,
+    decode: impl Fn(&[u8]) -> Result<Option<(T, usize)>, Error>,
+) -> Result<usize, Error> {
+    let mut i = 0;
+    let mut n = n;
+    dst.reserve(n.min(src.len()));
+    while n > 0 {
+        let run = &src[i..];
+        let run = &run[..run.len().min(n)];
+        let k = single_byte_prefix(run);
+        dst.extend(run[..k].iter().copied().map(&single));
+        i += k;
+        n -= k;
+        if n == 0 {
+            break;
+        }
+        let Some((x, k)) = decode(&src[i..])? else {
+            break;
+        };
+        dst.push(x);
+        i += k;
+        n -= 1;
+    }
+    Ok(i)
+}
+
+macro_rules! impl_decode_bulk {
+    ($t:ty, $decode:ident, $single:expr) => {
+        impl DecodeLeb128Bulk for $t {
+            fn decode_leb128_bulk(
+                src: &[u8],
+                n: usize,
+                dst: &mut Vec<Self>,
+            ) -> Result<usize, Error> {
+                decode_bulk(src, n, dst, $single, |src| {
+                    $decode(src).map_err(invalid_data)
+                })
+            }
+        }
+    };
+}
+
+/// Sign-extends a single-byte signed LEB128 value
+fn sign_extend(b: u8) -> i8 {
+    (b << 1) as i8 >> 1
+}
+
+impl_decode_bulk!(u8, decode_u8_leb128, |b| b);
+impl_decode_bulk!(u16, decode_u16_leb128, <u16>::from);
+impl_decode_bulk!(u32, decode_u32_leb128, <u32>::from);
+impl_decode_bulk!(u64, decode_u64_leb128, <u64>::from);
+impl_decode_bulk!(u128, decode_u128_leb128, <u128>::from);
+impl_decode_bulk!(i8, decode_i8_leb128, sign_extend);
+impl_decode_bulk!(i16, decode_i16_leb128, |b| sign_extend(b).into());
+impl_decode_bulk!(i32, decode_i32_leb128, |b| sign_extend(b).into());
+impl_decode_bulk!(i64, decode_i64_leb128, |b| sign_extend(b).into());
+impl_decode_bulk!(i128, decode_i128_leb128, |b| sign_extend(b).into());
+
+/// Returns the length of the longest prefix of `src` without continuation bits set,
+/// i.e. the number of single-byte LEB128 values `src` starts with
+#[cfg(target_arch = "x86_64")]
+fn single_byte_prefix(src: &[u8]) -> usize {
+    #[cfg(feature = "std")]
+    let avx2 = std::arch::is_x86_feature_detected!("avx2");
+    // runtime feature detection is not available without `std`
+    #[cfg(not(feature = "std"))]
+    let avx2 = cfg!(target_feature = "avx2");
+    if avx2 {
+        // SAFETY: AVX2 support was checked above
+        unsafe { x86_64::single_byte_prefix_avx2(src) }
+    } else {
+        // SAFETY: SSE2 is always available on x86_64
+        unsafe { x86_64::single_byte_prefix_sse2(src) }
+    }
+}
+
+/// Returns the length of the longest prefix of `src` without continuation bits set,
+/// i.e. the number of single-byte LEB128 values `src` starts with
+#[cfg(target_arch = "aarch64")]
+fn single_byte_prefix(src: &[u8]) -> usize {
+    // SAFETY: NEON is always available on aarch64
+    unsafe { aarch64::single_byte_prefix_neon(src) }
+}
+
+/// Returns the length of the longest prefix of `src` without continuation bits set,
+/// i.e. the number of single-byte LEB128 values `src` starts with
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn single_byte_prefix(src: &[u8]) -> usize {
+    single_byte_prefix_scalar(src)
+}
+
+fn single_byte_prefix_scalar(src: &[u8]) -> usize {
+    let (chunks, rest) = src.as_chunks::<8>();
+    let mut n = 0;
+    for chunk in chunks {
+        let w = u64::from_le_bytes(*chunk) & 0x8080_8080_8080_8080;
+        if w != 0 {
+            return n + (w.trailing_zeros() / 8) as usize;
+        }
+        n += 8;
+    }
+    n + rest.iter().take_while(|b| *b & 0x80 == 0).count()
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86_64 {
+    use core::arch::x86_64::{
+        _mm256_loadu_si256, _mm256_movemask_epi8, _mm_loadu_si128, _mm_movemask_epi8,
+    };
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn single_byte_prefix_sse2(src: &[u8]) -> usize {
+        let (chunks, rest) = src.as_chunks::<16>();
+        let mut n = 0;
+        for chunk in chunks {
+            let m = _mm_movemask_epi8(_mm_loadu_si128(chunk.as_ptr().cast())) as u32;
+            if m != 0 {
+                return n + m.trailing_zeros() as usize;
+            }
+            n += 16;
+        }
+        n + super::single_byte_prefix_scalar(rest)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn single_byte_prefix_avx2(src: &[u8]) -> usize {
+        let (chunks, rest) = src.as_chunks::<32>();
+        let mut n = 0;
+        for chunk in chunks {
+            let m = _mm256_movemask_epi8(_mm256_loadu_si256(chunk.as_ptr().cast())) as u32;
+            if m != 0 {
+                return n + m.trailing_zeros() as usize;
+            }
+            n += 32;
+        }
+        n + single_byte_prefix_sse2(rest)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+    use core::arch::aarch64::{vld1q_u8, vmaxvq_u8};
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn single_byte_prefix_neon(src: &[u8]) -> usize {
+        let (chunks, rest) = src.as_chunks::<16>();
+        let mut n = 0;
+        for chunk in chunks {
+            if vmaxvq_u8(vld1q_u8(chunk.as_ptr())) & 0x80 != 0 {
+                return n + super::single_byte_prefix_scalar(chunk);
+            }
+            n += 16;
+        }
+        n + super::single_byte_prefix_scalar(rest)
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use bytes::BytesMut;
+    use tokio_util::codec::{Decoder as _, Encoder as _};
+
+    use crate::{Leb128DecoderI64, Leb128DecoderU32, Leb128Encoder};
+
+    use super::*;
+
+    #[test]
+    fn bulk() {
+        let values: Vec<u32> = (0..1000u32)
+            .map(|i| match i % 7 {
+                0 => i.wrapping_mul(0x9e37_79b9),
+                1 => i << 7,
+                _ => i % 0x80,
+            })
+            .collect();
+        let mut buf = BytesMut::default();
+        for v in &values {
+            Leb128Encoder
+                .encode(v, &mut buf)
+                .expect("failed to encode u32");
+        }
+        assert_eq!(single_byte_prefix(&buf), single_byte_prefix_scalar(&buf));
+        #[cfg(target_arch = "x86_64")]
+        // SAFETY: SSE2 is always available on x86_64
+        assert_eq!(
+            unsafe { x86_64::single_byte_prefix_sse2(&buf) },
+            single_byte_prefix_scalar(&buf)
+        );
+
+        let mut dst = vec![];
+        let n =
+            u32::decode_leb128_bulk(&buf, values.len(), &mut dst).expect("failed to decode u32s");
+        assert_eq!(n, buf.len());
+        assert_eq!(dst, values);
+
+        let mut dst = vec![];
+        let n = u32::decode_leb128_bulk(&buf[..buf.len() - 1], values.len(), &mut dst)
+            .expect("failed to decode u32s");
+        assert_eq!(dst, values[..dst.len()]);
+        let mut rest = BytesMut::from(&buf[n..]);
+        for v in &values[dst.len()..] {
+            assert_eq!(Leb128DecoderU32.decode(&mut rest).unwrap(), Some(*v));
+        }
+
+        let mut dst = vec![];
+        let n = u32::decode_leb128_bulk(&buf, 10, &mut dst).expect("failed to decode u32s");
+        assert_eq!(dst, values[..10]);
+        let mut rest = BytesMut::from(&buf[n..]);
+        assert_eq!(
+            Leb128DecoderU32.decode(&mut rest).unwrap(),
+            Some(values[10])
+        );
+
+        let values: Vec<i64> = (-500..500i64)
+            .map(|i| if i % 5 == 0 { i << 40 } else { i % 64 })
+            .collect();
+        let mut buf = BytesMut::default();
+        for v in &values {
+            Leb128Encoder
+                .encode(v, &mut buf)
+                .expect("failed to encode i64");
+        }
+        let mut dst = vec![];
+        let n =
+            i64::decode_leb128_bulk(&buf, values.len(), &mut dst).expect("failed to decode i64s");
+        assert_eq!(n, buf.len());
+        assert_eq!(dst, values);
+        let mut buf = BytesMut::from(&buf[..]);
+        assert_eq!(Leb128DecoderI64.decode(&mut buf).unwrap(), Some(values[0]));
+
+        let mut dst = vec![];
+        let n = u8::decode_leb128_bulk(&[0x01, 0x7f, 0xff, 0x01, 0x80], 4, &mut dst)
+            .expect("failed to decode u8s");
+        assert_eq!(n, 4);
+        assert_eq!(dst, [0x01, 0x7f, 0xff]);
+
+        let mut dst = vec![];
+        let n = i8::decode_leb128_bulk(&[0x01, 0x7f, 0xc0, 0x00], 3, &mut dst)
+            .expect("failed to decode i8s");
+        assert_eq!(n, 4);
+        assert_eq!(dst, [1, -1, 64]);
+
+        let values = [0, u128::MAX, 1 << 100, 0x7f];
+        let mut buf = BytesMut::default();
+        for v in &values {
+            Leb128Encoder
+                .encode(v, &mut buf)
+                .expect("failed to encode u128");
+        }
+        let mut dst = vec![];
+        let n =
+            u128::decode_leb128_bulk(&buf, values.len(), &mut dst).expect("failed to decode u128s");
+        assert_eq!(n, buf.len());
+        assert_eq!(dst, values);
+
+        let values = [0, -1, 1 << 100, -65];
+        let mut buf = BytesMut::default();
+        for v in &values {
+            Leb128Encoder
+                .encode(v, &mut buf)
+                .expect("failed to encode i128");
+        }
+        let mut dst = vec![];
+        let n =
+            i128::decode_leb128_bulk(&buf, values.len(), &mut dst).expect("failed to decode i128s");
+        assert_eq!(n, buf.len());
+        assert_eq!(dst, values);
+
+        u32::decode_leb128_bulk(&[0x01, 0xff, 0xff, 0xff, 0xff, 0x10], 2, &mut vec![])
+            .expect_err("u32 decode should have failed, since it encoded 33 bits");
+    }
+}
@@ -0,0 +1,652 @@
+#[cfg(feature = "tokio")]
+use bytes::{Buf as _, BytesMut};
+#[cfg(feature = "tokio")]
+use tokio_util::codec::{Decoder, Encoder};
+
+#[cfg(feature = "tokio")]
+use crate::{
+    decode_var_i128_leb128, decode_var_i16_leb128, decode_var_i32_leb128, decode_var_i64_leb128,
+    decode_var_i8_leb128, decode_var_u128_leb128, decode_var_u16_leb128, decode_var_u32_leb128,
+    decode_var_u64_leb128, decode_var_u8_leb128, invalid_data, put_i128_leb128, put_i16_leb128,
+    put_i32_leb128, put_i64_leb128, put_i8_leb128, put_u128_leb128, put_u128_leb128_padded,
+    put_u16_leb128, put_u16_leb128_padded, put_u32_leb128, put_u32_leb128_padded, put_u64_leb128,
+    put_u64_leb128_padded, put_u8_leb128, put_u8_leb128_padded, put_zigzag_i128_leb128,
+    put_zigzag_i16_leb128, put_zigzag_i32_leb128, put_zigzag_i64_leb128, put_zigzag_i8_leb128,
+    Overflow,
+};
+
+pub struct Leb128DecoderU8;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderU8 {
+    type Item = u8;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..2 {
+            let Some(b) = src.get(i) else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 7 && *b > 0x01 {
+                return Err(invalid_data(Overflow::<8>));
+            }
+            x |= (b & 0x7f) << s;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                return Ok(Some(x));
+            }
+            s += 7;
+        }
+        Err(invalid_data(Overflow::<8>))
+    }
+}
+
+pub struct Leb128DecoderU16;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderU16 {
+    type Item = u16;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..3 {
+            let Some(b) = src.get(i) else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 14 && *b > 0x03 {
+                return Err(invalid_data(Overflow::<16>));
+            }
+            x |= (u16::from(*b) & 0x7f) << s;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                return Ok(Some(x));
+            }
+            s += 7;
+        }
+        Err(invalid_data(Overflow::<16>))
+    }
+}
+
+pub struct Leb128DecoderU32;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderU32 {
+    type Item = u32;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..5 {
+            let Some(b) = src.get(i) else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 28 && *b > 0x0f {
+                return Err(invalid_data(Overflow::<32>));
+            }
+            x |= (u32::from(*b) & 0x7f) << s;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                return Ok(Some(x));
+            }
+            s += 7;
+        }
+        Err(invalid_data(Overflow::<32>))
+    }
+}
+
+pub struct Leb128DecoderU64;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderU64 {
+    type Item = u64;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..10 {
+            let Some(b) = src.get(i) else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 63 && *b > 0x01 {
+                return Err(invalid_data(Overflow::<64>));
+            }
+            x |= (u64::from(*b) & 0x7f) << s;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                return Ok(Some(x));
+            }
+            s += 7;
+        }
+        Err(invalid_data(Overflow::<64>))
+    }
+}
+
+pub struct Leb128DecoderU128;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderU128 {
+    type Item = u128;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..19 {
+            let Some(b) = src.get(i) else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 126 && *b > 0x03 {
+                return Err(invalid_data(Overflow::<128>));
+            }
+            x |= (u128::from(*b) & 0x7f) << s;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                return Ok(Some(x));
+            }
+            s += 7;
+        }
+        Err(invalid_data(Overflow::<128>))
+    }
+}
+
+pub struct Leb128DecoderI8;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderI8 {
+    type Item = i8;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..2 {
+            let Some(b) = src.get(i).copied() else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 7 && b > 0x01 {
+                return Err(invalid_data(Overflow::<8>));
+            }
+            x |= ((b as i8) & 0x7f) << s;
+            s += 7;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                if s != 14 && b & 0x40 != 0 {
+                    return Ok(Some(x | !0 << s));
+                } else {
+                    return Ok(Some(x));
+                }
+            }
+        }
+        Err(invalid_data(Overflow::<8>))
+    }
+}
+
+pub struct Leb128DecoderI16;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderI16 {
+    type Item = i16;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..3 {
+            let Some(b) = src.get(i).copied() else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 14 && b > 0x03 {
+                return Err(invalid_data(Overflow::<16>));
+            }
+            x |= (i16::from(b) & 0x7f) << s;
+            s += 7;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                if s != 21 && b & 0x40 != 0 {
+                    return Ok(Some(x | !0 << s));
+                } else {
+                    return Ok(Some(x));
+                }
+            }
+        }
+        Err(invalid_data(Overflow::<16>))
+    }
+}
+
+pub struct Leb128DecoderI32;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderI32 {
+    type Item = i32;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..5 {
+            let Some(b) = src.get(i).copied() else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 28 && b > 0x0f {
+                return Err(invalid_data(Overflow::<32>));
+            }
+            x |= (i32::from(b) & 0x7f) << s;
+            s += 7;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                if s != 35 && b & 0x40 != 0 {
+                    return Ok(Some(x | !0 << s));
+                } else {
+                    return Ok(Some(x));
+                }
+            }
+        }
+        Err(invalid_data(Overflow::<32>))
+    }
+}
+
+pub struct Leb128DecoderI64;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderI64 {
+    type Item = i64;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..10 {
+            let Some(b) = src.get(i).copied() else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 63 && b > 0x01 {
+                return Err(invalid_data(Overflow::<64>));
+            }
+            x |= (i64::from(b) & 0x7f) << s;
+            s += 7;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                if s != 70 && b & 0x40 != 0 {
+                    return Ok(Some(x | !0 << s));
+                } else {
+                    return Ok(Some(x));
+                }
+            }
+        }
+        Err(invalid_data(Overflow::<64>))
+    }
+}
+
+pub struct Leb128DecoderI128;
+
+#[cfg(feature = "tokio")]
+impl Decoder for Leb128DecoderI128 {
+    type Item = i128;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let mut x = 0;
+        let mut s = 0u8;
+        for i in 0..19 {
+            let Some(b) = src.get(i).copied() else {
+                src.reserve(1);
+                return Ok(None);
+            };
+            if s == 126 && b > 0x03 {
+                return Err(invalid_data(Overflow::<128>));
+            }
+            x |= (i128::from(b) & 0x7f) << s;
+            s += 7;
+            if b & 0x80 == 0 {
+                src.advance(i + 1);
+                if s != 133 && b & 0x40 != 0 {
+                    return Ok(Some(x | !0 << s));
+                } else {
+                    return Ok(Some(x));
+                }
+            }
+        }
+        Err(invalid_data(Overflow::<128>))
+    }
+}
+
+macro_rules! impl_var_decoder {
+    ($dec:ident, $t:ty, $f:ident) => {
+        #[cfg(feature = "tokio")]
+        impl Decoder for $dec {
+            type Item = $t;
+            type Error = std::io::Error;
+
+            fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+                let Some((x, n)) = $f(src, self.bits)? else {
+                    src.reserve(1);
+                    return Ok(None);
+                };
+                src.advance(n);
+                Ok(Some(x))
+            }
+        }
+    };
+}
+
+/// [`Decoder`] of u8 values encoded using `bits`-bit unsigned LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderU8 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderU8, u8, decode_var_u8_leb128);
+
+/// [`Decoder`] of u16 values encoded using `bits`-bit unsigned LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderU16 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderU16, u16, decode_var_u16_leb128);
+
+/// [`Decoder`] of u32 values encoded using `bits`-bit unsigned LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderU32 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderU32, u32, decode_var_u32_leb128);
+
+/// [`Decoder`] of u64 values encoded using `bits`-bit unsigned LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderU64 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderU64, u64, decode_var_u64_leb128);
+
+/// [`Decoder`] of u128 values encoded using `bits`-bit unsigned LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderU128 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderU128, u128, decode_var_u128_leb128);
+
+/// [`Decoder`] of i8 values encoded using `bits`-bit signed LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderI8 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderI8, i8, decode_var_i8_leb128);
+
+/// [`Decoder`] of i16 values encoded using `bits`-bit signed LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderI16 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderI16, i16, decode_var_i16_leb128);
+
+/// [`Decoder`] of i32 values encoded using `bits`-bit signed LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderI32 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderI32, i32, decode_var_i32_leb128);
+
+/// [`Decoder`] of i64 values encoded using `bits`-bit signed LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderI64 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderI64, i64, decode_var_i64_leb128);
+
+/// [`Decoder`] of i128 values encoded using `bits`-bit signed LEB128
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128VarDecoderI128 {
+    pub bits: u8,
+}
+
+impl_var_decoder!(Leb128VarDecoderI128, i128, decode_var_i128_leb128);
+
+pub struct Leb128Encoder;
+
+macro_rules! impl_encode {
+    ($enc:ident, $t:ty, $f:ident) => {
+        #[cfg(feature = "tokio")]
+        impl Encoder<$t> for $enc {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: $t, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                dst.extend_from_slice($f(&mut Default::default(), item));
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl Encoder<&$t> for $enc {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: &$t, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode(*item, dst)
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl Encoder<&&$t> for $enc {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: &&$t, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode(*item, dst)
+            }
+        }
+    };
+}
+
+impl_encode!(Leb128Encoder, u8, put_u8_leb128);
+impl_encode!(Leb128Encoder, u16, put_u16_leb128);
+impl_encode!(Leb128Encoder, u32, put_u32_leb128);
+impl_encode!(Leb128Encoder, u64, put_u64_leb128);
+impl_encode!(Leb128Encoder, u128, put_u128_leb128);
+impl_encode!(Leb128Encoder, i8, put_i8_leb128);
+impl_encode!(Leb128Encoder, i16, put_i16_leb128);
+impl_encode!(Leb128Encoder, i32, put_i32_leb128);
+impl_encode!(Leb128Encoder, i64, put_i64_leb128);
+impl_encode!(Leb128Encoder, i128, put_i128_leb128);
+
+pub struct ZigZagDecoderI8;
+
+#[cfg(feature = "tokio")]
+impl Decoder for ZigZagDecoderI8 {
+    type Item = i8;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(x) = Leb128DecoderU8.decode(src)? else {
+            return Ok(None);
+        };
+        Ok(Some((x >> 1) as i8 ^ -((x & 1) as i8)))
+    }
+}
+
+pub struct ZigZagDecoderI16;
+
+#[cfg(feature = "tokio")]
+impl Decoder for ZigZagDecoderI16 {
+    type Item = i16;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(x) = Leb128DecoderU16.decode(src)? else {
+            return Ok(None);
+        };
+        Ok(Some((x >> 1) as i16 ^ -((x & 1) as i16)))
+    }
+}
+
+pub struct ZigZagDecoderI32;
+
+#[cfg(feature = "tokio")]
+impl Decoder for ZigZagDecoderI32 {
+    type Item = i32;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(x) = Leb128DecoderU32.decode(src)? else {
+            return Ok(None);
+        };
+        Ok(Some((x >> 1) as i32 ^ -((x & 1) as i32)))
+    }
+}
+
+pub struct ZigZagDecoderI64;
+
+#[cfg(feature = "tokio")]
+impl Decoder for ZigZagDecoderI64 {
+    type Item = i64;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(x) = Leb128DecoderU64.decode(src)? else {
+            return Ok(None);
+        };
+        Ok(Some((x >> 1) as i64 ^ -((x & 1) as i64)))
+    }
+}
+
+pub struct ZigZagDecoderI128;
+
+#[cfg(feature = "tokio")]
+impl Decoder for ZigZagDecoderI128 {
+    type Item = i128;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(x) = Leb128DecoderU128.decode(src)? else {
+            return Ok(None);
+        };
+        Ok(Some((x >> 1) as i128 ^ -((x & 1) as i128)))
+    }
+}
+
+/// [`Encoder`] of signed integers using ZigZag encoding on top of unsigned LEB128,
+/// as used by Protocol Buffers
+pub struct ZigZagEncoder;
+
+impl_encode!(ZigZagEncoder, i8, put_zigzag_i8_leb128);
+impl_encode!(ZigZagEncoder, i16, put_zigzag_i16_leb128);
+impl_encode!(ZigZagEncoder, i32, put_zigzag_i32_leb128);
+impl_encode!(ZigZagEncoder, i64, put_zigzag_i64_leb128);
+impl_encode!(ZigZagEncoder, i128, put_zigzag_i128_leb128);
+
+/// [`Encoder`] of LEB128 values padded to exactly `N` bytes
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Leb128PaddedEncoder<const N: usize>;
+
+macro_rules! impl_encode_padded {
+    ($t:ty, $f:ident) => {
+        #[cfg(feature = "tokio")]
+        impl<const N: usize> Encoder<$t> for Leb128PaddedEncoder<N> {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: $t, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                let mut buf = [0; N];
+                let buf = $f(&mut buf, item).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("value does not fit in {N} LEB128 bytes"),
+                    )
+                })?;
+                dst.extend_from_slice(buf);
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl<const N: usize> Encoder<&$t> for Leb128PaddedEncoder<N> {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: &$t, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode(*item, dst)
+            }
+        }
+    };
+}
+
+impl_encode_padded!(u8, put_u8_leb128_padded);
+impl_encode_padded!(u16, put_u16_leb128_padded);
+impl_encode_padded!(u32, put_u32_leb128_padded);
+impl_encode_padded!(u64, put_u64_leb128_padded);
+impl_encode_padded!(u128, put_u128_leb128_padded);
+
+/// Padded `N`-byte [`u32`] LEB128 placeholder within a [`BytesMut`], which can be
+/// patched once the value is known, e.g. a WebAssembly section or function body size.
+#[cfg(feature = "tokio")]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Leb128PlaceholderU32<const N: usize = 5> {
+    offset: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl<const N: usize> Leb128PlaceholderU32<N> {
+    /// Appends a placeholder encoding `0` to `dst`
+    pub fn reserve(dst: &mut BytesMut) -> Self {
+        let offset = dst.len();
+        let mut buf = [0; N];
+        dst.extend_from_slice(
+            put_u32_leb128_padded(&mut buf, 0).expect("`0` always fits in a padded LEB128"),
+        );
+        Self { offset }
+    }
+
+    /// Offset of the placeholder within the buffer
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Overwrites the placeholder in `dst` with `x`
+    pub fn patch(self, dst: &mut BytesMut, x: u32) -> std::io::Result<()> {
+        let Some(dst) = dst.get_mut(self.offset..self.offset + N) else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "placeholder is out of buffer bounds",
+            ));
+        };
+        let dst: &mut [u8; N] = dst.try_into().expect("slice length must be `N`");
+        if put_u32_leb128_padded(dst, x).is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("value does not fit in {N} LEB128 bytes"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Overwrites the placeholder in `dst` with the number of bytes written after it
+    pub fn patch_len(self, dst: &mut BytesMut) -> std::io::Result<()> {
+        let n = dst.len().checked_sub(self.offset + N).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "placeholder is out of buffer bounds",
+            )
+        })?;
+        let n = u32::try_from(n)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        self.patch(dst, n)
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind};
+
+#[cfg(not(feature = "std"))]
+pub use no_std::*;
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    use ::core::fmt::{self, Display};
+
+    use alloc::boxed::Box;
+
+    /// Kind of an [`Error`], a subset of `std::io::ErrorKind`, which it is an alias for
+    /// if `std` feature is enabled
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[non_exhaustive]
+    pub enum ErrorKind {
+        InvalidData,
+        InvalidInput,
+        UnexpectedEof,
+        WriteZero,
+        Other,
+    }
+
+    impl Display for ErrorKind {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::InvalidData => f.write_str("invalid data"),
+                Self::InvalidInput => f.write_str("invalid input parameter"),
+                Self::UnexpectedEof => f.write_str("unexpected end of file"),
+                Self::WriteZero => f.write_str("write zero"),
+                Self::Other => f.write_str("other error"),
+            }
+        }
+    }
+
+    /// Error returned by buffer and slice codecs, mirroring the API of `std::io::Error`,
+    /// which it is an alias for if `std` feature is enabled
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+        error: Option<Box<dyn ::core::error::Error + Send + Sync>>,
+    }
+
+    impl Error {
+        /// Creates a new error from a known kind of error as well as an arbitrary error payload
+        pub fn new<E>(kind: ErrorKind, error: E) -> Self
+        where
+            E: Into<Box<dyn ::core::error::Error + Send + Sync>>,
+        {
+            Self {
+                kind,
+                error: Some(error.into()),
+            }
+        }
+
+        /// Creates a new error of [`ErrorKind::Other`] kind from an arbitrary error payload
+        pub fn other<E>(error: E) -> Self
+        where
+            E: Into<Box<dyn ::core::error::Error + Send + Sync>>,
+        {
+            Self::new(ErrorKind::Other, error)
+        }
+
+        /// Returns the kind of this error
+        #[must_use]
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+
+        /// Returns a reference to the inner error, if any
+        #[must_use]
+        pub fn get_ref(&self) -> Option<&(dyn ::core::error::Error + Send + Sync + 'static)> {
+            self.error.as_deref()
+        }
+
+        /// Consumes the error, returning the inner error, if any
+        #[must_use]
+        pub fn into_inner(self) -> Option<Box<dyn ::core::error::Error + Send + Sync>> {
+            self.error
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Self {
+            Self { kind, error: None }
+        }
+    }
+
+    impl Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match &self.error {
+                Some(error) => error.fmt(f),
+                None => self.kind.fmt(f),
+            }
+        }
+    }
+
+    impl ::core::error::Error for Error {
+        fn source(&self) -> Option<&(dyn ::core::error::Error + 'static)> {
+            self.error.as_ref().and_then(|error| error.source())
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! LEB128 extensions for [`futures_io`] readers and writers
+
+use ::core::future::{poll_fn, Future};
+use ::core::pin::Pin;
+
+use crate::{
+    check_var_bits, decode_var_i128_leb128, decode_var_i16_leb128, decode_var_i32_leb128,
+    decode_var_i64_leb128, decode_var_i8_leb128, decode_var_u128_leb128, decode_var_u16_leb128,
+    decode_var_u32_leb128, decode_var_u64_leb128, decode_var_u8_leb128, invalid_data,
+    put_i128_leb128, put_i16_leb128, put_i32_leb128, put_i64_leb128, put_i8_leb128,
+    put_u128_leb128, put_u16_leb128, put_u32_leb128, put_u64_leb128, put_u8_leb128,
+    put_zigzag_i128_leb128, put_zigzag_i16_leb128, put_zigzag_i32_leb128, put_zigzag_i64_leb128,
+    put_zigzag_i8_leb128, BufLeb128Ext as _, Overflow, OverflowVar,
+};
+
+/// Reads exactly `buf.len()` bytes from `r`
+#[doc(hidden)]
+pub async fn read_exact<R>(r: &mut R, mut buf: &mut [u8]) -> std::io::Result<()>
+where
+    R: ::futures_io::AsyncRead + Unpin + ?Sized,
+{
+    while !buf.is_empty() {
+        let n = poll_fn(|cx| Pin::new(&mut *r).poll_read(cx, buf)).await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        buf = &mut buf[n..];
+    }
+    Ok(())
+}
+
+/// Writes all of `buf` to `w`
+#[doc(hidden)]
+pub async fn write_all<W>(w: &mut W, mut buf: &[u8]) -> std::io::Result<()>
+where
+    W: ::futures_io::AsyncWrite + Unpin + ?Sized,
+{
+    while !buf.is_empty() {
+        let n = poll_fn(|cx| Pin::new(&mut *w).poll_write(cx, buf)).await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::WriteZero.into());
+        }
+        buf = &buf[n..];
+    }
+    Ok(())
+}
+
+macro_rules! read_leb128 {
+    ($($f:ident, $peek:ident, $t:ty, $bits:literal, $max:literal;)+) => {
+        $(
+            #[doc = concat!("Reads LEB128-encoded [`", stringify!($t), "`]")]
+            #[doc = ""]
+            #[doc = "This method is not cancellation safe, see [`AsyncReadLeb128`](crate::AsyncReadLeb128#cancel-safety)."]
+            fn $f(&mut self) -> impl Future<Output = std::io::Result<$t>>
+            where
+                Self: Unpin,
+            {
+                async move {
+                    let mut buf = [0; $max];
+                    for i in 0..$max {
+                        read_exact(self, &mut buf[i..=i]).await?;
+                        if let Some((x, _)) = (&buf[..=i]).$peek()? {
+                            return Ok(x);
+                        }
+                    }
+                    Err(invalid_data(Overflow::<$bits>))
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! read_var_leb128 {
+    ($($f:ident, $decode:ident, $t:ty, $max:literal;)+) => {
+        $(
+            #[doc = concat!("Reads LEB128-encoded [`", stringify!($t), "`] of at most `n` bits")]
+            #[doc = ""]
+            #[doc = "This method is not cancellation safe, see [`AsyncReadLeb128`](crate::AsyncReadLeb128#cancel-safety)."]
+            fn $f(&mut self, n: u8) -> impl Future<Output = std::io::Result<$t>>
+            where
+                Self: Unpin,
+            {
+                async move {
+                    check_var_bits(n, <$t>::BITS as u8)?;
+                    let mut buf = [0; $max];
+                    for i in 0..$max {
+                        read_exact(self, &mut buf[i..=i]).await?;
+                        if let Some((x, _)) = $decode(&buf[..=i], n)? {
+                            return Ok(x);
+                        }
+                    }
+                    Err(invalid_data(OverflowVar(n)))
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! read_zigzag_leb128 {
+    ($($f:ident, $read:ident, $t:ty;)+) => {
+        $(
+            #[doc = concat!("Reads ZigZag LEB128-encoded [`", stringify!($t), "`]")]
+            #[doc = ""]
+            #[doc = "This method is not cancellation safe, see [`AsyncReadLeb128`](crate::AsyncReadLeb128#cancel-safety)."]
+            fn $f(&mut self) -> impl Future<Output = std::io::Result<$t>>
+            where
+                Self: Unpin,
+            {
+                async move {
+                    let x = self.$read().await?;
+                    Ok((x >> 1) as $t ^ -((x & 1) as $t))
+                }
+            }
+        )+
+    };
+}
+
+/// LEB128 extensions for [`futures_io::AsyncRead`], producing values
+/// identical to [`ReadLeb128`](crate::blocking::ReadLeb128)
+///
+/// The methods of this trait are not cancellation safe, see
+/// [`AsyncReadLeb128`](crate::AsyncReadLeb128#cancel-safety).
+pub trait AsyncReadLeb128: ::futures_io::AsyncRead {
+    read_leb128! {
+        read_u8_leb128, peek_u8_leb128, u8, 8, 2;
+        read_u16_leb128, peek_u16_leb128, u16, 16, 3;
+        read_u32_leb128, peek_u32_leb128, u32, 32, 5;
+        read_u64_leb128, peek_u64_leb128, u64, 64, 10;
+        read_u128_leb128, peek_u128_leb128, u128, 128, 19;
+        read_i8_leb128, peek_i8_leb128, i8, 8, 2;
+        read_i16_leb128, peek_i16_leb128, i16, 16, 3;
+        read_i32_leb128, peek_i32_leb128, i32, 32, 5;
+        read_i64_leb128, peek_i64_leb128, i64, 64, 10;
+        read_i128_leb128, peek_i128_leb128, i128, 128, 19;
+    }
+
+    read_var_leb128! {
+        read_var_u8_leb128, decode_var_u8_leb128, u8, 2;
+        read_var_u16_leb128, decode_var_u16_leb128, u16, 3;
+        read_var_u32_leb128, decode_var_u32_leb128, u32, 5;
+        read_var_u64_leb128, decode_var_u64_leb128, u64, 10;
+        read_var_u128_leb128, decode_var_u128_leb128, u128, 19;
+        read_var_i8_leb128, decode_var_i8_leb128, i8, 2;
+        read_var_i16_leb128, decode_var_i16_leb128, i16, 3;
+        read_var_i32_leb128, decode_var_i32_leb128, i32, 5;
+        read_var_i64_leb128, decode_var_i64_leb128, i64, 10;
+        read_var_i128_leb128, decode_var_i128_leb128, i128, 19;
+    }
+
+    read_zigzag_leb128! {
+        read_zigzag_i8_leb128, read_u8_leb128, i8;
+        read_zigzag_i16_leb128, read_u16_leb128, i16;
+        read_zigzag_i32_leb128, read_u32_leb128, i32;
+        read_zigzag_i64_leb128, read_u64_leb128, i64;
+        read_zigzag_i128_leb128, read_u128_leb128, i128;
+    }
+}
+
+impl<T: ::futures_io::AsyncRead + ?Sized> AsyncReadLeb128 for T {}
+
+macro_rules! write_leb128 {
+    ($($f:ident, $put:ident, $t:ty;)+) => {
+        $(
+            #[doc = concat!("Writes [`", stringify!($t), "`] using [`", stringify!($put), "`]")]
+            fn $f(&mut self, x: $t) -> impl Future<Output = std::io::Result<()>>
+            where
+                Self: Unpin,
+            {
+                async move { write_all(self, $put(&mut Default::default(), x)).await }
+            }
+        )+
+    };
+}
+
+/// LEB128 extensions for [`futures_io::AsyncWrite`], producing bytes
+/// identical to [`WriteLeb128`](crate::blocking::WriteLeb128)
+pub trait AsyncWriteLeb128: ::futures_io::AsyncWrite {
+    write_leb128! {
+        write_u8_leb128, put_u8_leb128, u8;
+        write_u16_leb128, put_u16_leb128, u16;
+        write_u32_leb128, put_u32_leb128, u32;
+        write_u64_leb128, put_u64_leb128, u64;
+        write_u128_leb128, put_u128_leb128, u128;
+        write_i8_leb128, put_i8_leb128, i8;
+        write_i16_leb128, put_i16_leb128, i16;
+        write_i32_leb128, put_i32_leb128, i32;
+        write_i64_leb128, put_i64_leb128, i64;
+        write_i128_leb128, put_i128_leb128, i128;
+        write_zigzag_i8_leb128, put_zigzag_i8_leb128, i8;
+        write_zigzag_i16_leb128, put_zigzag_i16_leb128, i16;
+        write_zigzag_i32_leb128, put_zigzag_i32_leb128, i32;
+        write_zigzag_i64_leb128, put_zigzag_i64_leb128, i64;
+        write_zigzag_i128_leb128, put_zigzag_i128_leb128, i128;
+    }
+}
+
+impl<T: ::futures_io::AsyncWrite + ?Sized> AsyncWriteLeb128 for T {}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn futures_io() {
+        block_on(async {
+            let mut w = Cursor::new(vec![]);
+            w.write_u32_leb128(300).await.expect("failed to write u32");
+            w.write_i64_leb128(-1).await.expect("failed to write i64");
+            w.write_zigzag_i8_leb128(-2)
+                .await
+                .expect("failed to write i8");
+            w.write_u16_leb128(0x1ff)
+                .await
+                .expect("failed to write u16");
+            let buf = w.into_inner();
+
+            let mut expected = vec![];
+            crate::blocking::WriteLeb128::write_u32_leb128(&mut expected, 300)
+                .expect("failed to write u32");
+            crate::blocking::WriteLeb128::write_i64_leb128(&mut expected, -1)
+                .expect("failed to write i64");
+            crate::blocking::WriteLeb128::write_zigzag_i8_leb128(&mut expected, -2)
+                .expect("failed to write i8");
+            crate::blocking::WriteLeb128::write_u16_leb128(&mut expected, 0x1ff)
+                .expect("failed to write u16");
+            assert_eq!(buf, expected);
+
+            let mut r = Cursor::new(buf);
+            assert_eq!(r.read_u32_leb128().await.expect("failed to read u32"), 300);
+            assert_eq!(r.read_i64_leb128().await.expect("failed to read i64"), -1);
+            assert_eq!(
+                r.read_zigzag_i8_leb128().await.expect("failed to read i8"),
+                -2
+            );
+            assert_eq!(
+                r.read_var_u16_leb128(9).await.expect("failed to read u9"),
+                0x1ff
+            );
+            Cursor::new([0x80, 0x80])
+                .read_u32_leb128()
+                .await
+                .expect_err("reading truncated u32 should have failed");
+            Cursor::new([0xff, 0xff, 0xff, 0xff, 0x1f])
+                .read_u32_leb128()
+                .await
+                .expect_err("u32 read should have failed, since it encoded 33 bits");
+        });
+    }
+}
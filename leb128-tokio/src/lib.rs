@@ -1,73 +1,162 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::cast_possible_truncation)]
 
+extern crate alloc;
+
+mod bulk;
+mod codec;
+mod error;
+
+#[cfg(feature = "std")]
+pub mod blocking;
+
+#[cfg(feature = "futures-io")]
+pub mod futures_io;
+
+pub use bulk::*;
+pub use codec::*;
+pub use error::*;
+
 use ::core::fmt::Display;
+#[cfg(feature = "tokio")]
 use ::core::future::Future;
 
-use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
-use tokio_util::bytes::{Buf as _, BytesMut};
-use tokio_util::codec::{Decoder, Encoder};
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+
+use bytes::{Buf, BufMut};
+#[cfg(feature = "tokio")]
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, AsyncWrite,
+    AsyncWriteExt as _,
+};
 
 /// Error returned for overflows decoding statically-sized integers
 #[derive(Debug)]
 pub struct Overflow<const N: usize>;
 
 impl Display for Overflow<8> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
         write!(f, "varint overflows an 8-bit integer")
     }
 }
 
-impl std::error::Error for Overflow<8> {}
+impl ::core::error::Error for Overflow<8> {}
 
 impl Display for Overflow<16> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
         write!(f, "varint overflows a 16-bit integer")
     }
 }
 
-impl std::error::Error for Overflow<16> {}
+impl ::core::error::Error for Overflow<16> {}
 
 impl Display for Overflow<32> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
         write!(f, "varint overflows a 32-bit integer")
     }
 }
 
-impl std::error::Error for Overflow<32> {}
+impl ::core::error::Error for Overflow<32> {}
 
 impl Display for Overflow<64> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
         write!(f, "varint overflows a 64-bit integer")
     }
 }
 
-impl std::error::Error for Overflow<64> {}
+impl ::core::error::Error for Overflow<64> {}
 
 impl Display for Overflow<128> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
         write!(f, "varint overflows a 128-bit integer")
     }
 }
 
-impl std::error::Error for Overflow<128> {}
+impl ::core::error::Error for Overflow<128> {}
 
 /// Error returned for overflows decoding variable size integers
 #[derive(Debug)]
 pub struct OverflowVar(u8);
 
 impl Display for OverflowVar {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
         write!(f, "varint overflows a {}-bit integer", self.0)
     }
 }
 
-impl std::error::Error for OverflowVar {}
+impl ::core::error::Error for OverflowVar {}
+
+fn invalid_data(err: impl Sync + Send + ::core::error::Error + 'static) -> Error {
+    Error::new(ErrorKind::InvalidData, err)
+}
+
+/// Returns an error if `n` is not a valid width of a varint decoded as a `bits`-bit integer
+fn check_var_bits(n: u8, bits: u8) -> Result<(), Error> {
+    if n == 0 || n > bits {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            alloc::format!("invalid varint width `{n}` for a {bits}-bit integer"),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns `true` if `b` is a valid last byte of a signed varint with `r` value bits left.
+/// If `r` is 0, `b` must be the sign extension of the preceding bits, `neg` is the sign bit.
+fn is_signed_tail(b: u8, r: u8, neg: bool) -> bool {
+    if b & 0x80 != 0 {
+        return false;
+    }
+    if r == 0 {
+        return b == if neg { 0x7f } else { 0 };
+    }
+    let hi = b >> (r - 1);
+    hi == 0 || hi == 0x7f >> (r - 1)
+}
 
-fn invalid_data(err: impl Sync + Send + std::error::Error + 'static) -> std::io::Error {
-    std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+#[cfg(feature = "tokio")]
+macro_rules! read_var_leb128 {
+    ($($f:ident, $decode:ident, $t:ty, $ty:literal, $max:literal;)+) => {
+        $(
+            #[doc = concat!("Reads LEB128-encoded [`", stringify!($t), "`] of at most `n` bits")]
+            #[doc = ""]
+            #[doc = "This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety)."]
+            #[cfg_attr(
+                feature = "tracing",
+                tracing::instrument(level = "trace", ret, skip_all, fields(ty = $ty, n))
+            )]
+            fn $f(&mut self, n: u8) -> impl Future<Output = std::io::Result<$t>>
+            where
+                Self: Unpin,
+            {
+                async move {
+                    check_var_bits(n, <$t>::BITS as u8)?;
+                    let mut buf = [0; $max];
+                    for i in 0..$max {
+                        buf[i] = self.read_u8().await?;
+                        if let Some((x, _)) = $decode(&buf[..=i], n)? {
+                            return Ok(x);
+                        }
+                    }
+                    Err(invalid_data(OverflowVar(n)))
+                }
+            }
+        )+
+    };
 }
 
+#[cfg(feature = "tokio")]
+/// LEB128 extensions for [`AsyncRead`]
+///
+/// # Cancel safety
+///
+/// The methods of this trait are not cancellation safe. If a future returned by them is dropped
+/// before completion, bytes read so far are lost and the stream is left desynchronized.
 pub trait AsyncReadLeb128: AsyncRead {
+    /// Reads LEB128-encoded [`u8`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u8"))
@@ -94,6 +183,9 @@ pub trait AsyncReadLeb128: AsyncRead {
         }
     }
 
+    /// Reads LEB128-encoded [`u16`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u16"))
@@ -120,6 +212,9 @@ pub trait AsyncReadLeb128: AsyncRead {
         }
     }
 
+    /// Reads LEB128-encoded [`u32`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u32"))
@@ -146,6 +241,9 @@ pub trait AsyncReadLeb128: AsyncRead {
         }
     }
 
+    /// Reads LEB128-encoded [`u64`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u64"))
@@ -172,6 +270,9 @@ pub trait AsyncReadLeb128: AsyncRead {
         }
     }
 
+    /// Reads LEB128-encoded [`u128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u128"))
@@ -198,175 +299,17 @@ pub trait AsyncReadLeb128: AsyncRead {
         }
     }
 
-    #[cfg_attr(
-        feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "uvarint", n))
-    )]
-    fn read_var_u8_leb128(&mut self, n: u8) -> impl Future<Output = std::io::Result<u8>>
-    where
-        Self: Unpin,
-    {
-        async move {
-            if n == 8 {
-                return self.read_u8_leb128().await;
-            }
-            debug_assert!(n <= 8);
-            let max = (n / 7) + 1;
-            let mut x = 0u8;
-            let mut s = 0u8;
-            for _ in 0..max {
-                let b = self.read_u8().await?;
-                if s == (n / 7) * 7 && b > n % 7 {
-                    return Err(invalid_data(OverflowVar(n)));
-                }
-                x |= (b & 0x7f) << s;
-                if b & 0x80 == 0 {
-                    return Ok(x);
-                }
-                s += 7;
-            }
-            Err(invalid_data(OverflowVar(n)))
-        }
-    }
-
-    #[cfg_attr(
-        feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "uvarint", n))
-    )]
-    fn read_var_u16_leb128(&mut self, n: u8) -> impl Future<Output = std::io::Result<u16>>
-    where
-        Self: Unpin,
-    {
-        async move {
-            match n {
-                8 => return self.read_u8_leb128().await.map(Into::into),
-                16 => return self.read_u16_leb128().await,
-                _ => {}
-            }
-            debug_assert!(n <= 16);
-            let max = (n / 7) + 1;
-            let mut x = 0;
-            let mut s = 0u8;
-            for _ in 0..max {
-                let b = self.read_u8().await?;
-                if s == (n / 7) * 7 && b > n % 7 {
-                    return Err(invalid_data(OverflowVar(n)));
-                }
-                x |= (u16::from(b) & 0x7f) << s;
-                if b & 0x80 == 0 {
-                    return Ok(x);
-                }
-                s += 7;
-            }
-            Err(invalid_data(OverflowVar(n)))
-        }
-    }
-
-    #[cfg_attr(
-        feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "uvarint", n))
-    )]
-    fn read_var_u32_leb128(&mut self, n: u8) -> impl Future<Output = std::io::Result<u32>>
-    where
-        Self: Unpin,
-    {
-        async move {
-            match n {
-                8 => return self.read_u8_leb128().await.map(Into::into),
-                16 => return self.read_u16_leb128().await.map(Into::into),
-                32 => return self.read_u32_leb128().await,
-                _ => {}
-            }
-            debug_assert!(n <= 32);
-            let max = (n / 7) + 1;
-            let mut x = 0;
-            let mut s = 0u8;
-            for _ in 0..max {
-                let b = self.read_u8().await?;
-                if s == (n / 7) * 7 && b > n % 7 {
-                    return Err(invalid_data(OverflowVar(n)));
-                }
-                x |= (u32::from(b) & 0x7f) << s;
-                if b & 0x80 == 0 {
-                    return Ok(x);
-                }
-                s += 7;
-            }
-            Err(invalid_data(OverflowVar(n)))
-        }
-    }
-
-    #[cfg_attr(
-        feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "uvarint", n))
-    )]
-    fn read_var_u64_leb128(&mut self, n: u8) -> impl Future<Output = std::io::Result<u64>>
-    where
-        Self: Unpin,
-    {
-        async move {
-            match n {
-                8 => return self.read_u8_leb128().await.map(Into::into),
-                16 => return self.read_u16_leb128().await.map(Into::into),
-                32 => return self.read_u32_leb128().await.map(Into::into),
-                64 => return self.read_u64_leb128().await,
-                _ => {}
-            }
-            debug_assert!(n <= 64);
-            let max = (n / 7) + 1;
-            let mut x = 0;
-            let mut s = 0u8;
-            for _ in 0..max {
-                let b = self.read_u8().await?;
-                if s == (n / 7) * 7 && b > n % 7 {
-                    return Err(invalid_data(OverflowVar(n)));
-                }
-                x |= (u64::from(b) & 0x7f) << s;
-                if b & 0x80 == 0 {
-                    return Ok(x);
-                }
-                s += 7;
-            }
-            Err(invalid_data(OverflowVar(n)))
-        }
-    }
-
-    #[cfg_attr(
-        feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "uvarint", n))
-    )]
-    fn read_var_u128_leb128(&mut self, n: u8) -> impl Future<Output = std::io::Result<u128>>
-    where
-        Self: Unpin,
-    {
-        async move {
-            match n {
-                8 => return self.read_u8_leb128().await.map(Into::into),
-                16 => return self.read_u16_leb128().await.map(Into::into),
-                32 => return self.read_u32_leb128().await.map(Into::into),
-                64 => return self.read_u64_leb128().await.map(Into::into),
-                128 => return self.read_u128_leb128().await,
-                _ => {}
-            }
-            debug_assert!(n <= 128);
-            let max = (n / 7) + 1;
-            let mut x = 0;
-            let mut s = 0u8;
-            for _ in 0..max {
-                let b = self.read_u8().await?;
-                if s == (n / 7) * 7 && b > n % 7 {
-                    return Err(invalid_data(OverflowVar(n)));
-                }
-                x |= (u128::from(b) & 0x7f) << s;
-                if b & 0x80 == 0 {
-                    return Ok(x);
-                }
-                s += 7;
-            }
-            Err(invalid_data(OverflowVar(n)))
-        }
+    read_var_leb128! {
+        read_var_u8_leb128, decode_var_u8_leb128, u8, "uvarint", 2;
+        read_var_u16_leb128, decode_var_u16_leb128, u16, "uvarint", 3;
+        read_var_u32_leb128, decode_var_u32_leb128, u32, "uvarint", 5;
+        read_var_u64_leb128, decode_var_u64_leb128, u64, "uvarint", 10;
+        read_var_u128_leb128, decode_var_u128_leb128, u128, "uvarint", 19;
     }
 
+    /// Reads LEB128-encoded [`i8`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i8"))
@@ -397,6 +340,9 @@ pub trait AsyncReadLeb128: AsyncRead {
         }
     }
 
+    /// Reads LEB128-encoded [`i16`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i16"))
@@ -427,6 +373,9 @@ pub trait AsyncReadLeb128: AsyncRead {
         }
     }
 
+    /// Reads LEB128-encoded [`i32`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i32"))
@@ -457,6 +406,9 @@ pub trait AsyncReadLeb128: AsyncRead {
         }
     }
 
+    /// Reads LEB128-encoded [`i64`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i64"))
@@ -487,6 +439,9 @@ pub trait AsyncReadLeb128: AsyncRead {
         }
     }
 
+    /// Reads LEB128-encoded [`i128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i128"))
@@ -516,10 +471,331 @@ pub trait AsyncReadLeb128: AsyncRead {
             Err(invalid_data(Overflow::<128>))
         }
     }
+
+    read_var_leb128! {
+        read_var_i8_leb128, decode_var_i8_leb128, i8, "svarint", 2;
+        read_var_i16_leb128, decode_var_i16_leb128, i16, "svarint", 3;
+        read_var_i32_leb128, decode_var_i32_leb128, i32, "svarint", 5;
+        read_var_i64_leb128, decode_var_i64_leb128, i64, "svarint", 10;
+        read_var_i128_leb128, decode_var_i128_leb128, i128, "svarint", 19;
+    }
+
+    /// Reads ZigZag LEB128-encoded [`i8`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i8"))
+    )]
+    fn read_zigzag_i8_leb128(&mut self) -> impl Future<Output = std::io::Result<i8>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let x = self.read_u8_leb128().await?;
+            Ok((x >> 1) as i8 ^ -((x & 1) as i8))
+        }
+    }
+
+    /// Reads ZigZag LEB128-encoded [`i16`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i16"))
+    )]
+    fn read_zigzag_i16_leb128(&mut self) -> impl Future<Output = std::io::Result<i16>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let x = self.read_u16_leb128().await?;
+            Ok((x >> 1) as i16 ^ -((x & 1) as i16))
+        }
+    }
+
+    /// Reads ZigZag LEB128-encoded [`i32`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i32"))
+    )]
+    fn read_zigzag_i32_leb128(&mut self) -> impl Future<Output = std::io::Result<i32>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let x = self.read_u32_leb128().await?;
+            Ok((x >> 1) as i32 ^ -((x & 1) as i32))
+        }
+    }
+
+    /// Reads ZigZag LEB128-encoded [`i64`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i64"))
+    )]
+    fn read_zigzag_i64_leb128(&mut self) -> impl Future<Output = std::io::Result<i64>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let x = self.read_u64_leb128().await?;
+            Ok((x >> 1) as i64 ^ -((x & 1) as i64))
+        }
+    }
+
+    /// Reads ZigZag LEB128-encoded [`i128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i128"))
+    )]
+    fn read_zigzag_i128_leb128(&mut self) -> impl Future<Output = std::io::Result<i128>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let x = self.read_u128_leb128().await?;
+            Ok((x >> 1) as i128 ^ -((x & 1) as i128))
+        }
+    }
 }
 
+#[cfg(feature = "tokio")]
 impl<T: AsyncRead> AsyncReadLeb128 for T {}
 
+#[cfg(feature = "tokio")]
+/// LEB128 reads from [`AsyncBufRead`] decoding values straight out of the internal buffer
+pub trait AsyncBufReadLeb128: AsyncBufRead {
+    /// Reads [`u8`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_u8_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u8"))
+    )]
+    fn read_u8_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<u8>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_u8_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_u8_leb128().await
+        }
+    }
+
+    /// Reads [`u16`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_u16_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u16"))
+    )]
+    fn read_u16_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<u16>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_u16_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_u16_leb128().await
+        }
+    }
+
+    /// Reads [`u32`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_u32_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u32"))
+    )]
+    fn read_u32_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<u32>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_u32_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_u32_leb128().await
+        }
+    }
+
+    /// Reads [`u64`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_u64_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u64"))
+    )]
+    fn read_u64_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<u64>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_u64_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_u64_leb128().await
+        }
+    }
+
+    /// Reads [`u128`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_u128_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u128"))
+    )]
+    fn read_u128_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<u128>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_u128_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_u128_leb128().await
+        }
+    }
+
+    /// Reads [`i8`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_i8_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i8"))
+    )]
+    fn read_i8_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<i8>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_i8_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_i8_leb128().await
+        }
+    }
+
+    /// Reads [`i16`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_i16_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i16"))
+    )]
+    fn read_i16_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<i16>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_i16_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_i16_leb128().await
+        }
+    }
+
+    /// Reads [`i32`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_i32_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i32"))
+    )]
+    fn read_i32_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<i32>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_i32_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_i32_leb128().await
+        }
+    }
+
+    /// Reads [`i64`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_i64_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i64"))
+    )]
+    fn read_i64_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<i64>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_i64_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_i64_leb128().await
+        }
+    }
+
+    /// Reads [`i128`] LEB128 directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadLeb128::read_i128_leb128`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadLeb128`](AsyncReadLeb128#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i128"))
+    )]
+    fn read_i128_leb128_buffered(&mut self) -> impl Future<Output = std::io::Result<i128>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((x, n)) = buf.peek_i128_leb128()? {
+                self.consume(n);
+                return Ok(x);
+            }
+            self.read_i128_leb128().await
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: AsyncBufRead> AsyncBufReadLeb128 for T {}
+
 pub fn put_u8_leb128(buf: &mut [u8; 2], mut x: u8) -> &mut [u8] {
     let mut i = 0;
     while x >= 0x80 {
@@ -575,6 +851,93 @@ pub fn put_u128_leb128(buf: &mut [u8; 19], mut x: u128) -> &mut [u8] {
     &mut buf[..=i]
 }
 
+/// Writes `x` using exactly `N` bytes, padding the encoding with continuation bytes if necessary.
+/// Returns [`None`] if `x` does not fit in `N` bytes.
+pub fn put_u8_leb128_padded<const N: usize>(buf: &mut [u8; N], mut x: u8) -> Option<&mut [u8; N]> {
+    const { assert!(N > 0 && N <= 2) };
+    for b in &mut buf[..N - 1] {
+        *b = x | 0x80;
+        x >>= 7;
+    }
+    if x >= 0x80 {
+        return None;
+    }
+    buf[N - 1] = x;
+    Some(buf)
+}
+
+/// Writes `x` using exactly `N` bytes, padding the encoding with continuation bytes if necessary.
+/// Returns [`None`] if `x` does not fit in `N` bytes.
+pub fn put_u16_leb128_padded<const N: usize>(
+    buf: &mut [u8; N],
+    mut x: u16,
+) -> Option<&mut [u8; N]> {
+    const { assert!(N > 0 && N <= 3) };
+    for b in &mut buf[..N - 1] {
+        *b = (x as u8) | 0x80;
+        x >>= 7;
+    }
+    if x >= 0x80 {
+        return None;
+    }
+    buf[N - 1] = x as u8;
+    Some(buf)
+}
+
+/// Writes `x` using exactly `N` bytes, padding the encoding with continuation bytes if necessary.
+/// Returns [`None`] if `x` does not fit in `N` bytes.
+pub fn put_u32_leb128_padded<const N: usize>(
+    buf: &mut [u8; N],
+    mut x: u32,
+) -> Option<&mut [u8; N]> {
+    const { assert!(N > 0 && N <= 5) };
+    for b in &mut buf[..N - 1] {
+        *b = (x as u8) | 0x80;
+        x >>= 7;
+    }
+    if x >= 0x80 {
+        return None;
+    }
+    buf[N - 1] = x as u8;
+    Some(buf)
+}
+
+/// Writes `x` using exactly `N` bytes, padding the encoding with continuation bytes if necessary.
+/// Returns [`None`] if `x` does not fit in `N` bytes.
+pub fn put_u64_leb128_padded<const N: usize>(
+    buf: &mut [u8; N],
+    mut x: u64,
+) -> Option<&mut [u8; N]> {
+    const { assert!(N > 0 && N <= 10) };
+    for b in &mut buf[..N - 1] {
+        *b = (x as u8) | 0x80;
+        x >>= 7;
+    }
+    if x >= 0x80 {
+        return None;
+    }
+    buf[N - 1] = x as u8;
+    Some(buf)
+}
+
+/// Writes `x` using exactly `N` bytes, padding the encoding with continuation bytes if necessary.
+/// Returns [`None`] if `x` does not fit in `N` bytes.
+pub fn put_u128_leb128_padded<const N: usize>(
+    buf: &mut [u8; N],
+    mut x: u128,
+) -> Option<&mut [u8; N]> {
+    const { assert!(N > 0 && N <= 19) };
+    for b in &mut buf[..N - 1] {
+        *b = (x as u8) | 0x80;
+        x >>= 7;
+    }
+    if x >= 0x80 {
+        return None;
+    }
+    buf[N - 1] = x as u8;
+    Some(buf)
+}
+
 pub fn put_i8_leb128(buf: &mut [u8; 2], mut x: i8) -> &mut [u8] {
     let mut i = 0;
     loop {
@@ -655,6 +1018,279 @@ pub fn put_i128_leb128(buf: &mut [u8; 19], mut x: i128) -> &mut [u8] {
     }
 }
 
+pub fn put_zigzag_i8_leb128(buf: &mut [u8; 2], x: i8) -> &mut [u8] {
+    put_u8_leb128(buf, ((x << 1) ^ (x >> 7)) as u8)
+}
+
+pub fn put_zigzag_i16_leb128(buf: &mut [u8; 3], x: i16) -> &mut [u8] {
+    put_u16_leb128(buf, ((x << 1) ^ (x >> 15)) as u16)
+}
+
+pub fn put_zigzag_i32_leb128(buf: &mut [u8; 5], x: i32) -> &mut [u8] {
+    put_u32_leb128(buf, ((x << 1) ^ (x >> 31)) as u32)
+}
+
+pub fn put_zigzag_i64_leb128(buf: &mut [u8; 10], x: i64) -> &mut [u8] {
+    put_u64_leb128(buf, ((x << 1) ^ (x >> 63)) as u64)
+}
+
+pub fn put_zigzag_i128_leb128(buf: &mut [u8; 19], x: i128) -> &mut [u8] {
+    put_u128_leb128(buf, ((x << 1) ^ (x >> 127)) as u128)
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_u8(x: u8) -> usize {
+    let n = (u8::BITS - x.leading_zeros()) as usize;
+    if n == 0 {
+        1
+    } else {
+        n.div_ceil(7)
+    }
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_u16(x: u16) -> usize {
+    let n = (u16::BITS - x.leading_zeros()) as usize;
+    if n == 0 {
+        1
+    } else {
+        n.div_ceil(7)
+    }
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_u32(x: u32) -> usize {
+    let n = (u32::BITS - x.leading_zeros()) as usize;
+    if n == 0 {
+        1
+    } else {
+        n.div_ceil(7)
+    }
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_u64(x: u64) -> usize {
+    let n = (u64::BITS - x.leading_zeros()) as usize;
+    if n == 0 {
+        1
+    } else {
+        n.div_ceil(7)
+    }
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_u128(x: u128) -> usize {
+    let n = (u128::BITS - x.leading_zeros()) as usize;
+    if n == 0 {
+        1
+    } else {
+        n.div_ceil(7)
+    }
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_i8(x: i8) -> usize {
+    let n = if x < 0 {
+        x.leading_ones()
+    } else {
+        x.leading_zeros()
+    };
+    ((i8::BITS - n) as usize + 1).div_ceil(7)
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_i16(x: i16) -> usize {
+    let n = if x < 0 {
+        x.leading_ones()
+    } else {
+        x.leading_zeros()
+    };
+    ((i16::BITS - n) as usize + 1).div_ceil(7)
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_i32(x: i32) -> usize {
+    let n = if x < 0 {
+        x.leading_ones()
+    } else {
+        x.leading_zeros()
+    };
+    ((i32::BITS - n) as usize + 1).div_ceil(7)
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_i64(x: i64) -> usize {
+    let n = if x < 0 {
+        x.leading_ones()
+    } else {
+        x.leading_zeros()
+    };
+    ((i64::BITS - n) as usize + 1).div_ceil(7)
+}
+
+/// Returns the number of bytes `x` is encoded as using LEB128
+#[must_use]
+pub const fn leb128_len_i128(x: i128) -> usize {
+    let n = if x < 0 {
+        x.leading_ones()
+    } else {
+        x.leading_zeros()
+    };
+    ((i128::BITS - n) as usize + 1).div_ceil(7)
+}
+
+macro_rules! impl_decode_unsigned {
+    ($f:ident, $t:ty, $bits:literal, $max:literal, $s:literal, $b:literal) => {
+        #[doc = concat!("Decodes [`", stringify!($t), "`] LEB128 at the start of `src`.")]
+        #[doc = "Returns the value and its encoded length or [`None`] if more data is needed."]
+        pub fn $f(src: &[u8]) -> Result<Option<($t, usize)>, Overflow<$bits>> {
+            let mut x = 0;
+            let mut s = 0u8;
+            for (i, b) in src.iter().copied().take($max).enumerate() {
+                if s == $s && b > $b {
+                    return Err(Overflow);
+                }
+                x |= (b as $t & 0x7f) << s;
+                if b & 0x80 == 0 {
+                    return Ok(Some((x, i + 1)));
+                }
+                s += 7;
+            }
+            if src.len() < $max {
+                Ok(None)
+            } else {
+                Err(Overflow)
+            }
+        }
+    };
+}
+
+macro_rules! impl_decode_signed {
+    ($f:ident, $t:ty, $bits:literal, $max:literal, $s:literal, $b:literal) => {
+        #[doc = concat!("Decodes [`", stringify!($t), "`] LEB128 at the start of `src`.")]
+        #[doc = "Returns the value and its encoded length or [`None`] if more data is needed."]
+        pub fn $f(src: &[u8]) -> Result<Option<($t, usize)>, Overflow<$bits>> {
+            let mut x = 0;
+            let mut s = 0u8;
+            for (i, b) in src.iter().copied().take($max).enumerate() {
+                if s == $s && b > $b {
+                    return Err(Overflow);
+                }
+                x |= (b as $t & 0x7f) << s;
+                s += 7;
+                if b & 0x80 == 0 {
+                    if s != $s + 7 && b & 0x40 != 0 {
+                        return Ok(Some((x | !0 << s, i + 1)));
+                    } else {
+                        return Ok(Some((x, i + 1)));
+                    }
+                }
+            }
+            if src.len() < $max {
+                Ok(None)
+            } else {
+                Err(Overflow)
+            }
+        }
+    };
+}
+
+impl_decode_unsigned!(decode_u8_leb128, u8, 8, 2, 7, 0x01);
+impl_decode_unsigned!(decode_u16_leb128, u16, 16, 3, 14, 0x03);
+impl_decode_unsigned!(decode_u32_leb128, u32, 32, 5, 28, 0x0f);
+impl_decode_unsigned!(decode_u64_leb128, u64, 64, 10, 63, 0x01);
+impl_decode_unsigned!(decode_u128_leb128, u128, 128, 19, 126, 0x03);
+impl_decode_signed!(decode_i8_leb128, i8, 8, 2, 7, 0x01);
+impl_decode_signed!(decode_i16_leb128, i16, 16, 3, 14, 0x03);
+impl_decode_signed!(decode_i32_leb128, i32, 32, 5, 28, 0x0f);
+impl_decode_signed!(decode_i64_leb128, i64, 64, 10, 63, 0x01);
+impl_decode_signed!(decode_i128_leb128, i128, 128, 19, 126, 0x03);
+
+macro_rules! impl_decode_var_unsigned {
+    ($f:ident, $t:ty, $bits:literal) => {
+        #[doc = concat!("Decodes [`", stringify!($t), "`] LEB128 of at most `n` bits at the start of `src`.")]
+        #[doc = "Returns the value and its encoded length or [`None`] if more data is needed."]
+        #[doc = ""]
+        #[doc = concat!("Fails with [`ErrorKind::InvalidInput`] if `n` is 0 or greater than ", stringify!($bits), ".")]
+        pub fn $f(src: &[u8], n: u8) -> Result<Option<($t, usize)>, Error> {
+            check_var_bits(n, $bits)?;
+            let max = usize::from(n / 7) + 1;
+            let mut x = 0;
+            let mut s = 0u8;
+            for (i, b) in src.iter().copied().take(max).enumerate() {
+                if s == (n / 7) * 7 && b >= 1 << (n % 7) {
+                    return Err(invalid_data(OverflowVar(n)));
+                }
+                x |= (b as $t & 0x7f) << s;
+                if b & 0x80 == 0 {
+                    return Ok(Some((x, i + 1)));
+                }
+                s += 7;
+            }
+            if src.len() < max {
+                Ok(None)
+            } else {
+                Err(invalid_data(OverflowVar(n)))
+            }
+        }
+    };
+}
+
+macro_rules! impl_decode_var_signed {
+    ($f:ident, $t:ty, $bits:literal) => {
+        #[doc = concat!("Decodes [`", stringify!($t), "`] LEB128 of at most `n` bits at the start of `src`.")]
+        #[doc = "Returns the value and its encoded length or [`None`] if more data is needed."]
+        #[doc = ""]
+        #[doc = concat!("Fails with [`ErrorKind::InvalidInput`] if `n` is 0 or greater than ", stringify!($bits), ".")]
+        pub fn $f(src: &[u8], n: u8) -> Result<Option<($t, usize)>, Error> {
+            check_var_bits(n, $bits)?;
+            let max = usize::from(n / 7) + 1;
+            let mut x = 0;
+            let mut s = 0u8;
+            for (i, b) in src.iter().copied().take(max).enumerate() {
+                if s == (n / 7) * 7 && !is_signed_tail(b, n % 7, s > 0 && x >> (s - 1) & 1 != 0) {
+                    return Err(invalid_data(OverflowVar(n)));
+                }
+                x |= (b as $t & 0x7f) << s;
+                s += 7;
+                if b & 0x80 == 0 {
+                    if s < $bits && b & 0x40 != 0 {
+                        return Ok(Some((x | !0 << s, i + 1)));
+                    } else {
+                        return Ok(Some((x, i + 1)));
+                    }
+                }
+            }
+            if src.len() < max {
+                Ok(None)
+            } else {
+                Err(invalid_data(OverflowVar(n)))
+            }
+        }
+    };
+}
+
+impl_decode_var_unsigned!(decode_var_u8_leb128, u8, 8);
+impl_decode_var_unsigned!(decode_var_u16_leb128, u16, 16);
+impl_decode_var_unsigned!(decode_var_u32_leb128, u32, 32);
+impl_decode_var_unsigned!(decode_var_u64_leb128, u64, 64);
+impl_decode_var_unsigned!(decode_var_u128_leb128, u128, 128);
+impl_decode_var_signed!(decode_var_i8_leb128, i8, 8);
+impl_decode_var_signed!(decode_var_i16_leb128, i16, 16);
+impl_decode_var_signed!(decode_var_i32_leb128, i32, 32);
+impl_decode_var_signed!(decode_var_i64_leb128, i64, 64);
+impl_decode_var_signed!(decode_var_i128_leb128, i128, 128);
+
+#[cfg(feature = "tokio")]
 pub trait AsyncWriteLeb128: AsyncWrite {
     #[cfg_attr(
         feature = "tracing",
@@ -674,475 +1310,485 @@ pub trait AsyncWriteLeb128: AsyncWrite {
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u16"))
     )]
-    fn write_u16_leb128(&mut self, x: u16) -> impl Future<Output = std::io::Result<()>>
+    fn write_u16_leb128(&mut self, x: u16) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            self.write_all(put_u16_leb128(&mut Default::default(), x))
+                .await
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u32"))
+    )]
+    fn write_u32_leb128(&mut self, x: u32) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            self.write_all(put_u32_leb128(&mut Default::default(), x))
+                .await
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u64"))
+    )]
+    fn write_u64_leb128(&mut self, x: u64) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            self.write_all(put_u64_leb128(&mut Default::default(), x))
+                .await
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u128"))
+    )]
+    fn write_u128_leb128(&mut self, x: u128) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            self.write_all(put_u128_leb128(&mut Default::default(), x))
+                .await
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i8"))
+    )]
+    fn write_i8_leb128(&mut self, x: i8) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            self.write_all(put_i8_leb128(&mut Default::default(), x))
+                .await
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i16"))
+    )]
+    fn write_i16_leb128(&mut self, x: i16) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            self.write_all(put_u16_leb128(&mut Default::default(), x))
+            self.write_all(put_i16_leb128(&mut Default::default(), x))
                 .await
         }
     }
 
     #[cfg_attr(
         feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u32"))
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i32"))
     )]
-    fn write_u32_leb128(&mut self, x: u32) -> impl Future<Output = std::io::Result<()>>
+    fn write_i32_leb128(&mut self, x: i32) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            self.write_all(put_u32_leb128(&mut Default::default(), x))
+            self.write_all(put_i32_leb128(&mut Default::default(), x))
                 .await
         }
     }
 
     #[cfg_attr(
         feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u64"))
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i64"))
     )]
-    fn write_u64_leb128(&mut self, x: u64) -> impl Future<Output = std::io::Result<()>>
+    fn write_i64_leb128(&mut self, x: i64) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            self.write_all(put_u64_leb128(&mut Default::default(), x))
+            self.write_all(put_i64_leb128(&mut Default::default(), x))
                 .await
         }
     }
 
     #[cfg_attr(
         feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "u128"))
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i128"))
     )]
-    fn write_u128_leb128(&mut self, x: u128) -> impl Future<Output = std::io::Result<()>>
+    fn write_i128_leb128(&mut self, x: i128) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            self.write_all(put_u128_leb128(&mut Default::default(), x))
+            self.write_all(put_i128_leb128(&mut Default::default(), x))
                 .await
         }
     }
 
     #[cfg_attr(
         feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i8"))
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i8"))
     )]
-    fn write_i8_leb128(&mut self, x: i8) -> impl Future<Output = std::io::Result<()>>
+    fn write_zigzag_i8_leb128(&mut self, x: i8) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            self.write_all(put_i8_leb128(&mut Default::default(), x))
+            self.write_all(put_zigzag_i8_leb128(&mut Default::default(), x))
                 .await
         }
     }
 
     #[cfg_attr(
         feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i16"))
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i16"))
     )]
-    fn write_i16_leb128(&mut self, x: i16) -> impl Future<Output = std::io::Result<()>>
+    fn write_zigzag_i16_leb128(&mut self, x: i16) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            self.write_all(put_i16_leb128(&mut Default::default(), x))
+            self.write_all(put_zigzag_i16_leb128(&mut Default::default(), x))
                 .await
         }
     }
 
     #[cfg_attr(
         feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i32"))
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i32"))
     )]
-    fn write_i32_leb128(&mut self, x: i32) -> impl Future<Output = std::io::Result<()>>
+    fn write_zigzag_i32_leb128(&mut self, x: i32) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            self.write_all(put_i32_leb128(&mut Default::default(), x))
+            self.write_all(put_zigzag_i32_leb128(&mut Default::default(), x))
                 .await
         }
     }
 
     #[cfg_attr(
         feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i64"))
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i64"))
     )]
-    fn write_i64_leb128(&mut self, x: i64) -> impl Future<Output = std::io::Result<()>>
+    fn write_zigzag_i64_leb128(&mut self, x: i64) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            self.write_all(put_i64_leb128(&mut Default::default(), x))
+            self.write_all(put_zigzag_i64_leb128(&mut Default::default(), x))
                 .await
         }
     }
 
     #[cfg_attr(
         feature = "tracing",
-        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "i128"))
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "zigzag i128"))
     )]
-    fn write_i128_leb128(&mut self, x: i128) -> impl Future<Output = std::io::Result<()>>
+    fn write_zigzag_i128_leb128(&mut self, x: i128) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            self.write_all(put_i128_leb128(&mut Default::default(), x))
+            self.write_all(put_zigzag_i128_leb128(&mut Default::default(), x))
                 .await
         }
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<T: AsyncWrite> AsyncWriteLeb128 for T {}
 
-pub struct Leb128DecoderU8;
-
-impl Decoder for Leb128DecoderU8 {
-    type Item = u8;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..2 {
-            let Some(b) = src.get(i) else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 7 && *b > 0x01 {
-                return Err(invalid_data(Overflow::<8>));
-            }
-            x |= (b & 0x7f) << s;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                return Ok(Some(x));
-            }
-            s += 7;
+/// Copies up to `N` leading bytes of `src` into `dst` without advancing `src`.
+/// Bytes are collected using [`Buf::chunks_vectored`], so that values spanning several
+/// chunks of e.g. a [`Chain`](bytes::buf::Chain) are visible.
+#[cfg(feature = "std")]
+fn peek<const N: usize>(src: &(impl Buf + ?Sized), dst: &mut [u8; N]) -> usize {
+    let mut chunks = [IoSlice::new(&[]); N];
+    let k = src.chunks_vectored(&mut chunks);
+    let mut n = 0;
+    for chunk in &chunks[..k] {
+        let m = chunk.len().min(N - n);
+        dst[n..n + m].copy_from_slice(&chunk[..m]);
+        n += m;
+        if n == N {
+            break;
         }
-        Err(invalid_data(Overflow::<8>))
     }
+    n
 }
 
-pub struct Leb128DecoderU16;
-
-impl Decoder for Leb128DecoderU16 {
-    type Item = u16;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..3 {
-            let Some(b) = src.get(i) else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 14 && *b > 0x03 {
-                return Err(invalid_data(Overflow::<16>));
-            }
-            x |= (u16::from(*b) & 0x7f) << s;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                return Ok(Some(x));
-            }
-            s += 7;
-        }
-        Err(invalid_data(Overflow::<16>))
-    }
+/// Copies up to `N` leading bytes of `src` into `dst` without advancing `src`.
+/// `Buf::chunks_vectored` is not available without `std`, so only [`Buf::chunk`] is visible.
+#[cfg(not(feature = "std"))]
+fn peek<const N: usize>(src: &(impl Buf + ?Sized), dst: &mut [u8; N]) -> usize {
+    let chunk = src.chunk();
+    let n = chunk.len().min(N);
+    dst[..n].copy_from_slice(&chunk[..n]);
+    n
 }
 
-pub struct Leb128DecoderU32;
-
-impl Decoder for Leb128DecoderU32 {
-    type Item = u32;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..5 {
-            let Some(b) = src.get(i) else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 28 && *b > 0x0f {
-                return Err(invalid_data(Overflow::<32>));
-            }
-            x |= (u32::from(*b) & 0x7f) << s;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                return Ok(Some(x));
-            }
-            s += 7;
+/// Decodes a value of at most `N` bytes at the start of `src` using `decode`, advancing `src`
+/// past it.
+///
+/// If `src` holds more bytes than [`Buf::chunks_vectored`] exposes, as is the case e.g. for a
+/// [`Take`](bytes::buf::Take), the value is read byte-wise instead, but only once at least `N`
+/// bytes remain, so that reading cannot run out of data. Otherwise, [`None`] is returned and
+/// `src` is left untouched.
+fn get<const N: usize, T, E>(
+    src: &mut (impl Buf + ?Sized),
+    decode: impl Fn(&[u8]) -> Result<Option<(T, usize)>, E>,
+) -> Result<Option<T>, Error>
+where
+    E: Sync + Send + ::core::error::Error + 'static,
+{
+    let mut buf = [0; N];
+    let n = peek(src, &mut buf);
+    if let Some((x, k)) = decode(&buf[..n]).map_err(invalid_data)? {
+        src.advance(k);
+        return Ok(Some(x));
+    }
+    if n == N || src.remaining() < N {
+        return Ok(None);
+    }
+    for i in 0..N {
+        buf[i] = src.get_u8();
+        if let Some((x, _)) = decode(&buf[..=i]).map_err(invalid_data)? {
+            return Ok(Some(x));
         }
-        Err(invalid_data(Overflow::<32>))
     }
+    unreachable!("`N` bytes always contain a complete value or fail to decode")
 }
 
-pub struct Leb128DecoderU64;
-
-impl Decoder for Leb128DecoderU64 {
-    type Item = u64;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..10 {
-            let Some(b) = src.get(i) else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 63 && *b > 0x01 {
-                return Err(invalid_data(Overflow::<64>));
-            }
-            x |= (u64::from(*b) & 0x7f) << s;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                return Ok(Some(x));
-            }
-            s += 7;
-        }
-        Err(invalid_data(Overflow::<64>))
+/// LEB128 extensions for [`Buf`].
+///
+/// `peek_*` methods only inspect the bytes exposed by [`Buf::chunks_vectored`], or just
+/// [`Buf::chunk`] without `std`, which may be fewer than [`Buf::remaining`], e.g. for a
+/// [`Take`](bytes::buf::Take). `get_*` methods read such buffers byte-wise, once enough bytes
+/// remain for the longest encoding of the value, and return [`None`] leaving them untouched
+/// otherwise.
+pub trait BufLeb128Ext: Buf {
+    /// Decodes [`u8`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_u8_leb128(&self) -> Result<Option<(u8, usize)>, Error> {
+        let mut buf = [0; 2];
+        let n = peek(self, &mut buf);
+        decode_u8_leb128(&buf[..n]).map_err(invalid_data)
     }
-}
 
-pub struct Leb128DecoderU128;
-
-impl Decoder for Leb128DecoderU128 {
-    type Item = u128;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..19 {
-            let Some(b) = src.get(i) else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 126 && *b > 0x03 {
-                return Err(invalid_data(Overflow::<128>));
-            }
-            x |= (u128::from(*b) & 0x7f) << s;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                return Ok(Some(x));
-            }
-            s += 7;
-        }
-        Err(invalid_data(Overflow::<128>))
+    /// Decodes [`u16`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_u16_leb128(&self) -> Result<Option<(u16, usize)>, Error> {
+        let mut buf = [0; 3];
+        let n = peek(self, &mut buf);
+        decode_u16_leb128(&buf[..n]).map_err(invalid_data)
     }
-}
 
-pub struct Leb128DecoderI8;
-
-impl Decoder for Leb128DecoderI8 {
-    type Item = i8;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..2 {
-            let Some(b) = src.get(i).copied() else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 7 && b > 0x01 {
-                return Err(invalid_data(Overflow::<8>));
-            }
-            x |= ((b as i8) & 0x7f) << s;
-            s += 7;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                if s != 14 && b & 0x40 != 0 {
-                    return Ok(Some(x | !0 << s));
-                } else {
-                    return Ok(Some(x));
-                }
-            }
-        }
-        Err(invalid_data(Overflow::<8>))
+    /// Decodes [`u32`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_u32_leb128(&self) -> Result<Option<(u32, usize)>, Error> {
+        let mut buf = [0; 5];
+        let n = peek(self, &mut buf);
+        decode_u32_leb128(&buf[..n]).map_err(invalid_data)
     }
-}
 
-pub struct Leb128DecoderI16;
-
-impl Decoder for Leb128DecoderI16 {
-    type Item = i16;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..3 {
-            let Some(b) = src.get(i).copied() else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 14 && b > 0x03 {
-                return Err(invalid_data(Overflow::<16>));
-            }
-            x |= (i16::from(b) & 0x7f) << s;
-            s += 7;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                if s != 21 && b & 0x40 != 0 {
-                    return Ok(Some(x | !0 << s));
-                } else {
-                    return Ok(Some(x));
-                }
-            }
-        }
-        Err(invalid_data(Overflow::<16>))
+    /// Decodes [`u64`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_u64_leb128(&self) -> Result<Option<(u64, usize)>, Error> {
+        let mut buf = [0; 10];
+        let n = peek(self, &mut buf);
+        decode_u64_leb128(&buf[..n]).map_err(invalid_data)
     }
-}
 
-pub struct Leb128DecoderI32;
-
-impl Decoder for Leb128DecoderI32 {
-    type Item = i32;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..5 {
-            let Some(b) = src.get(i).copied() else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 28 && b > 0x0f {
-                return Err(invalid_data(Overflow::<32>));
-            }
-            x |= (i32::from(b) & 0x7f) << s;
-            s += 7;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                if s != 35 && b & 0x40 != 0 {
-                    return Ok(Some(x | !0 << s));
-                } else {
-                    return Ok(Some(x));
-                }
-            }
-        }
-        Err(invalid_data(Overflow::<32>))
+    /// Decodes [`u128`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_u128_leb128(&self) -> Result<Option<(u128, usize)>, Error> {
+        let mut buf = [0; 19];
+        let n = peek(self, &mut buf);
+        decode_u128_leb128(&buf[..n]).map_err(invalid_data)
     }
-}
 
-pub struct Leb128DecoderI64;
-
-impl Decoder for Leb128DecoderI64 {
-    type Item = i64;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..10 {
-            let Some(b) = src.get(i).copied() else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 63 && b > 0x01 {
-                return Err(invalid_data(Overflow::<64>));
-            }
-            x |= (i64::from(b) & 0x7f) << s;
-            s += 7;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                if s != 70 && b & 0x40 != 0 {
-                    return Ok(Some(x | !0 << s));
-                } else {
-                    return Ok(Some(x));
-                }
-            }
-        }
-        Err(invalid_data(Overflow::<64>))
+    /// Decodes [`i8`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_i8_leb128(&self) -> Result<Option<(i8, usize)>, Error> {
+        let mut buf = [0; 2];
+        let n = peek(self, &mut buf);
+        decode_i8_leb128(&buf[..n]).map_err(invalid_data)
     }
-}
 
-pub struct Leb128DecoderI128;
-
-impl Decoder for Leb128DecoderI128 {
-    type Item = i128;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let mut x = 0;
-        let mut s = 0u8;
-        for i in 0..19 {
-            let Some(b) = src.get(i).copied() else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if s == 126 && b > 0x03 {
-                return Err(invalid_data(Overflow::<128>));
-            }
-            x |= (i128::from(b) & 0x7f) << s;
-            s += 7;
-            if b & 0x80 == 0 {
-                src.advance(i + 1);
-                if s != 133 && b & 0x40 != 0 {
-                    return Ok(Some(x | !0 << s));
-                } else {
-                    return Ok(Some(x));
-                }
-            }
-        }
-        Err(invalid_data(Overflow::<128>))
+    /// Decodes [`i16`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_i16_leb128(&self) -> Result<Option<(i16, usize)>, Error> {
+        let mut buf = [0; 3];
+        let n = peek(self, &mut buf);
+        decode_i16_leb128(&buf[..n]).map_err(invalid_data)
+    }
+
+    /// Decodes [`i32`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_i32_leb128(&self) -> Result<Option<(i32, usize)>, Error> {
+        let mut buf = [0; 5];
+        let n = peek(self, &mut buf);
+        decode_i32_leb128(&buf[..n]).map_err(invalid_data)
+    }
+
+    /// Decodes [`i64`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_i64_leb128(&self) -> Result<Option<(i64, usize)>, Error> {
+        let mut buf = [0; 10];
+        let n = peek(self, &mut buf);
+        decode_i64_leb128(&buf[..n]).map_err(invalid_data)
+    }
+
+    /// Decodes [`i128`] LEB128 at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_i128_leb128(&self) -> Result<Option<(i128, usize)>, Error> {
+        let mut buf = [0; 19];
+        let n = peek(self, &mut buf);
+        decode_i128_leb128(&buf[..n]).map_err(invalid_data)
+    }
+
+    /// Gets [`u8`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_u8_leb128(&mut self) -> Result<Option<u8>, Error> {
+        get::<2, _, _>(self, decode_u8_leb128)
+    }
+
+    /// Gets [`u16`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_u16_leb128(&mut self) -> Result<Option<u16>, Error> {
+        get::<3, _, _>(self, decode_u16_leb128)
+    }
+
+    /// Gets [`u32`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_u32_leb128(&mut self) -> Result<Option<u32>, Error> {
+        get::<5, _, _>(self, decode_u32_leb128)
+    }
+
+    /// Gets [`u64`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_u64_leb128(&mut self) -> Result<Option<u64>, Error> {
+        get::<10, _, _>(self, decode_u64_leb128)
+    }
+
+    /// Gets [`u128`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_u128_leb128(&mut self) -> Result<Option<u128>, Error> {
+        get::<19, _, _>(self, decode_u128_leb128)
+    }
+
+    /// Gets [`i8`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_i8_leb128(&mut self) -> Result<Option<i8>, Error> {
+        get::<2, _, _>(self, decode_i8_leb128)
+    }
+
+    /// Gets [`i16`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_i16_leb128(&mut self) -> Result<Option<i16>, Error> {
+        get::<3, _, _>(self, decode_i16_leb128)
+    }
+
+    /// Gets [`i32`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_i32_leb128(&mut self) -> Result<Option<i32>, Error> {
+        get::<5, _, _>(self, decode_i32_leb128)
+    }
+
+    /// Gets [`i64`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_i64_leb128(&mut self) -> Result<Option<i64>, Error> {
+        get::<10, _, _>(self, decode_i64_leb128)
+    }
+
+    /// Gets [`i128`] LEB128 from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`BufLeb128Ext`] for buffers exposing only part of the value.
+    fn get_i128_leb128(&mut self) -> Result<Option<i128>, Error> {
+        get::<19, _, _>(self, decode_i128_leb128)
     }
 }
 
-pub struct Leb128Encoder;
+impl<T: Buf + ?Sized> BufLeb128Ext for T {}
 
-macro_rules! impl_encode {
-    ($t:ty, $f:ident) => {
-        impl Encoder<$t> for Leb128Encoder {
-            type Error = std::io::Error;
+/// LEB128 extensions for [`BufMut`]
+pub trait BufMutLeb128Ext: BufMut {
+    /// Puts [`u8`] LEB128 into the buffer
+    fn put_u8_leb128(&mut self, x: u8) {
+        self.put_slice(put_u8_leb128(&mut Default::default(), x));
+    }
 
-            fn encode(&mut self, item: $t, dst: &mut BytesMut) -> Result<(), Self::Error> {
-                dst.extend_from_slice($f(&mut Default::default(), item));
-                Ok(())
-            }
-        }
+    /// Puts [`u16`] LEB128 into the buffer
+    fn put_u16_leb128(&mut self, x: u16) {
+        self.put_slice(put_u16_leb128(&mut Default::default(), x));
+    }
 
-        impl Encoder<&$t> for Leb128Encoder {
-            type Error = std::io::Error;
+    /// Puts [`u32`] LEB128 into the buffer
+    fn put_u32_leb128(&mut self, x: u32) {
+        self.put_slice(put_u32_leb128(&mut Default::default(), x));
+    }
 
-            fn encode(&mut self, item: &$t, dst: &mut BytesMut) -> Result<(), Self::Error> {
-                self.encode(*item, dst)
-            }
-        }
+    /// Puts [`u64`] LEB128 into the buffer
+    fn put_u64_leb128(&mut self, x: u64) {
+        self.put_slice(put_u64_leb128(&mut Default::default(), x));
+    }
 
-        impl Encoder<&&$t> for Leb128Encoder {
-            type Error = std::io::Error;
+    /// Puts [`u128`] LEB128 into the buffer
+    fn put_u128_leb128(&mut self, x: u128) {
+        self.put_slice(put_u128_leb128(&mut Default::default(), x));
+    }
 
-            fn encode(&mut self, item: &&$t, dst: &mut BytesMut) -> Result<(), Self::Error> {
-                self.encode(*item, dst)
-            }
-        }
-    };
+    /// Puts [`i8`] LEB128 into the buffer
+    fn put_i8_leb128(&mut self, x: i8) {
+        self.put_slice(put_i8_leb128(&mut Default::default(), x));
+    }
+
+    /// Puts [`i16`] LEB128 into the buffer
+    fn put_i16_leb128(&mut self, x: i16) {
+        self.put_slice(put_i16_leb128(&mut Default::default(), x));
+    }
+
+    /// Puts [`i32`] LEB128 into the buffer
+    fn put_i32_leb128(&mut self, x: i32) {
+        self.put_slice(put_i32_leb128(&mut Default::default(), x));
+    }
+
+    /// Puts [`i64`] LEB128 into the buffer
+    fn put_i64_leb128(&mut self, x: i64) {
+        self.put_slice(put_i64_leb128(&mut Default::default(), x));
+    }
+
+    /// Puts [`i128`] LEB128 into the buffer
+    fn put_i128_leb128(&mut self, x: i128) {
+        self.put_slice(put_i128_leb128(&mut Default::default(), x));
+    }
 }
 
-impl_encode!(u8, put_u8_leb128);
-impl_encode!(u16, put_u16_leb128);
-impl_encode!(u32, put_u32_leb128);
-impl_encode!(u64, put_u64_leb128);
-impl_encode!(u128, put_u128_leb128);
-impl_encode!(i8, put_i8_leb128);
-impl_encode!(i16, put_i16_leb128);
-impl_encode!(i32, put_i32_leb128);
-impl_encode!(i64, put_i64_leb128);
-impl_encode!(i128, put_i128_leb128);
-
-#[cfg(test)]
+impl<T: BufMut + ?Sized> BufMutLeb128Ext for T {}
+
+#[cfg(all(test, feature = "tokio"))]
 mod tests {
+    use bytes::{Bytes, BytesMut};
+    use tokio::io::BufReader;
+    use tokio_util::codec::{Decoder as _, Encoder as _};
+
     use super::*;
 
     #[tokio::test]
@@ -1444,6 +2090,195 @@ mod tests {
         .expect_err("i128 read should have failed, since it encoded 129 bits");
     }
 
+    #[tokio::test]
+    async fn buffered() {
+        let mut r = BufReader::with_capacity(4, [0xe5, 0x8e, 0x26, 0xc0, 0xbb, 0x78].as_slice());
+        let v = r
+            .read_u32_leb128_buffered()
+            .await
+            .expect("failed to read u32");
+        assert_eq!(v, 624_485);
+        assert_eq!(r.buffer(), [0xc0]);
+
+        let v = r
+            .read_i64_leb128_buffered()
+            .await
+            .expect("failed to read i64");
+        assert_eq!(v, -123_456);
+
+        r.read_u8_leb128_buffered()
+            .await
+            .expect_err("u8 read should have failed, since the reader is empty");
+
+        [0xff, 0xff, 0xff, 0xff, 0x10]
+            .as_slice()
+            .read_u32_leb128_buffered()
+            .await
+            .expect_err("u32 read should have failed, since it encoded 33 bits");
+    }
+
+    #[test]
+    fn buf() {
+        let mut buf = [0xe5, 0x8e, 0x26, 0x7f].as_slice();
+        assert_eq!(buf.peek_u32_leb128().unwrap(), Some((624_485, 3)));
+        assert_eq!(buf.get_u32_leb128().unwrap(), Some(624_485));
+        assert_eq!(buf.get_i64_leb128().unwrap(), Some(-1));
+        assert_eq!(buf.get_u8_leb128().unwrap(), None);
+
+        let mut buf = [0xe5, 0x8e].as_slice();
+        assert_eq!(buf.get_u32_leb128().unwrap(), None);
+        assert_eq!(buf, [0xe5, 0x8e]);
+
+        let mut buf = Buf::chain(
+            [0xe5].as_slice(),
+            Buf::chain([0x8e].as_slice(), [0x26, 0x01].as_slice()),
+        );
+        assert_eq!(buf.get_u32_leb128().unwrap(), Some(624_485));
+        assert_eq!(buf.remaining(), 1);
+
+        let mut buf =
+            Buf::chain([0xe5].as_slice(), [0x8e, 0x26, 0x01, 0x02, 0x03].as_slice()).take(5);
+        assert_eq!(buf.peek_u32_leb128().unwrap(), None);
+        assert_eq!(buf.get_u32_leb128().unwrap(), Some(624_485));
+        assert_eq!(buf.remaining(), 2);
+
+        let mut buf = Buf::chain([0xe5].as_slice(), [0x8e, 0x26].as_slice()).take(3);
+        assert_eq!(buf.get_u32_leb128().unwrap(), None);
+        assert_eq!(buf.remaining(), 3);
+
+        [0xff, 0xff, 0xff, 0xff, 0x10]
+            .as_slice()
+            .get_u32_leb128()
+            .expect_err("u32 get should have failed, since it encoded 33 bits");
+
+        let mut buf = vec![];
+        buf.put_u32_leb128(624_485);
+        buf.put_i32_leb128(-123_456);
+        assert_eq!(buf, [0xe5, 0x8e, 0x26, 0xc0, 0xbb, 0x78]);
+
+        let mut buf = Bytes::from(buf);
+        assert_eq!(buf.get_u64_leb128().unwrap(), Some(624_485));
+        assert_eq!(buf.get_i128_leb128().unwrap(), Some(-123_456));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn len() {
+        for x in [0, 1, 0x7f, 0x80, 0x3fff, 0x4000, u32::MAX] {
+            assert_eq!(
+                leb128_len_u32(x),
+                put_u32_leb128(&mut Default::default(), x).len()
+            );
+        }
+        for x in [0, 1, -1, 63, 64, -64, -65, i64::MAX, i64::MIN] {
+            assert_eq!(
+                leb128_len_i64(x),
+                put_i64_leb128(&mut Default::default(), x).len()
+            );
+        }
+        assert_eq!(leb128_len_u128(u128::MAX), 19);
+        assert_eq!(leb128_len_i8(i8::MIN), 2);
+    }
+
+    #[tokio::test]
+    async fn zigzag() {
+        for (x, encoded) in [
+            (0, [0x00].as_slice()),
+            (-1, &[0x01]),
+            (1, &[0x02]),
+            (-2, &[0x03]),
+            (-64, &[0x7f]),
+            (64, &[0x80, 0x01]),
+            (i32::MAX, &[0xfe, 0xff, 0xff, 0xff, 0x0f]),
+            (i32::MIN, &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+        ] {
+            let mut buf = vec![];
+            buf.write_zigzag_i32_leb128(x)
+                .await
+                .expect("failed to write i32");
+            assert_eq!(buf, encoded);
+
+            let v = { encoded }
+                .read_zigzag_i32_leb128()
+                .await
+                .expect("failed to read i32");
+            assert_eq!(v, x);
+
+            let mut buf = BytesMut::default();
+            ZigZagEncoder
+                .encode(i64::from(x), &mut buf)
+                .expect("failed to encode i64");
+            assert_eq!(buf.as_ref(), encoded);
+
+            let v = ZigZagDecoderI64
+                .decode(&mut buf)
+                .expect("failed to decode i64");
+            assert_eq!(v, Some(i64::from(x)));
+        }
+
+        let v = [0xff, 0x01]
+            .as_slice()
+            .read_zigzag_i8_leb128()
+            .await
+            .expect("failed to read i8");
+        assert_eq!(v, i8::MIN);
+
+        let v = ZigZagDecoderI128
+            .decode(&mut [0xfe, 0xff, 0x03].as_slice().into())
+            .expect("failed to decode i128");
+        assert_eq!(v, Some(0x7fff));
+
+        ZigZagDecoderI16
+            .decode(&mut [0xff, 0xff, 0x04].as_slice().into())
+            .expect_err("i16 decode should have failed, since it encoded 17 bits");
+    }
+
+    #[test]
+    fn padded() {
+        assert_eq!(
+            put_u32_leb128_padded::<5>(&mut Default::default(), 624_485),
+            Some(&mut [0xe5, 0x8e, 0xa6, 0x80, 0x00])
+        );
+        assert_eq!(
+            put_u32_leb128_padded::<5>(&mut Default::default(), u32::MAX),
+            Some(&mut [0xff, 0xff, 0xff, 0xff, 0x0f])
+        );
+        assert_eq!(
+            put_u32_leb128_padded::<2>(&mut Default::default(), 0x3fff),
+            Some(&mut [0xff, 0x7f])
+        );
+        assert_eq!(
+            put_u32_leb128_padded::<2>(&mut Default::default(), 0x4000),
+            None
+        );
+        assert_eq!(
+            put_u8_leb128_padded::<2>(&mut Default::default(), 1),
+            Some(&mut [0x81, 0x00])
+        );
+
+        let mut buf = BytesMut::default();
+        Leb128PaddedEncoder::<5>
+            .encode(624_485u32, &mut buf)
+            .expect("failed to encode u32");
+        assert_eq!(buf.as_ref(), [0xe5, 0x8e, 0xa6, 0x80, 0x00]);
+        let v = Leb128DecoderU32
+            .decode(&mut buf)
+            .expect("failed to decode u32");
+        assert_eq!(v, Some(624_485));
+
+        Leb128PaddedEncoder::<1>
+            .encode(0x80u64, &mut buf)
+            .expect_err("u64 encode should have failed, since it does not fit in 1 byte");
+
+        let mut buf = BytesMut::from(b"\x01".as_slice());
+        let size = Leb128PlaceholderU32::<5>::reserve(&mut buf);
+        assert_eq!(size.offset(), 1);
+        assert_eq!(buf.as_ref(), [0x01, 0x80, 0x80, 0x80, 0x80, 0x00]);
+        buf.extend_from_slice(b"test");
+        size.patch_len(&mut buf).expect("failed to patch length");
+        assert_eq!(buf.as_ref(), b"\x01\x84\x80\x80\x80\x00test");
+    }
+
     #[tokio::test]
     async fn unsigned() {
         let v = [0x01u8]
@@ -1478,5 +2313,220 @@ mod tests {
             .await
             .expect("failed to read u64");
         assert_eq!(v, 0b1000_0000_0000_0000_0000_0000_0000_0000_0000);
+
+        let v = [0x03]
+            .as_slice()
+            .read_var_u8_leb128(2)
+            .await
+            .expect("failed to read u2");
+        assert_eq!(v, 3);
+
+        let v = Leb128VarDecoderU8 { bits: 1 }
+            .decode(&mut [0x01].as_slice().into())
+            .expect("failed to decode u1");
+        assert_eq!(v, Some(1));
+
+        Leb128VarDecoderU8 { bits: 1 }
+            .decode(&mut [0x02].as_slice().into())
+            .expect_err("u1 decode should have failed, since it encoded 2 bits");
+
+        Leb128VarDecoderU16 { bits: 9 }
+            .decode(&mut [0x80, 0x80, 0x01].as_slice().into())
+            .expect_err("u9 decode should have failed, since it used over 9 bits");
+
+        let v = Leb128VarDecoderU32 { bits: 9 }
+            .decode(&mut [0xff].as_slice().into())
+            .expect("failed to decode u9");
+        assert_eq!(v, None);
+
+        let v = Leb128VarDecoderU64 { bits: 64 }
+            .decode(&mut [0x80, 0x80, 0x80, 0x80, 0x80, 0x01].as_slice().into())
+            .expect("failed to decode u64");
+        assert_eq!(v, Some(0b1000_0000_0000_0000_0000_0000_0000_0000_0000));
+    }
+
+    #[tokio::test]
+    async fn signed() {
+        let v = [0x40]
+            .as_slice()
+            .read_var_i64_leb128(33)
+            .await
+            .expect("failed to read s33");
+        assert_eq!(v, -0x40);
+
+        let v = [0xff, 0xff, 0xff, 0xff, 0x0f]
+            .as_slice()
+            .read_var_i64_leb128(33)
+            .await
+            .expect("failed to read s33");
+        assert_eq!(v, (1 << 32) - 1);
+
+        let v = [0x80, 0x80, 0x80, 0x80, 0x70]
+            .as_slice()
+            .read_var_i64_leb128(33)
+            .await
+            .expect("failed to read s33");
+        assert_eq!(v, -(1 << 32));
+
+        [0x80, 0x80, 0x80, 0x80, 0x10]
+            .as_slice()
+            .read_var_i64_leb128(33)
+            .await
+            .expect_err("s33 read should have failed, since it encoded 34 bits");
+
+        let v = [0x80, 0x7f]
+            .as_slice()
+            .read_var_i8_leb128(8)
+            .await
+            .expect("failed to read s8");
+        assert_eq!(v, i8::MIN);
+
+        [0xc0, 0x00]
+            .as_slice()
+            .read_var_i8_leb128(7)
+            .await
+            .expect_err("s7 read should have failed, since it encoded 8 bits");
+
+        let v = [0x01]
+            .as_slice()
+            .read_var_i8_leb128(2)
+            .await
+            .expect("failed to read s2");
+        assert_eq!(v, 1);
+
+        let v = [0x7e]
+            .as_slice()
+            .read_var_i8_leb128(2)
+            .await
+            .expect("failed to read s2");
+        assert_eq!(v, -2);
+
+        [0x02]
+            .as_slice()
+            .read_var_i8_leb128(2)
+            .await
+            .expect_err("s2 read should have failed, since it encoded 3 bits");
+
+        let v = Leb128VarDecoderI64 { bits: 33 }
+            .decode(&mut [0x80, 0x80, 0x80, 0x80, 0x70].as_slice().into())
+            .expect("failed to decode s33");
+        assert_eq!(v, Some(-(1 << 32)));
+
+        Leb128VarDecoderI64 { bits: 33 }
+            .decode(&mut [0x80, 0x80, 0x80, 0x80, 0x20].as_slice().into())
+            .expect_err("s33 decode should have failed, since it encoded 34 bits");
+
+        let v = Leb128VarDecoderI32 { bits: 32 }
+            .decode(&mut [0xc0, 0xbb].as_slice().into())
+            .expect("failed to decode s32");
+        assert_eq!(v, None);
+
+        let v = Leb128VarDecoderI128 { bits: 128 }
+            .decode(&mut [0xc0, 0xbb, 0x78].as_slice().into())
+            .expect("failed to decode s128");
+        assert_eq!(v, Some(-123_456));
+    }
+
+    #[tokio::test]
+    async fn unsigned_max() {
+        let v = [0x03]
+            .as_slice()
+            .read_var_u8_leb128(2)
+            .await
+            .expect("failed to read u2");
+        assert_eq!(v, 3);
+
+        let v = [0xff, 0x03]
+            .as_slice()
+            .read_var_u16_leb128(9)
+            .await
+            .expect("failed to read u9");
+        assert_eq!(v, 0x1ff);
+
+        [0xff, 0x04]
+            .as_slice()
+            .read_var_u16_leb128(9)
+            .await
+            .expect_err("u9 read should have failed, since it encoded 10 bits");
+
+        let v = [0xff, 0xff, 0xff, 0xff, 0x0f]
+            .as_slice()
+            .read_var_u32_leb128(32)
+            .await
+            .expect("failed to read u32");
+        assert_eq!(v, u32::MAX);
+
+        let v = [0xff, 0xff, 0xff, 0x7f]
+            .as_slice()
+            .read_var_u32_leb128(28)
+            .await
+            .expect("failed to read u28");
+        assert_eq!(v, 0x0fff_ffff);
+
+        [0xff, 0xff, 0xff, 0xff, 0x01]
+            .as_slice()
+            .read_var_u32_leb128(28)
+            .await
+            .expect_err("u28 read should have failed, since it encoded 29 bits");
+    }
+
+    #[tokio::test]
+    async fn decode_var() {
+        let tails = [0x00, 0x01, 0x3f, 0x40, 0x7f, 0x80, 0xc0, 0xff];
+        for n in 1..=16 {
+            for a in 0..=u8::MAX {
+                for b in tails {
+                    for c in tails {
+                        let src = [a, b, c];
+                        let mut r = src.as_slice();
+                        match (
+                            decode_var_u16_leb128(&src, n),
+                            r.read_var_u16_leb128(n).await,
+                        ) {
+                            (Ok(Some((x, k))), Ok(y)) => {
+                                assert_eq!(x, y, "u{n} {src:02x?}");
+                                assert_eq!(k, src.len() - r.len(), "u{n} {src:02x?}");
+                            }
+                            (Err(_), Err(err)) => {
+                                assert_eq!(err.kind(), ErrorKind::InvalidData, "u{n} {src:02x?}");
+                            }
+                            (x, y) => panic!("u{n} {src:02x?} mismatch: {x:?} != {y:?}"),
+                        }
+                        let mut r = src.as_slice();
+                        match (
+                            decode_var_i16_leb128(&src, n),
+                            r.read_var_i16_leb128(n).await,
+                        ) {
+                            (Ok(Some((x, k))), Ok(y)) => {
+                                assert_eq!(x, y, "s{n} {src:02x?}");
+                                assert_eq!(k, src.len() - r.len(), "s{n} {src:02x?}");
+                            }
+                            (Err(_), Err(err)) => {
+                                assert_eq!(err.kind(), ErrorKind::InvalidData, "s{n} {src:02x?}");
+                            }
+                            (x, y) => panic!("s{n} {src:02x?} mismatch: {x:?} != {y:?}"),
+                        }
+                    }
+                }
+            }
+        }
+        assert!(matches!(decode_var_u32_leb128(&[0xff, 0xff], 28), Ok(None)));
+        assert!(matches!(decode_var_i8_leb128(&[0x80], 8), Ok(None)));
+
+        for n in [0, 17] {
+            let err = decode_var_u16_leb128(&[0], n).expect_err("invalid width should fail");
+            assert_eq!(err.kind(), ErrorKind::InvalidInput, "u{n}");
+            let err = Leb128VarDecoderI16 { bits: n }
+                .decode(&mut BytesMut::from([0].as_slice()))
+                .expect_err("invalid width should fail");
+            assert_eq!(err.kind(), ErrorKind::InvalidInput, "s{n}");
+            let mut r = [0].as_slice();
+            let err = r
+                .read_var_u16_leb128(n)
+                .await
+                .expect_err("invalid width should fail");
+            assert_eq!(err.kind(), ErrorKind::InvalidInput, "u{n}");
+            assert_eq!(r.len(), 1);
+        }
     }
 }
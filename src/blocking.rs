@@ -0,0 +1,196 @@
+//! Blocking extensions for [`std::io`] readers and writers
+
+pub use leb128_tokio::blocking::*;
+pub use utf8_tokio::blocking::*;
+
+use leb128_tokio::put_u32_leb128;
+
+use crate::cm::BufValueExt as _;
+use crate::READ_CHUNK_LEN;
+
+/// Blocking core value extensions for [`std::io::Read`], producing values identical to
+/// [`AsyncReadCore`](crate::AsyncReadCore)
+pub trait ReadCore: std::io::Read {
+    /// Reads [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names)
+    fn read_core_name(&mut self, s: &mut String) -> std::io::Result<()> {
+        let n = self.read_u32_leb128()?;
+        let n = n.try_into().unwrap_or(usize::MAX);
+        // `n` is untrusted, so grow the buffer in bounded chunks as data arrives
+        let mut buf = Vec::default();
+        while buf.len() < n {
+            let k = buf.len();
+            buf.resize(n.min(k.saturating_add(READ_CHUNK_LEN)), 0);
+            let n = match self.read(&mut buf[k..]) {
+                Ok(n) => n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {
+                    buf.truncate(k);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            buf.truncate(k + n);
+            if n == 0 {
+                break;
+            }
+        }
+        let buf = String::from_utf8(buf)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        s.push_str(&buf);
+        Ok(())
+    }
+}
+
+impl<T: std::io::Read + ?Sized> ReadCore for T {}
+
+/// Blocking core value extensions for [`std::io::Write`], producing bytes identical to
+/// [`AsyncWriteCore`](crate::AsyncWriteCore)
+pub trait WriteCore: std::io::Write {
+    /// Writes [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names)
+    fn write_core_name(&mut self, s: &str) -> std::io::Result<()> {
+        self.write_core_vec_bytes(s.as_bytes())
+    }
+
+    /// Writes [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+    /// of bytes
+    fn write_core_vec_bytes(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        let n = u32::try_from(buf.len())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        self.write_all(put_u32_leb128(&mut Default::default(), n))?;
+        self.write_all(buf)
+    }
+}
+
+impl<T: std::io::Write + ?Sized> WriteCore for T {}
+
+/// Blocking component model value extensions for [`std::io::Read`], producing values identical
+/// to [`AsyncReadValue`](crate::cm::AsyncReadValue)
+pub trait ReadValue: std::io::Read {
+    /// Reads `bool`
+    fn read_bool(&mut self) -> std::io::Result<bool> {
+        let mut b = [0];
+        self.read_exact(&mut b)?;
+        b.as_slice().get_bool().map(Option::unwrap_or_default)
+    }
+
+    /// Reads `option` status, [`true`] if the option is `some`
+    fn read_option_status(&mut self) -> std::io::Result<bool> {
+        let mut b = [0];
+        self.read_exact(&mut b)?;
+        b.as_slice()
+            .get_option_status()
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Reads `result` status, [`true`] if the result is `ok`
+    fn read_result_status(&mut self) -> std::io::Result<bool> {
+        let mut b = [0];
+        self.read_exact(&mut b)?;
+        b.as_slice()
+            .get_result_status()
+            .map(Option::unwrap_or_default)
+    }
+}
+
+impl<T: std::io::Read + ?Sized> ReadValue for T {}
+
+/// Blocking component model value extensions for [`std::io::Write`], producing bytes identical
+/// to [`AsyncWriteValue`](crate::cm::AsyncWriteValue)
+pub trait WriteValue: std::io::Write {
+    /// Writes `bool`
+    fn write_bool(&mut self, v: bool) -> std::io::Result<()> {
+        self.write_all(&[v.into()])
+    }
+
+    /// Writes `option` status
+    fn write_option_status<T>(&mut self, v: Option<T>) -> std::io::Result<()> {
+        self.write_all(&[v.is_some().into()])
+    }
+
+    /// Writes `result` status
+    fn write_result_status<T, E>(&mut self, v: Result<T, E>) -> std::io::Result<()> {
+        self.write_all(&[v.is_err().into()])
+    }
+}
+
+impl<T: std::io::Write + ?Sized> WriteValue for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn read() {
+        let mut buf = vec![];
+        buf.write_core_name("foo").expect("failed to write name");
+        buf.write_u32_leb128(300).expect("failed to write u32");
+        buf.write_char_utf8('ƒ').expect("failed to write char");
+        buf.write_option_status(Some(()))
+            .expect("failed to write option status");
+        buf.write_result_status(Err::<(), ()>(()))
+            .expect("failed to write result status");
+        buf.write_bool(true).expect("failed to write bool");
+
+        let mut r = buf.as_slice();
+        let mut s = String::new();
+        r.read_core_name(&mut s).expect("failed to read name");
+        assert_eq!(s, "foo");
+        assert_eq!(r.read_u32_leb128().expect("failed to read u32"), 300);
+        assert_eq!(r.read_char_utf8().expect("failed to read char"), 'ƒ');
+        assert!(r
+            .read_option_status()
+            .expect("failed to read option status"));
+        assert!(!r
+            .read_result_status()
+            .expect("failed to read result status"));
+        assert!(r.read_bool().expect("failed to read bool"));
+        assert!(r.is_empty());
+        [2].as_slice()
+            .read_bool()
+            .expect_err("reading invalid bool should have failed");
+        [].as_slice()
+            .read_option_status()
+            .expect_err("reading empty option status should have failed");
+
+        let mut s = String::new();
+        b"\xff\xff\xff\xff\x0ffoo"
+            .as_slice()
+            .read_core_name(&mut s)
+            .expect("failed to read truncated name");
+        assert_eq!(s, "foo");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test_log::test(tokio::test)]
+    async fn blocking() {
+        let mut buf = vec![];
+        buf.write_core_name("foo").expect("failed to write name");
+        buf.write_u32_leb128(300).expect("failed to write u32");
+        buf.write_char_utf8('ƒ').expect("failed to write char");
+        buf.write_option_status(Some(()))
+            .expect("failed to write option status");
+        buf.write_result_status(Err::<(), ()>(()))
+            .expect("failed to write result status");
+        buf.write_bool(true).expect("failed to write bool");
+
+        let mut expected = vec![];
+        crate::AsyncWriteCore::write_core_name(&mut expected, "foo")
+            .await
+            .expect("failed to write name");
+        crate::AsyncWriteLeb128::write_u32_leb128(&mut expected, 300)
+            .await
+            .expect("failed to write u32");
+        crate::AsyncWriteUtf8::write_char_utf8(&mut expected, 'ƒ')
+            .await
+            .expect("failed to write char");
+        crate::cm::AsyncWriteValue::write_option_status(&mut expected, Some(()))
+            .await
+            .expect("failed to write option status");
+        crate::cm::AsyncWriteValue::write_result_status(&mut expected, Err::<(), ()>(()))
+            .await
+            .expect("failed to write result status");
+        crate::cm::AsyncWriteValue::write_bool(&mut expected, true)
+            .await
+            .expect("failed to write bool");
+        assert_eq!(buf, expected);
+    }
+}
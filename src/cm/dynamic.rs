@@ -0,0 +1,137 @@
+//! Helpers shared by the dynamically-typed value codecs
+
+use core::fmt::Debug;
+
+use tokio_util::bytes::{Buf as _, BytesMut};
+use tokio_util::codec::Encoder as _;
+
+use crate::cm::U32Codec;
+
+/// Decodes a value using a [`DecodeBorrowed`](crate::DecodeBorrowed) implementation,
+/// returning `Ok(None)` from the enclosing function if more data is needed
+macro_rules! decode {
+    ($dec:expr, $src:expr) => {{
+        let Some((v, n)) = $dec.decode_borrowed($src)? else {
+            return Ok(None);
+        };
+        (v, n)
+    }};
+}
+pub(crate) use decode;
+
+pub(crate) fn type_mismatch(ty: &impl Debug, v: &impl Debug) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("value `{v:?}` does not match type `{ty:?}`"),
+    )
+}
+
+pub(crate) fn unknown_case(name: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("unknown case `{name}`"),
+    )
+}
+
+pub(crate) fn invalid_discriminant(disc: u32) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("invalid discriminant value `{disc}`"),
+    )
+}
+
+pub(crate) fn encode_len(len: usize, dst: &mut BytesMut) -> std::io::Result<()> {
+    let len = u32::try_from(len)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    U32Codec.encode(len, dst)
+}
+
+/// Encodes the discriminant of case `name` and returns its index in `cases`
+pub(crate) fn encode_discriminant(
+    cases: impl IntoIterator<Item = impl AsRef<str>>,
+    name: &str,
+    dst: &mut BytesMut,
+) -> std::io::Result<usize> {
+    let i = cases
+        .into_iter()
+        .position(|case| case.as_ref() == name)
+        .ok_or_else(|| unknown_case(name))?;
+    encode_len(i, dst)?;
+    Ok(i)
+}
+
+/// Encodes an optional variant or result payload using `encode`,
+/// failing if presence of the payload does not match the type
+pub(crate) fn encode_payload<T, V>(
+    ty: Option<&T>,
+    v: Option<&V>,
+    encode: impl FnOnce(&T, &V) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    match (ty, v) {
+        (None, None) => Ok(()),
+        (Some(ty), Some(v)) => encode(ty, v),
+        (Some(_), None) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "missing payload",
+        )),
+        (None, Some(_)) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "unexpected payload",
+        )),
+    }
+}
+
+/// Encodes the set flags `vs` of a flags type with flag names returned by `names`
+pub(crate) fn encode_flags<'a, I>(
+    names: impl Fn() -> I,
+    vs: impl IntoIterator<Item = impl AsRef<str>>,
+    dst: &mut BytesMut,
+) -> std::io::Result<()>
+where
+    I: ExactSizeIterator<Item = &'a str>,
+{
+    let mut buf = vec![0u8; names().len().div_ceil(8)];
+    for v in vs {
+        let v = v.as_ref();
+        let i = names()
+            .position(|name| name == v)
+            .ok_or_else(|| unknown_case(v))?;
+        buf[i / 8] |= 1 << (i % 8);
+    }
+    dst.extend_from_slice(&buf);
+    Ok(())
+}
+
+/// Decodes the names of set flags of a flags type with flag names returned by `names`.
+/// Returns the names and number of bytes consumed or [`None`] if more data is needed.
+pub(crate) fn decode_flags<'a, I>(
+    names: impl Fn() -> I,
+    src: &[u8],
+) -> std::io::Result<Option<(Vec<String>, usize)>>
+where
+    I: ExactSizeIterator<Item = &'a str>,
+{
+    let n = names().len().div_ceil(8);
+    let Some(mut buf) = src.get(..n) else {
+        return Ok(None);
+    };
+    let mut vs = vec![];
+    let mut i = 0;
+    while buf.has_remaining() {
+        let b = buf.get_u8();
+        for j in 0..8 {
+            if b & (1 << j) == 0 {
+                continue;
+            }
+            let name = names().nth(i + j).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("unknown flag bit `{}` set", i + j),
+                )
+            })?;
+            vs.push(name.to_string());
+        }
+        i += 8;
+    }
+    Ok(Some((vs, n)))
+}
@@ -0,0 +1,824 @@
+//! Type-directed JSON mapping for dynamic component model [`Value`]s.
+//!
+//! Values are mapped as follows:
+//!
+//! - `bool`, `string` and integers up to 32 bits map to JSON booleans, strings and numbers
+//! - `s64` and `u64` map to numbers if they can be represented exactly as a double-precision
+//!   float, i.e. their magnitude does not exceed 2^53 - 1, and to decimal strings otherwise.
+//!   Both forms are accepted as input
+//! - `f32` and `f64` map to numbers, NaN and infinities map to `"NaN"`, `"Infinity"` and
+//!   `"-Infinity"` strings
+//! - `char` maps to a single-character string
+//! - `list<u8>` maps to a standard, padded base64 string, other lists map to arrays
+//! - `record` maps to an object, fields of `option` type may be omitted if `none`
+//! - `tuple` maps to an array
+//! - `variant` cases without a payload map to the case name string, cases with a payload map to
+//!   a single-field object `{"<case>": <payload>}`. A payload-less case is also accepted as
+//!   `{"<case>": null}`
+//! - `enum` maps to the case name string
+//! - `flags` map to an array of set flag names
+//! - `option` maps `none` to `null` and `some(v)` to `v`. If the payload is itself an `option`,
+//!   `some(v)` maps to `{"some": v}` instead
+//! - `result` maps to `{"ok": <payload>}` or `{"err": <payload>}`, missing payloads map to `null`
+
+use base64::Engine as _;
+use serde_json::Map;
+use tokio_util::bytes::{Buf as _, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::cm::dynamic::{decode, encode_len, invalid_discriminant, type_mismatch};
+use crate::cm::{BufMutValueExt as _, BufValueExt as _, Type, U32Codec, Value};
+use crate::{
+    decode_core_vec_len, skip_value_len, CoreVecDecoderBytes, CoreVecEncoderBytes,
+    DecodeBorrowed as _, SkipValue,
+};
+
+/// Largest integer magnitude, which can be represented exactly by a JSON number
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+fn float_to_json(v: f64) -> serde_json::Value {
+    if v.is_nan() {
+        "NaN".into()
+    } else if v == f64::INFINITY {
+        "Infinity".into()
+    } else if v == f64::NEG_INFINITY {
+        "-Infinity".into()
+    } else {
+        v.into()
+    }
+}
+
+fn float_from_json(v: &serde_json::Value) -> Option<f64> {
+    match v {
+        serde_json::Value::Number(v) => v.as_f64(),
+        serde_json::Value::String(v) => match v.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn payload_to_json(ty: Option<&Type>, v: Option<&Value>) -> std::io::Result<serde_json::Value> {
+    match (ty, v) {
+        (None, None) => Ok(serde_json::Value::Null),
+        (Some(ty), Some(v)) => to_json(ty, v),
+        (Some(ty), None) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("missing payload of type `{ty:?}`"),
+        )),
+        (None, Some(_)) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "unexpected payload",
+        )),
+    }
+}
+
+/// Returns the payload type and JSON payload `v`, if the payload is present
+fn check_payload<'a, 'b>(
+    ty: Option<&'a Type>,
+    v: &'b serde_json::Value,
+) -> std::io::Result<Option<(&'a Type, &'b serde_json::Value)>> {
+    match (ty, v) {
+        (None, serde_json::Value::Null) => Ok(None),
+        (None, v) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("unexpected payload `{v}`"),
+        )),
+        (Some(ty), v) => Ok(Some((ty, v))),
+    }
+}
+
+fn payload_from_json(
+    ty: Option<&Type>,
+    v: &serde_json::Value,
+) -> std::io::Result<Option<Box<Value>>> {
+    check_payload(ty, v)?
+        .map(|(ty, v)| from_json(ty, v).map(Box::new))
+        .transpose()
+}
+
+/// Returns the single field of a JSON object `v`
+fn single_field(v: &serde_json::Value) -> Option<(&String, &serde_json::Value)> {
+    let v = v.as_object()?;
+    if v.len() != 1 {
+        return None;
+    }
+    v.iter().next()
+}
+
+/// Returns a JSON object with a single field `name` set to `v`
+fn single_field_object(name: &str, v: serde_json::Value) -> serde_json::Value {
+    serde_json::Value::Object(Map::from_iter([(name.into(), v)]))
+}
+
+/// Converts the payload `v` of `some` of type `option<ty>` into JSON
+fn some_to_json(ty: &Type, v: serde_json::Value) -> serde_json::Value {
+    if let Type::Option(..) = ty {
+        single_field_object("some", v)
+    } else {
+        v
+    }
+}
+
+/// Returns the payload of a JSON `option<elem>` of type `ty` or [`None`] if it is `none`
+fn option_from_json<'a>(
+    ty: &Type,
+    elem: &Type,
+    v: &'a serde_json::Value,
+) -> std::io::Result<Option<&'a serde_json::Value>> {
+    if v.is_null() {
+        return Ok(None);
+    }
+    if let Type::Option(..) = elem {
+        match single_field(v) {
+            Some((name, v)) if name == "some" => Ok(Some(v)),
+            _ => Err(type_mismatch(ty, v)),
+        }
+    } else {
+        Ok(Some(v))
+    }
+}
+
+/// Returns whether JSON `result` of type `ty` is `ok` and its payload
+fn result_from_json<'a>(
+    ty: &Type,
+    v: &'a serde_json::Value,
+) -> std::io::Result<(bool, &'a serde_json::Value)> {
+    match single_field(v) {
+        Some((name, v)) if name == "ok" => Ok((true, v)),
+        Some((name, v)) if name == "err" => Ok((false, v)),
+        _ => Err(type_mismatch(ty, v)),
+    }
+}
+
+/// Returns the index of the case of JSON `variant` of type `ty`, its payload type and payload
+fn variant_from_json<'a, 'b>(
+    ty: &Type,
+    cases: &'a [(String, Option<Type>)],
+    v: &'b serde_json::Value,
+) -> std::io::Result<(usize, Option<&'a Type>, &'b serde_json::Value)> {
+    let (name, payload) = match v {
+        serde_json::Value::String(name) => (name, &serde_json::Value::Null),
+        v => single_field(v).ok_or_else(|| type_mismatch(ty, v))?,
+    };
+    let i = cases
+        .iter()
+        .position(|(case, _)| case == name)
+        .ok_or_else(|| type_mismatch(ty, v))?;
+    let case = cases[i].1.as_ref();
+    if case.is_some() && v.is_string() {
+        return Err(type_mismatch(ty, v));
+    }
+    Ok((i, case, payload))
+}
+
+/// Returns JSON `record` `v` of type `ty` as an object, failing on unknown fields
+fn record_from_json<'a>(
+    ty: &Type,
+    fields: &[(String, Type)],
+    v: &'a serde_json::Value,
+) -> std::io::Result<&'a Map<String, serde_json::Value>> {
+    let obj = v.as_object().ok_or_else(|| type_mismatch(ty, v))?;
+    if let Some(name) = obj
+        .keys()
+        .find(|k| !fields.iter().any(|(name, _)| name == *k))
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("unknown record field `{name}`"),
+        ));
+    }
+    Ok(obj)
+}
+
+fn missing_field(name: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("missing record field `{name}`"),
+    )
+}
+
+/// Converts `v` of type `ty` into JSON
+pub fn to_json(ty: &Type, v: &Value) -> std::io::Result<serde_json::Value> {
+    match (ty, v) {
+        (Type::Bool, Value::Bool(v)) => Ok((*v).into()),
+        (Type::S8, Value::S8(v)) => Ok((*v).into()),
+        (Type::U8, Value::U8(v)) => Ok((*v).into()),
+        (Type::S16, Value::S16(v)) => Ok((*v).into()),
+        (Type::U16, Value::U16(v)) => Ok((*v).into()),
+        (Type::S32, Value::S32(v)) => Ok((*v).into()),
+        (Type::U32, Value::U32(v)) => Ok((*v).into()),
+        (Type::S64, Value::S64(v)) if v.unsigned_abs() <= MAX_SAFE_INTEGER => Ok((*v).into()),
+        (Type::S64, Value::S64(v)) => Ok(v.to_string().into()),
+        (Type::U64, Value::U64(v)) if *v <= MAX_SAFE_INTEGER => Ok((*v).into()),
+        (Type::U64, Value::U64(v)) => Ok(v.to_string().into()),
+        // use the shortest decimal representation of `f32`, e.g. `0.1` instead of `0.10000000149011612`
+        (Type::F32, Value::F32(v)) => Ok(float_to_json(
+            v.to_string().parse().unwrap_or_else(|_| f64::from(*v)),
+        )),
+        (Type::F64, Value::F64(v)) => Ok(float_to_json(*v)),
+        (Type::Char, Value::Char(v)) => Ok(v.to_string().into()),
+        (Type::String, Value::String(v)) => Ok(v.as_str().into()),
+        (Type::List(ty), Value::List(vs)) if **ty == Type::U8 => {
+            let buf = vs
+                .iter()
+                .map(|v| match v {
+                    Value::U8(v) => Ok(*v),
+                    _ => Err(type_mismatch(ty, v)),
+                })
+                .collect::<std::io::Result<Vec<_>>>()?;
+            Ok(base64::engine::general_purpose::STANDARD.encode(buf).into())
+        }
+        (Type::List(ty), Value::List(vs)) => vs
+            .iter()
+            .map(|v| to_json(ty, v))
+            .collect::<std::io::Result<_>>()
+            .map(serde_json::Value::Array),
+        (Type::Record(fields), Value::Record(vs)) if fields.len() == vs.len() => {
+            let mut obj = Map::with_capacity(fields.len());
+            for (name, ty) in fields {
+                let (_, v) = vs
+                    .iter()
+                    .find(|(k, _)| k == name)
+                    .ok_or_else(|| missing_field(name))?;
+                obj.insert(name.clone(), to_json(ty, v)?);
+            }
+            Ok(serde_json::Value::Object(obj))
+        }
+        (Type::Tuple(tys), Value::Tuple(vs)) if tys.len() == vs.len() => tys
+            .iter()
+            .zip(vs)
+            .map(|(ty, v)| to_json(ty, v))
+            .collect::<std::io::Result<_>>()
+            .map(serde_json::Value::Array),
+        (Type::Variant(cases), Value::Variant(name, v)) => {
+            let (_, ty) = cases.iter().find(|(case, _)| case == name).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("unknown case `{name}`"),
+                )
+            })?;
+            if ty.is_none() && v.is_none() {
+                return Ok(name.as_str().into());
+            }
+            let v = payload_to_json(ty.as_ref(), v.as_deref())?;
+            Ok(single_field_object(name, v))
+        }
+        (Type::Enum(cases), Value::Enum(name)) if cases.contains(name) => Ok(name.as_str().into()),
+        (Type::Flags(names), Value::Flags(vs)) if vs.iter().all(|v| names.contains(v)) => {
+            Ok(vs.iter().map(String::as_str).collect())
+        }
+        (Type::Option(_), Value::Option(None)) => Ok(serde_json::Value::Null),
+        (Type::Option(ty), Value::Option(Some(v))) => Ok(some_to_json(ty, to_json(ty, v)?)),
+        (Type::Result { ok, err }, Value::Result(v)) => {
+            let (name, v) = match v {
+                Ok(v) => ("ok", payload_to_json(ok.as_deref(), v.as_deref())?),
+                Err(v) => ("err", payload_to_json(err.as_deref(), v.as_deref())?),
+            };
+            Ok(single_field_object(name, v))
+        }
+        _ => Err(type_mismatch(ty, v)),
+    }
+}
+
+macro_rules! int_from_json {
+    ($ty:expr, $v:expr, $variant:ident, $as:ident) => {{
+        let v = $v;
+        let n = match v {
+            serde_json::Value::Number(n) => n.$as(),
+            _ => None,
+        };
+        n.and_then(|n| n.try_into().ok())
+            .map(Value::$variant)
+            .ok_or_else(|| type_mismatch($ty, v))
+    }};
+}
+
+macro_rules! int64_from_json {
+    ($ty:expr, $v:expr, $variant:ident, $as:ident) => {{
+        let v = $v;
+        let n = match v {
+            serde_json::Value::Number(n) => n.$as(),
+            serde_json::Value::String(s) => s.parse().ok(),
+            _ => None,
+        };
+        n.map(Value::$variant).ok_or_else(|| type_mismatch($ty, v))
+    }};
+}
+
+/// Converts JSON `v` into a value of type `ty`
+pub fn from_json(ty: &Type, v: &serde_json::Value) -> std::io::Result<Value> {
+    match ty {
+        Type::Bool => v
+            .as_bool()
+            .map(Value::Bool)
+            .ok_or_else(|| type_mismatch(ty, v)),
+        Type::S8 => int_from_json!(ty, v, S8, as_i64),
+        Type::U8 => int_from_json!(ty, v, U8, as_u64),
+        Type::S16 => int_from_json!(ty, v, S16, as_i64),
+        Type::U16 => int_from_json!(ty, v, U16, as_u64),
+        Type::S32 => int_from_json!(ty, v, S32, as_i64),
+        Type::U32 => int_from_json!(ty, v, U32, as_u64),
+        Type::S64 => int64_from_json!(ty, v, S64, as_i64),
+        Type::U64 => int64_from_json!(ty, v, U64, as_u64),
+        #[allow(clippy::cast_possible_truncation)]
+        Type::F32 => float_from_json(v)
+            // finite values out of `f32` range would otherwise silently become infinite
+            .filter(|v| !v.is_finite() || (*v as f32).is_finite())
+            .map(|v| Value::F32(v as f32))
+            .ok_or_else(|| type_mismatch(ty, v)),
+        Type::F64 => float_from_json(v)
+            .map(Value::F64)
+            .ok_or_else(|| type_mismatch(ty, v)),
+        Type::Char => {
+            let mut chars = v
+                .as_str()
+                .map(str::chars)
+                .ok_or_else(|| type_mismatch(ty, v))?;
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Value::Char(c)),
+                _ => Err(type_mismatch(ty, v)),
+            }
+        }
+        Type::String => v
+            .as_str()
+            .map(|v| Value::String(v.into()))
+            .ok_or_else(|| type_mismatch(ty, v)),
+        Type::List(elem) if **elem == Type::U8 => {
+            let s = v.as_str().ok_or_else(|| type_mismatch(ty, v))?;
+            let buf = base64::engine::general_purpose::STANDARD
+                .decode(s)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+            Ok(Value::List(buf.into_iter().map(Value::U8).collect()))
+        }
+        Type::List(elem) => v
+            .as_array()
+            .ok_or_else(|| type_mismatch(ty, v))?
+            .iter()
+            .map(|v| from_json(elem, v))
+            .collect::<std::io::Result<_>>()
+            .map(Value::List),
+        Type::Record(fields) => {
+            let obj = record_from_json(ty, fields, v)?;
+            fields
+                .iter()
+                .map(|(name, ty)| match (obj.get(name), ty) {
+                    (Some(v), ty) => Ok((name.clone(), from_json(ty, v)?)),
+                    (None, Type::Option(_)) => Ok((name.clone(), Value::Option(None))),
+                    (None, _) => Err(missing_field(name)),
+                })
+                .collect::<std::io::Result<_>>()
+                .map(Value::Record)
+        }
+        Type::Tuple(tys) => match v.as_array() {
+            Some(vs) if vs.len() == tys.len() => tys
+                .iter()
+                .zip(vs)
+                .map(|(ty, v)| from_json(ty, v))
+                .collect::<std::io::Result<_>>()
+                .map(Value::Tuple),
+            _ => Err(type_mismatch(ty, v)),
+        },
+        Type::Variant(cases) => {
+            let (i, case, payload) = variant_from_json(ty, cases, v)?;
+            let payload = payload_from_json(case, payload)?;
+            Ok(Value::Variant(cases[i].0.clone(), payload))
+        }
+        Type::Enum(cases) => v
+            .as_str()
+            .and_then(|name| cases.iter().find(|case| *case == name))
+            .map(|name| Value::Enum(name.clone()))
+            .ok_or_else(|| type_mismatch(ty, v)),
+        Type::Flags(names) => {
+            let vs = v.as_array().ok_or_else(|| type_mismatch(ty, v))?;
+            let mut set = vec![false; names.len()];
+            for v in vs {
+                let i = v
+                    .as_str()
+                    .and_then(|v| names.iter().position(|name| name == v))
+                    .ok_or_else(|| type_mismatch(ty, v))?;
+                set[i] = true;
+            }
+            Ok(Value::Flags(
+                names
+                    .iter()
+                    .zip(set)
+                    .filter(|(_, set)| *set)
+                    .map(|(name, _)| name.clone())
+                    .collect(),
+            ))
+        }
+        Type::Option(elem) => match option_from_json(ty, elem, v)? {
+            None => Ok(Value::Option(None)),
+            Some(v) => from_json(elem, v).map(|v| Value::Option(Some(Box::new(v)))),
+        },
+        Type::Result { ok, err } => match result_from_json(ty, v)? {
+            (true, v) => payload_from_json(ok.as_deref(), v).map(|v| Value::Result(Ok(v))),
+            (false, v) => payload_from_json(err.as_deref(), v).map(|v| Value::Result(Err(v))),
+        },
+    }
+}
+
+/// Like [`encode_json`], but leaves a partially encoded value on error
+fn put_json(ty: &Type, v: &serde_json::Value, dst: &mut BytesMut) -> std::io::Result<()> {
+    match ty {
+        Type::List(elem) if **elem == Type::U8 => {
+            let s = v.as_str().ok_or_else(|| type_mismatch(ty, v))?;
+            let buf = base64::engine::general_purpose::STANDARD
+                .decode(s)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+            CoreVecEncoderBytes.encode(buf, dst)
+        }
+        Type::List(elem) => {
+            let vs = v.as_array().ok_or_else(|| type_mismatch(ty, v))?;
+            encode_len(vs.len(), dst)?;
+            for v in vs {
+                put_json(elem, v, dst)?;
+            }
+            Ok(())
+        }
+        Type::Record(fields) => {
+            let obj = record_from_json(ty, fields, v)?;
+            for (name, ty) in fields {
+                match (obj.get(name), ty) {
+                    (Some(v), ty) => put_json(ty, v, dst)?,
+                    (None, Type::Option(_)) => {
+                        dst.reserve(1);
+                        dst.put_option_status(None::<()>);
+                    }
+                    (None, _) => return Err(missing_field(name)),
+                }
+            }
+            Ok(())
+        }
+        Type::Tuple(tys) => match v.as_array() {
+            Some(vs) if vs.len() == tys.len() => {
+                for (ty, v) in tys.iter().zip(vs) {
+                    put_json(ty, v, dst)?;
+                }
+                Ok(())
+            }
+            _ => Err(type_mismatch(ty, v)),
+        },
+        Type::Variant(cases) => {
+            let (i, case, payload) = variant_from_json(ty, cases, v)?;
+            let payload = check_payload(case, payload)?;
+            encode_len(i, dst)?;
+            if let Some((ty, v)) = payload {
+                put_json(ty, v, dst)?;
+            }
+            Ok(())
+        }
+        Type::Option(elem) => {
+            let v = option_from_json(ty, elem, v)?;
+            dst.reserve(1);
+            dst.put_option_status(v);
+            if let Some(v) = v {
+                put_json(elem, v, dst)?;
+            }
+            Ok(())
+        }
+        Type::Result { ok, err } => {
+            let (is_ok, v) = result_from_json(ty, v)?;
+            let payload = if is_ok {
+                check_payload(ok.as_deref(), v)?
+            } else {
+                check_payload(err.as_deref(), v)?
+            };
+            dst.reserve(1);
+            dst.put_result_status(if is_ok { Ok(()) } else { Err(()) });
+            if let Some((ty, v)) = payload {
+                put_json(ty, v, dst)?;
+            }
+            Ok(())
+        }
+        _ => from_json(ty, v)?.encode(ty, dst),
+    }
+}
+
+/// Encodes JSON `v` as a value of type `ty`.
+///
+/// Aggregates are encoded directly, without converting `v` into a [`Value`] first.
+/// On error, `dst` is left untouched.
+pub fn encode_json(ty: &Type, v: &serde_json::Value, dst: &mut BytesMut) -> std::io::Result<()> {
+    let start = dst.len();
+    if let Err(err) = put_json(ty, v, dst) {
+        dst.truncate(start);
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Decodes a value of type `ty` from `src` as JSON.
+/// Returns the JSON value and number of bytes consumed or [`None`] if more data is needed.
+///
+/// Aggregates are decoded directly, without materialising a [`Value`] first.
+pub fn decode_json(ty: &Type, src: &[u8]) -> std::io::Result<Option<(serde_json::Value, usize)>> {
+    macro_rules! decode_nested {
+        ($ty:expr, $src:expr) => {{
+            let Some((v, n)) = decode_json($ty, $src)? else {
+                return Ok(None);
+            };
+            (v, n)
+        }};
+    }
+
+    let (v, n) = match ty {
+        Type::List(elem) if **elem == Type::U8 => {
+            let (buf, n) = decode!(CoreVecDecoderBytes::default(), src);
+            (
+                base64::engine::general_purpose::STANDARD.encode(buf).into(),
+                n,
+            )
+        }
+        Type::List(elem) => {
+            let Some((len, mut n)) = decode_core_vec_len(src)? else {
+                return Ok(None);
+            };
+            let mut vs = Vec::with_capacity(len.min(src.len() - n));
+            for _ in 0..len {
+                let (v, k) = decode_nested!(elem, &src[n..]);
+                vs.push(v);
+                n += k;
+            }
+            (serde_json::Value::Array(vs), n)
+        }
+        Type::Record(fields) => {
+            let mut n = 0;
+            let mut obj = Map::with_capacity(fields.len());
+            for (name, ty) in fields {
+                let (v, k) = decode_nested!(ty, &src[n..]);
+                obj.insert(name.clone(), v);
+                n += k;
+            }
+            (serde_json::Value::Object(obj), n)
+        }
+        Type::Tuple(tys) => {
+            let mut n = 0;
+            let mut vs = Vec::with_capacity(tys.len());
+            for ty in tys {
+                let (v, k) = decode_nested!(ty, &src[n..]);
+                vs.push(v);
+                n += k;
+            }
+            (serde_json::Value::Array(vs), n)
+        }
+        Type::Variant(cases) => {
+            let (disc, n) = decode!(U32Codec, src);
+            let (name, ty) = usize::try_from(disc)
+                .ok()
+                .and_then(|i| cases.get(i))
+                .ok_or_else(|| invalid_discriminant(disc))?;
+            if let Some(ty) = ty {
+                let (v, k) = decode_nested!(ty, &src[n..]);
+                (single_field_object(name, v), n + k)
+            } else {
+                (name.as_str().into(), n)
+            }
+        }
+        Type::Option(elem) => {
+            let Some(is_some) = { src }.get_option_status()? else {
+                return Ok(None);
+            };
+            if is_some {
+                let (v, n) = decode_nested!(elem, &src[1..]);
+                (some_to_json(elem, v), n + 1)
+            } else {
+                (serde_json::Value::Null, 1)
+            }
+        }
+        Type::Result { ok, err } => {
+            let Some(is_ok) = { src }.get_result_status()? else {
+                return Ok(None);
+            };
+            let (name, ty) = if is_ok { ("ok", ok) } else { ("err", err) };
+            let (v, n) = if let Some(ty) = ty {
+                decode_nested!(ty, &src[1..])
+            } else {
+                (serde_json::Value::Null, 0)
+            };
+            (single_field_object(name, v), n + 1)
+        }
+        _ => {
+            let (v, n) = decode!(ty, src);
+            (to_json(ty, &v)?, n)
+        }
+    };
+    Ok(Some((v, n)))
+}
+
+/// Codec for JSON values encoded as a particular [`Type`]
+///
+/// Decoding scans `src` without materialising anything until a complete value is buffered.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JsonCodec {
+    ty: Type,
+    need: usize,
+}
+
+impl JsonCodec {
+    /// Constructs a new codec for JSON values encoded as `ty`
+    pub fn new(ty: Type) -> Self {
+        Self { ty, need: 0 }
+    }
+
+    /// Returns the type JSON values are encoded as
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /// Returns the type JSON values are encoded as, consuming the codec
+    pub fn into_inner(self) -> Type {
+        self.ty
+    }
+}
+
+impl Encoder<&serde_json::Value> for JsonCodec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: &serde_json::Value, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        encode_json(&self.ty, item, dst)
+    }
+}
+
+impl Encoder<serde_json::Value> for JsonCodec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: serde_json::Value, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        encode_json(&self.ty, &item, dst)
+    }
+}
+
+impl Decoder for JsonCodec {
+    type Item = serde_json::Value;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(n) = skip_value_len(&self.ty, &mut self.need, src)? else {
+            return Ok(None);
+        };
+        let Some((v, _)) = decode_json(&self.ty, &src[..n])? else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "value shorter than its scanned length",
+            ));
+        };
+        src.advance(n);
+        Ok(Some(v))
+    }
+}
+
+impl SkipValue for JsonCodec {
+    fn value_len(&self, src: &[u8]) -> std::io::Result<Option<usize>> {
+        self.ty.value_len(src)
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        self.ty.min_value_len(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test_log::test]
+    fn json() {
+        let ty = Type::Record(vec![
+            ("id".into(), Type::U64),
+            ("offset".into(), Type::S64),
+            ("ratio".into(), Type::F32),
+            ("limit".into(), Type::F64),
+            ("data".into(), Type::List(Box::new(Type::U8))),
+            ("initial".into(), Type::Char),
+            ("pos".into(), Type::Tuple(vec![Type::S16, Type::S16])),
+            (
+                "shape".into(),
+                Type::List(Box::new(Type::Variant(vec![
+                    ("empty".into(), None),
+                    ("circle".into(), Some(Type::U32)),
+                ]))),
+            ),
+            (
+                "color".into(),
+                Type::Enum(vec!["red".into(), "green".into()]),
+            ),
+            (
+                "perms".into(),
+                Type::Flags(vec!["read".into(), "write".into(), "exec".into()]),
+            ),
+            (
+                "parent".into(),
+                Type::Option(Box::new(Type::Option(Box::new(Type::String)))),
+            ),
+            ("comment".into(), Type::Option(Box::new(Type::String))),
+            (
+                "status".into(),
+                Type::Result {
+                    ok: None,
+                    err: Some(Box::new(Type::String)),
+                },
+            ),
+        ]);
+        let v = json!({
+            "id": u64::MAX,
+            "offset": -42,
+            "ratio": 0.1,
+            "limit": "Infinity",
+            "data": "AQID",
+            "initial": "ß",
+            "pos": [1, -1],
+            "shape": ["empty", {"circle": 3}, {"empty": null}],
+            "color": "green",
+            "perms": ["exec", "read"],
+            "parent": {"some": null},
+            "status": {"ok": null},
+        });
+        let mut buf = BytesMut::default();
+        JsonCodec::new(ty.clone())
+            .encode(&v, &mut buf)
+            .expect("failed to encode JSON value");
+        let mut expected = vec![0xff; 9];
+        expected.extend_from_slice(b"\x01\x56");
+        expected.extend_from_slice(&0.1f32.to_le_bytes());
+        expected.extend_from_slice(&f64::INFINITY.to_le_bytes());
+        expected.extend_from_slice(b"\x03\x01\x02\x03\xc3\x9f\x01\x7f");
+        expected.extend_from_slice(b"\x03\x00\x01\x03\x00\x01\x05\x01\x00\x00\x00");
+        assert_eq!(buf.as_ref(), expected);
+
+        let decoded = JsonCodec::new(ty.clone())
+            .decode(&mut buf)
+            .expect("failed to decode JSON value");
+        assert!(buf.is_empty());
+        assert_eq!(
+            decoded,
+            Some(json!({
+                "id": "18446744073709551615",
+                "offset": -42,
+                "ratio": 0.1,
+                "limit": "Infinity",
+                "data": "AQID",
+                "initial": "ß",
+                "pos": [1, -1],
+                "shape": ["empty", {"circle": 3}, "empty"],
+                "color": "green",
+                "perms": ["read", "exec"],
+                "parent": {"some": null},
+                "comment": null,
+                "status": {"ok": null},
+            }))
+        );
+
+        let mut buf = BytesMut::default();
+        encode_json(&ty, &v, &mut buf).expect("failed to encode JSON value");
+        let (value, n) = ty
+            .decode_borrowed(&buf)
+            .expect("failed to decode value")
+            .expect("short value read");
+        assert_eq!(n, buf.len());
+        assert_eq!(
+            to_json(&ty, &value).expect("failed to convert value"),
+            decoded.expect("failed to decode JSON value")
+        );
+
+        let ty = Type::List(Box::new(Type::U8));
+        let data = base64::engine::general_purpose::STANDARD.encode([0xab; 1000]);
+        let mut buf = BytesMut::from(b"\xff".as_slice());
+        encode_json(&ty, &json!([1, 2]), &mut buf)
+            .expect_err("encode should have failed, since `list<u8>` is a base64 string");
+        assert_eq!(buf.as_ref(), b"\xff");
+        encode_json(&ty, &json!(data), &mut buf).expect("failed to encode bytes");
+        let mut dec = JsonCodec::new(ty);
+        let mut src = BytesMut::default();
+        for chunk in buf[1..].chunks(100) {
+            assert_eq!(dec.decode(&mut src).expect("failed to decode bytes"), None);
+            src.extend_from_slice(chunk);
+        }
+        assert_eq!(
+            dec.decode(&mut src).expect("failed to decode bytes"),
+            Some(json!(data))
+        );
+        assert!(src.is_empty());
+
+        from_json(&Type::U8, &json!(256))
+            .expect_err("conversion should have failed, since 256 overflows u8");
+        from_json(&Type::F32, &json!(1e39))
+            .expect_err("conversion should have failed, since 1e39 overflows f32");
+        from_json(&Type::U32, &json!("1"))
+            .expect_err("conversion should have failed, since 32-bit integers are numbers");
+        from_json(&Type::Char, &json!("ab"))
+            .expect_err("conversion should have failed, since `ab` is not a char");
+        from_json(
+            &Type::Variant(vec![("a".into(), Some(Type::U8))]),
+            &json!("a"),
+        )
+        .expect_err("conversion should have failed, since the payload is missing");
+        from_json(&Type::Record(vec![]), &json!({"a": 1}))
+            .expect_err("conversion should have failed, since `a` is not a field");
+    }
+}
@@ -1,4 +1,20 @@
 //! [Component model](https://component-model.bytecodealliance.org/) codec
 
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "wasmtime")]
+pub mod wasmtime;
+#[cfg(feature = "tokio")]
+pub mod wave;
+
+#[cfg(feature = "tokio")]
+mod dynamic;
+#[cfg(feature = "tokio")]
+mod value;
 mod values;
+
+#[cfg(feature = "tokio")]
+pub use value::*;
 pub use values::*;
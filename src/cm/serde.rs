@@ -0,0 +1,1019 @@
+//! [`serde`] data model mapping onto the component model value encoding
+//!
+//! The mapping is type-directed and produces output byte-identical to the corresponding
+//! hand-composed codecs:
+//!
+//! | serde                                    | component model                  |
+//! |------------------------------------------|----------------------------------|
+//! | `bool`, integers up to 64 bits, floats   | `bool`, `s*`, `u*`, `f32`, `f64` |
+//! | `char`, `str`                            | `char`, `string`                 |
+//! | bytes                                    | `list<u8>`                       |
+//! | `Option`                                 | `option`                         |
+//! | unit, unit struct                        | empty `tuple`                    |
+//! | newtype struct                           | the wrapped value                |
+//! | sequence                                 | `list`                           |
+//! | map                                      | `list<tuple<K, V>>`              |
+//! | tuple, tuple struct, struct              | `tuple`, `record`                |
+//! | enum                                     | `variant` (or `enum`)            |
+//!
+//! The encoding is not self-describing, therefore [`Deserializer`] does not support
+//! [`serde::Deserializer::deserialize_any`] and [`serde::Deserializer::deserialize_ignored_any`].
+
+use ::core::fmt::{self, Display};
+use ::core::marker::PhantomData;
+
+use ::serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use ::serde::ser::{
+    self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+};
+use leb128_tokio::Leb128Encoder;
+use tokio_util::bytes::{Buf as _, BufMut as _, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+use utf8_tokio::Utf8Codec;
+
+use crate::cm::{
+    BoolCodec, F32Codec, F64Codec, S16Codec, S32Codec, S64Codec, S8Codec, U16Codec, U32Codec,
+    U64Codec, U8Codec,
+};
+use crate::{CoreNameDecoder, CoreNameEncoder, CoreVecDecoderBytes, CoreVecEncoderBytes};
+use crate::{DecodeBorrowed, SkipValue};
+
+/// Error returned by [`Serializer`] and [`Deserializer`]
+#[derive(Debug)]
+pub struct Error(std::io::Error);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            msg.to_string(),
+        ))
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            msg.to_string(),
+        ))
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self(err)
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(Error(err): Error) -> Self {
+        err
+    }
+}
+
+/// Serializes `value` into `dst`, leaving `dst` unchanged on error
+pub fn serialize<T: Serialize + ?Sized>(value: &T, dst: &mut BytesMut) -> std::io::Result<()> {
+    let n = dst.len();
+    if let Err(err) = value.serialize(&mut Serializer::new(dst)) {
+        dst.truncate(n);
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// Deserializes a value at the start of `src`.
+/// Returns the value and its encoded length.
+///
+/// Fails with [`std::io::ErrorKind::UnexpectedEof`] if `src` is too short.
+pub fn deserialize<'de, T: de::Deserialize<'de>>(src: &'de [u8]) -> std::io::Result<(T, usize)> {
+    let mut de = Deserializer::new(src);
+    let v = T::deserialize(&mut de)?;
+    Ok((v, src.len() - de.src.len()))
+}
+
+/// [`serde`] serializer producing the component model value encoding
+#[derive(Debug)]
+pub struct Serializer<'a> {
+    dst: &'a mut BytesMut,
+}
+
+impl<'a> Serializer<'a> {
+    pub fn new(dst: &'a mut BytesMut) -> Self {
+        Self { dst }
+    }
+
+    fn encode<E: Encoder<T, Error = std::io::Error>, T>(
+        &mut self,
+        mut enc: E,
+        v: T,
+    ) -> Result<(), Error> {
+        enc.encode(v, self.dst)?;
+        Ok(())
+    }
+
+    fn encode_discriminant(&mut self, variant_index: u32) -> Result<(), Error> {
+        self.encode(Leb128Encoder, variant_index)
+    }
+}
+
+impl<'a, 'b> ser::Serializer for &'a mut Serializer<'b> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = ListSerializer<'a, 'b>;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = ListSerializer<'a, 'b>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.encode(BoolCodec, v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.encode(S8Codec, v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.encode(S16Codec, v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.encode(S32Codec, v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.encode(S64Codec, v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.encode(U8Codec, v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.encode(U16Codec, v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.encode(U32Codec, v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.encode(U64Codec, v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.encode(F32Codec, v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.encode(F64Codec, v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.encode(Utf8Codec, v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.encode(CoreNameEncoder, v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.encode(CoreVecEncoderBytes, v)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.dst.put_u8(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        self.dst.put_u8(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+    ) -> Result<(), Error> {
+        self.encode_discriminant(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.encode_discriminant(variant_index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        ListSerializer::new(self, len)
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, Error> {
+        self.encode_discriminant(variant_index)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        ListSerializer::new(self, len)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self, Error> {
+        self.encode_discriminant(variant_index)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl SerializeTuple for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl SerializeTupleStruct for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl SerializeTupleVariant for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl SerializeStruct for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl SerializeStructVariant for &mut Serializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Serializer for sequences and maps, which are encoded as `list`.
+///
+/// If the length is not known in advance, elements are buffered until the end of the list.
+#[derive(Debug)]
+pub struct ListSerializer<'a, 'b> {
+    ser: &'a mut Serializer<'b>,
+    buf: Option<BytesMut>,
+    len: u32,
+}
+
+impl<'a, 'b> ListSerializer<'a, 'b> {
+    fn new(ser: &'a mut Serializer<'b>, len: Option<usize>) -> Result<Self, Error> {
+        if let Some(len) = len {
+            let len = u32::try_from(len)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+            ser.encode(Leb128Encoder, len)?;
+            Ok(Self {
+                ser,
+                buf: None,
+                len,
+            })
+        } else {
+            Ok(Self {
+                ser,
+                buf: Some(BytesMut::default()),
+                len: 0,
+            })
+        }
+    }
+
+    fn serialize<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        if let Some(buf) = self.buf.as_mut() {
+            value.serialize(&mut Serializer::new(buf))
+        } else {
+            value.serialize(&mut *self.ser)
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        if self.buf.is_some() {
+            self.len = self.len.checked_add(1).ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "list length overflow")
+            })?;
+        }
+        self.serialize(value)
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        if let Some(buf) = self.buf {
+            self.ser.encode(Leb128Encoder, self.len)?;
+            self.ser.dst.extend_from_slice(&buf);
+        }
+        Ok(())
+    }
+}
+
+impl SerializeSeq for ListSerializer<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl SerializeMap for ListSerializer<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.push(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.serialize(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+/// [`serde`] deserializer reading the component model value encoding from a byte slice
+#[derive(Debug)]
+pub struct Deserializer<'de> {
+    src: &'de [u8],
+    len: usize,
+    scan: bool,
+    need: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn new(src: &'de [u8]) -> Self {
+        Self {
+            src,
+            len: src.len(),
+            scan: false,
+            need: 0,
+        }
+    }
+
+    /// Returns a deserializer, which only validates the structure of values and passes
+    /// empty strings and byte slices to visitors instead of copying them
+    fn scan(src: &'de [u8]) -> Self {
+        Self {
+            scan: true,
+            ..Self::new(src)
+        }
+    }
+
+    /// Returns the remaining, not yet deserialized input
+    pub fn remaining(&self) -> &'de [u8] {
+        self.src
+    }
+
+    /// Records the lower bound of the input length after reaching its end
+    fn unexpected_eof(&mut self, min_len: usize) -> Error {
+        self.need = self.len - self.src.len() + min_len;
+        std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()
+    }
+
+    fn decode<D: DecodeBorrowed + SkipValue>(&mut self, dec: D) -> Result<D::Value<'de>, Error> {
+        let Some((v, n)) = dec.decode_borrowed(self.src)? else {
+            return Err(self.unexpected_eof(dec.min_value_len(self.src)));
+        };
+        self.src = &self.src[n..];
+        Ok(v)
+    }
+
+    fn decode_len(&mut self) -> Result<usize, Error> {
+        let len = self.decode(U32Codec)?;
+        let len = len
+            .try_into()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        Ok(len)
+    }
+
+    fn decode_status(&mut self) -> Result<bool, Error> {
+        let Some((&b, rest)) = self.src.split_first() else {
+            return Err(self.unexpected_eof(1));
+        };
+        self.src = rest;
+        match b {
+            0 => Ok(false),
+            1 => Ok(true),
+            n => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid option status byte value `{n}`"),
+            )
+            .into()),
+        }
+    }
+}
+
+fn not_self_describing() -> Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "component model value encoding is not self-describing",
+    )
+    .into()
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(not_self_describing())
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(self.decode(BoolCodec)?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i8(self.decode(S8Codec)?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i16(self.decode(S16Codec)?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i32(self.decode(S32Codec)?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(self.decode(S64Codec)?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u8(self.decode(U8Codec)?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u16(self.decode(U16Codec)?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u32(self.decode(U32Codec)?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u64(self.decode(U64Codec)?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f32(self.decode(F32Codec)?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(self.decode(F64Codec)?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_char(self.decode(Utf8Codec)?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.scan {
+            self.decode(CoreVecDecoderBytes::default())?;
+            return visitor.visit_borrowed_str("");
+        }
+        visitor.visit_borrowed_str(self.decode(CoreNameDecoder::default())?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let buf = self.decode(CoreVecDecoderBytes::default())?;
+        visitor.visit_borrowed_bytes(if self.scan { &[] } else { buf })
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.decode_status()? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.decode_len()?;
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.decode_len()?;
+        visitor.visit_map(Elements { de: self, len })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(Elements {
+            de: self,
+            len: fields.len(),
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(not_self_describing())
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Access to a fixed number of consecutive values, i.e. elements of a `list`, `tuple` or
+/// `record` fields
+struct Elements<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    len: usize,
+}
+
+impl<'de> SeqAccess<'de> for Elements<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len.min(self.de.src.len()))
+    }
+}
+
+impl<'de> MapAccess<'de> for Elements<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        self.next_element_seed(seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len.min(self.de.src.len()))
+    }
+}
+
+impl<'de> EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let disc = self.decode(U32Codec)?;
+        let v = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(disc))?;
+        Ok((v, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(Elements {
+            de: self,
+            len: fields.len(),
+        })
+    }
+}
+
+/// Codec for [`Serialize`] and [`DeserializeOwned`] types using the [`serde`] mapping
+///
+/// Incomplete values are scanned without copying strings and byte arrays and are only
+/// deserialized once complete. The input is not scanned again until it reaches the length
+/// the value was found to require.
+#[derive(Debug)]
+pub struct SerdeCodec<T> {
+    need: usize,
+    _ty: PhantomData<fn(T) -> T>,
+}
+
+impl<T> Default for SerdeCodec<T> {
+    fn default() -> Self {
+        Self {
+            need: 0,
+            _ty: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize> Encoder<T> for SerdeCodec<T> {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        serialize(&item, dst)
+    }
+}
+
+impl<T: Serialize> Encoder<&T> for SerdeCodec<T> {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: &T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        serialize(item, dst)
+    }
+}
+
+impl<T: DeserializeOwned> Decoder for SerdeCodec<T> {
+    type Item = T;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < self.need {
+            return Ok(None);
+        }
+        let mut de = Deserializer::scan(src);
+        if let Err(Error(err)) = T::deserialize(&mut de) {
+            // other errors are reported by the deserialization below
+            if err.kind() == std::io::ErrorKind::UnexpectedEof {
+                self.need = de.need.max(src.len() + 1);
+                return Ok(None);
+            }
+        }
+        self.need = 0;
+        match deserialize(src) {
+            Ok((v, n)) => {
+                src.advance(n);
+                Ok(Some(v))
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use ::serde::{Deserialize, Serialize};
+
+    use crate::cm::{OptionEncoder, ResultEncoder, TupleEncoder, U8ListCodec, UnitCodec};
+    use crate::CoreVecEncoder;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum State {
+        Idle,
+        Running(u32),
+        Failed { code: i16, reason: String },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record<'a> {
+        name: &'a str,
+        tags: Vec<String>,
+        #[serde(with = "serde_bytes_compat")]
+        payload: Vec<u8>,
+        state: Option<State>,
+        attrs: BTreeMap<String, u64>,
+        point: (f32, char),
+        result: Result<bool, ()>,
+    }
+
+    mod serde_bytes_compat {
+        use ::serde::{Deserialize as _, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_bytes(v)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+            <&[u8]>::deserialize(d).map(<[u8]>::to_vec)
+        }
+    }
+
+    #[test_log::test]
+    fn record() {
+        let v = Record {
+            name: "x",
+            tags: vec!["a".into(), "b".into()],
+            payload: vec![1, 2, 3],
+            state: Some(State::Failed {
+                code: -2,
+                reason: "oops".into(),
+            }),
+            attrs: BTreeMap::from([("k".into(), 300)]),
+            point: (1.5, 'ƒ'),
+            result: Err(()),
+        };
+        let mut buf = BytesMut::default();
+        serialize(&v, &mut buf).expect("failed to serialize record");
+
+        let mut expected = BytesMut::default();
+        TupleEncoder((
+            CoreNameEncoder,
+            CoreVecEncoder(CoreNameEncoder),
+            U8ListCodec::default(),
+            OptionEncoder(TupleEncoder((U32Codec, S16Codec, CoreNameEncoder))),
+            CoreVecEncoder(TupleEncoder((CoreNameEncoder, U64Codec))),
+            TupleEncoder((F32Codec, Utf8Codec)),
+            ResultEncoder::err_only(UnitCodec),
+        ))
+        .encode(
+            (
+                "x",
+                ["a", "b"],
+                [1u8, 2, 3],
+                Some((2u32, -2i16, "oops")),
+                [("k", 300u64)],
+                (1.5f32, 'ƒ'),
+                Result::<(), ()>::Err(()),
+            ),
+            &mut expected,
+        )
+        .expect("failed to encode tuple");
+        assert_eq!(buf, expected);
+
+        let (got, n) = deserialize::<Record>(&buf).expect("failed to deserialize record");
+        assert_eq!(n, buf.len());
+        assert_eq!(got, v);
+        assert_eq!(got.name.as_ptr(), buf[1..].as_ptr());
+
+        let err = deserialize::<Record>(&buf[..buf.len() - 1])
+            .expect_err("deserialize should have failed, since input is short");
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test_log::test]
+    fn codec() {
+        let mut buf = BytesMut::default();
+        let mut codec = SerdeCodec::<State>::default();
+        codec
+            .encode(State::Idle, &mut buf)
+            .expect("failed to encode `idle`");
+        codec
+            .encode(&State::Running(0x80), &mut buf)
+            .expect("failed to encode `running`");
+        assert_eq!(buf.as_ref(), b"\0\x01\x80\x01");
+
+        let mut short = BytesMut::from(&buf[..3]);
+        let v = codec.decode(&mut short).expect("failed to decode `idle`");
+        assert_eq!(v, Some(State::Idle));
+        let v = codec
+            .decode(&mut short)
+            .expect("failed to decode short value");
+        assert_eq!(v, None);
+        assert_eq!(short.as_ref(), b"\x01\x80");
+        short.extend_from_slice(&buf[3..]);
+        let v = codec
+            .decode(&mut short)
+            .expect("failed to decode `running`");
+        assert_eq!(v, Some(State::Running(0x80)));
+
+        codec
+            .decode(&mut BytesMut::from(b"\x03".as_slice()))
+            .expect_err("decode should have failed, since 3 is not a valid discriminant");
+        deserialize::<Option<u8>>(b"\x02\0")
+            .expect_err("deserialize should have failed, since 2 is not a valid status");
+        deserialize::<::serde::de::IgnoredAny>(b"\0")
+            .expect_err("deserialize should have failed, since encoding is not self-describing");
+
+        let mut buf = BytesMut::default();
+        ser::Serializer::collect_seq(
+            &mut Serializer::new(&mut buf),
+            (0..3u8).filter(|x| x % 2 == 0),
+        )
+        .expect("failed to serialize list of unknown length");
+        assert_eq!(buf.as_ref(), b"\x02\0\x02");
+
+        struct Failing;
+
+        impl Serialize for Failing {
+            fn serialize<S: ser::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+                Err(ser::Error::custom("failing"))
+            }
+        }
+
+        serialize(&("foo", Failing), &mut buf)
+            .expect_err("serialize should have failed, since a field failed");
+        assert_eq!(buf.as_ref(), b"\x02\0\x02");
+    }
+
+    #[test_log::test]
+    fn incremental() {
+        let v = State::Failed {
+            code: 1,
+            reason: "x".repeat(1000),
+        };
+        let mut buf = BytesMut::default();
+        serialize(&v, &mut buf).expect("failed to serialize `failed`");
+
+        let mut codec = SerdeCodec::<State>::default();
+        let mut src = BytesMut::from(&buf[..10]);
+        let got = codec
+            .decode(&mut src)
+            .expect("failed to decode short value");
+        assert_eq!(got, None);
+        assert_eq!(codec.need, buf.len());
+        src.extend_from_slice(&buf[10..buf.len() - 1]);
+        let got = codec
+            .decode(&mut src)
+            .expect("failed to decode short value");
+        assert_eq!(got, None);
+        src.extend_from_slice(&buf[buf.len() - 1..]);
+        let got = codec.decode(&mut src).expect("failed to decode `failed`");
+        assert_eq!(got, Some(v));
+        assert!(src.is_empty());
+    }
+}
@@ -0,0 +1,594 @@
+use tokio_util::bytes::{Buf as _, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+use utf8_tokio::Utf8Codec;
+
+use crate::cm::dynamic::{
+    decode, decode_flags, encode_discriminant, encode_flags, encode_len, encode_payload,
+    invalid_discriminant, type_mismatch,
+};
+use crate::cm::{
+    BoolCodec, BufMutValueExt as _, BufValueExt as _, F32Codec, F64Codec, S16Codec, S32Codec,
+    S64Codec, S8Codec, U16Codec, U32Codec, U64Codec, U8Codec,
+};
+use crate::{
+    decode_core_vec_len, min_core_vec_len, min_core_vec_value_len, skip_value_len, CoreNameDecoder,
+    CoreNameEncoder, DecodeBorrowed, SkipValue,
+};
+
+/// Dynamic component model value type
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Type {
+    Bool,
+    S8,
+    U8,
+    S16,
+    U16,
+    S32,
+    U32,
+    S64,
+    U64,
+    F32,
+    F64,
+    Char,
+    String,
+    List(Box<Type>),
+    Record(Vec<(String, Type)>),
+    Tuple(Vec<Type>),
+    Variant(Vec<(String, Option<Type>)>),
+    Enum(Vec<String>),
+    Option(Box<Type>),
+    Result {
+        ok: Option<Box<Type>>,
+        err: Option<Box<Type>>,
+    },
+    Flags(Vec<String>),
+}
+
+/// Dynamic component model value.
+///
+/// Values are encoded and decoded for a given [`Type`], e.g. using [`ValueCodec`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    S8(i8),
+    U8(u8),
+    S16(i16),
+    U16(u16),
+    S32(i32),
+    U32(u32),
+    S64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    String(String),
+    List(Vec<Value>),
+    /// Record fields in order of the record type
+    Record(Vec<(String, Value)>),
+    Tuple(Vec<Value>),
+    /// Variant case name and optional payload
+    Variant(String, Option<Box<Value>>),
+    /// Enum case name
+    Enum(String),
+    Option(Option<Box<Value>>),
+    Result(Result<Option<Box<Value>>, Option<Box<Value>>>),
+    /// Names of set flags
+    Flags(Vec<String>),
+}
+
+impl Value {
+    /// Encodes the value as `ty`
+    pub fn encode(&self, ty: &Type, dst: &mut BytesMut) -> std::io::Result<()> {
+        match (ty, self) {
+            (Type::Bool, Self::Bool(v)) => BoolCodec.encode(v, dst),
+            (Type::S8, Self::S8(v)) => S8Codec.encode(v, dst),
+            (Type::U8, Self::U8(v)) => U8Codec.encode(v, dst),
+            (Type::S16, Self::S16(v)) => S16Codec.encode(v, dst),
+            (Type::U16, Self::U16(v)) => U16Codec.encode(v, dst),
+            (Type::S32, Self::S32(v)) => S32Codec.encode(v, dst),
+            (Type::U32, Self::U32(v)) => U32Codec.encode(v, dst),
+            (Type::S64, Self::S64(v)) => S64Codec.encode(v, dst),
+            (Type::U64, Self::U64(v)) => U64Codec.encode(v, dst),
+            (Type::F32, Self::F32(v)) => F32Codec.encode(v, dst),
+            (Type::F64, Self::F64(v)) => F64Codec.encode(v, dst),
+            (Type::Char, Self::Char(v)) => Utf8Codec.encode(v, dst),
+            (Type::String, Self::String(v)) => CoreNameEncoder.encode(v, dst),
+            (Type::List(ty), Self::List(vs)) => {
+                encode_len(vs.len(), dst)?;
+                for v in vs {
+                    v.encode(ty, dst)?;
+                }
+                Ok(())
+            }
+            (Type::Record(fields), Self::Record(vs)) if fields.len() == vs.len() => {
+                for (name, ty) in fields {
+                    let (_, v) = vs.iter().find(|(k, _)| k == name).ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            format!("missing record field `{name}`"),
+                        )
+                    })?;
+                    v.encode(ty, dst)?;
+                }
+                Ok(())
+            }
+            (Type::Tuple(tys), Self::Tuple(vs)) if tys.len() == vs.len() => {
+                for (ty, v) in tys.iter().zip(vs) {
+                    v.encode(ty, dst)?;
+                }
+                Ok(())
+            }
+            (Type::Variant(cases), Self::Variant(name, v)) => {
+                let i = encode_discriminant(cases.iter().map(|(name, _)| name), name, dst)?;
+                encode_payload(cases[i].1.as_ref(), v.as_deref(), |ty, v| v.encode(ty, dst))
+            }
+            (Type::Enum(cases), Self::Enum(name)) => {
+                encode_discriminant(cases, name, dst)?;
+                Ok(())
+            }
+            (Type::Option(ty), Self::Option(v)) => {
+                dst.reserve(1);
+                dst.put_option_status(v.as_ref());
+                if let Some(v) = v {
+                    v.encode(ty, dst)?;
+                }
+                Ok(())
+            }
+            (Type::Result { ok, err }, Self::Result(v)) => {
+                dst.reserve(1);
+                dst.put_result_status(v.as_ref());
+                match v {
+                    Ok(v) => encode_payload(ok.as_deref(), v.as_deref(), |ty, v| v.encode(ty, dst)),
+                    Err(v) => {
+                        encode_payload(err.as_deref(), v.as_deref(), |ty, v| v.encode(ty, dst))
+                    }
+                }
+            }
+            (Type::Flags(names), Self::Flags(vs)) => {
+                encode_flags(|| names.iter().map(String::as_str), vs, dst)
+            }
+            _ => Err(type_mismatch(ty, self)),
+        }
+    }
+}
+
+fn decode_payload(
+    ty: Option<&Type>,
+    src: &[u8],
+) -> std::io::Result<Option<(Option<Box<Value>>, usize)>> {
+    let Some(ty) = ty else {
+        return Ok(Some((None, 0)));
+    };
+    let (v, n) = decode!(ty, src);
+    Ok(Some((Some(Box::new(v)), n)))
+}
+
+impl DecodeBorrowed for Type {
+    type Value<'a> = Value;
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> std::io::Result<Option<(Self::Value<'a>, usize)>> {
+        let (v, n) = match self {
+            Self::Bool => {
+                let (v, n) = decode!(BoolCodec, src);
+                (Value::Bool(v), n)
+            }
+            Self::S8 => {
+                let (v, n) = decode!(S8Codec, src);
+                (Value::S8(v), n)
+            }
+            Self::U8 => {
+                let (v, n) = decode!(U8Codec, src);
+                (Value::U8(v), n)
+            }
+            Self::S16 => {
+                let (v, n) = decode!(S16Codec, src);
+                (Value::S16(v), n)
+            }
+            Self::U16 => {
+                let (v, n) = decode!(U16Codec, src);
+                (Value::U16(v), n)
+            }
+            Self::S32 => {
+                let (v, n) = decode!(S32Codec, src);
+                (Value::S32(v), n)
+            }
+            Self::U32 => {
+                let (v, n) = decode!(U32Codec, src);
+                (Value::U32(v), n)
+            }
+            Self::S64 => {
+                let (v, n) = decode!(S64Codec, src);
+                (Value::S64(v), n)
+            }
+            Self::U64 => {
+                let (v, n) = decode!(U64Codec, src);
+                (Value::U64(v), n)
+            }
+            Self::F32 => {
+                let (v, n) = decode!(F32Codec, src);
+                (Value::F32(v), n)
+            }
+            Self::F64 => {
+                let (v, n) = decode!(F64Codec, src);
+                (Value::F64(v), n)
+            }
+            Self::Char => {
+                let (v, n) = decode!(Utf8Codec, src);
+                (Value::Char(v), n)
+            }
+            Self::String => {
+                let (v, n) = decode!(CoreNameDecoder::default(), src);
+                (Value::String(v.to_string()), n)
+            }
+            Self::List(ty) => {
+                let Some((len, mut n)) = decode_core_vec_len(src)? else {
+                    return Ok(None);
+                };
+                let mut vs = Vec::with_capacity(len.min(src.len() - n));
+                for _ in 0..len {
+                    let (v, k) = decode!(ty, &src[n..]);
+                    vs.push(v);
+                    n += k;
+                }
+                (Value::List(vs), n)
+            }
+            Self::Record(fields) => {
+                let mut n = 0;
+                let mut vs = Vec::with_capacity(fields.len());
+                for (name, ty) in fields {
+                    let (v, k) = decode!(ty, &src[n..]);
+                    vs.push((name.clone(), v));
+                    n += k;
+                }
+                (Value::Record(vs), n)
+            }
+            Self::Tuple(tys) => {
+                let mut n = 0;
+                let mut vs = Vec::with_capacity(tys.len());
+                for ty in tys {
+                    let (v, k) = decode!(ty, &src[n..]);
+                    vs.push(v);
+                    n += k;
+                }
+                (Value::Tuple(vs), n)
+            }
+            Self::Variant(cases) => {
+                let (disc, n) = decode!(U32Codec, src);
+                let (name, ty) = usize::try_from(disc)
+                    .ok()
+                    .and_then(|i| cases.get(i))
+                    .ok_or_else(|| invalid_discriminant(disc))?;
+                let Some((v, k)) = decode_payload(ty.as_ref(), &src[n..])? else {
+                    return Ok(None);
+                };
+                (Value::Variant(name.clone(), v), n + k)
+            }
+            Self::Enum(cases) => {
+                let (disc, n) = decode!(U32Codec, src);
+                let name = usize::try_from(disc)
+                    .ok()
+                    .and_then(|i| cases.get(i))
+                    .ok_or_else(|| invalid_discriminant(disc))?;
+                (Value::Enum(name.clone()), n)
+            }
+            Self::Option(ty) => {
+                let Some(is_some) = { src }.get_option_status()? else {
+                    return Ok(None);
+                };
+                if is_some {
+                    let (v, n) = decode!(ty, &src[1..]);
+                    (Value::Option(Some(Box::new(v))), n + 1)
+                } else {
+                    (Value::Option(None), 1)
+                }
+            }
+            Self::Result { ok, err } => {
+                let Some(is_ok) = { src }.get_result_status()? else {
+                    return Ok(None);
+                };
+                if is_ok {
+                    let Some((v, n)) = decode_payload(ok.as_deref(), &src[1..])? else {
+                        return Ok(None);
+                    };
+                    (Value::Result(Ok(v)), n + 1)
+                } else {
+                    let Some((v, n)) = decode_payload(err.as_deref(), &src[1..])? else {
+                        return Ok(None);
+                    };
+                    (Value::Result(Err(v)), n + 1)
+                }
+            }
+            Self::Flags(names) => {
+                let Some((vs, n)) = decode_flags(|| names.iter().map(String::as_str), src)? else {
+                    return Ok(None);
+                };
+                (Value::Flags(vs), n)
+            }
+        };
+        Ok(Some((v, n)))
+    }
+}
+
+/// Returns the encoded length of consecutive values of types `tys` at the start of `src`
+fn values_len<'a>(
+    tys: impl IntoIterator<Item = &'a Type>,
+    src: &[u8],
+) -> std::io::Result<Option<usize>> {
+    let mut n = 0;
+    for ty in tys {
+        let Some(k) = ty.value_len(&src[n..])? else {
+            return Ok(None);
+        };
+        n += k;
+    }
+    Ok(Some(n))
+}
+
+/// Returns a lower bound for the encoded length of consecutive values of types `tys`
+/// at the start of `src`
+fn min_values_len<'a>(tys: impl IntoIterator<Item = &'a Type>, src: &[u8]) -> usize {
+    let mut n = 0;
+    for ty in tys {
+        match ty.value_len(&src[n..]) {
+            Ok(Some(k)) => n += k,
+            _ => return n + ty.min_value_len(&src[n..]),
+        }
+    }
+    n.max(src.len() + 1)
+}
+
+fn payload_value_len(ty: Option<&Type>, src: &[u8]) -> std::io::Result<Option<usize>> {
+    ty.map_or(Ok(Some(0)), |ty| ty.value_len(src))
+}
+
+fn min_payload_len(ty: Option<&Type>, src: &[u8]) -> usize {
+    ty.map_or(src.len() + 1, |ty| ty.min_value_len(src))
+}
+
+impl SkipValue for Type {
+    fn value_len(&self, src: &[u8]) -> std::io::Result<Option<usize>> {
+        match self {
+            Self::Bool => BoolCodec.value_len(src),
+            Self::S8 => S8Codec.value_len(src),
+            Self::U8 => U8Codec.value_len(src),
+            Self::S16 => S16Codec.value_len(src),
+            Self::U16 => U16Codec.value_len(src),
+            Self::S32 => S32Codec.value_len(src),
+            Self::U32 => U32Codec.value_len(src),
+            Self::S64 => S64Codec.value_len(src),
+            Self::U64 => U64Codec.value_len(src),
+            Self::F32 => F32Codec.value_len(src),
+            Self::F64 => F64Codec.value_len(src),
+            Self::Char => Utf8Codec.value_len(src),
+            Self::String => CoreNameDecoder::default().value_len(src),
+            Self::List(ty) => {
+                let Some((len, mut n)) = decode_core_vec_len(src)? else {
+                    return Ok(None);
+                };
+                for _ in 0..len {
+                    let Some(k) = ty.value_len(&src[n..])? else {
+                        return Ok(None);
+                    };
+                    n += k;
+                }
+                Ok(Some(n))
+            }
+            Self::Record(fields) => values_len(fields.iter().map(|(_, ty)| ty), src),
+            Self::Tuple(tys) => values_len(tys, src),
+            Self::Variant(cases) => {
+                let (disc, n) = decode!(U32Codec, src);
+                let (_, ty) = usize::try_from(disc)
+                    .ok()
+                    .and_then(|i| cases.get(i))
+                    .ok_or_else(|| invalid_discriminant(disc))?;
+                Ok(payload_value_len(ty.as_ref(), &src[n..])?.map(|k| n + k))
+            }
+            Self::Enum(cases) => {
+                let (disc, n) = decode!(U32Codec, src);
+                usize::try_from(disc)
+                    .ok()
+                    .and_then(|i| cases.get(i))
+                    .ok_or_else(|| invalid_discriminant(disc))?;
+                Ok(Some(n))
+            }
+            Self::Option(ty) => match { src }.get_option_status()? {
+                None => Ok(None),
+                Some(false) => Ok(Some(1)),
+                Some(true) => Ok(ty.value_len(&src[1..])?.map(|n| n + 1)),
+            },
+            Self::Result { ok, err } => {
+                let Some(is_ok) = { src }.get_result_status()? else {
+                    return Ok(None);
+                };
+                let ty = if is_ok { ok } else { err };
+                Ok(payload_value_len(ty.as_deref(), &src[1..])?.map(|n| n + 1))
+            }
+            Self::Flags(names) => {
+                Ok(decode_flags(|| names.iter().map(String::as_str), src)?.map(|(_, n)| n))
+            }
+        }
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        match self {
+            Self::String => min_core_vec_len(src, 1),
+            Self::List(ty) => min_core_vec_value_len(ty.as_ref(), src),
+            Self::Record(fields) => min_values_len(fields.iter().map(|(_, ty)| ty), src),
+            Self::Tuple(tys) => min_values_len(tys, src),
+            Self::Variant(cases) => match U32Codec.decode_borrowed(src) {
+                Ok(Some((disc, n))) => {
+                    let ty = usize::try_from(disc)
+                        .ok()
+                        .and_then(|i| cases.get(i))
+                        .and_then(|(_, ty)| ty.as_ref());
+                    n + min_payload_len(ty, &src[n..])
+                }
+                _ => src.len() + 1,
+            },
+            Self::Option(ty) => match { src }.get_option_status() {
+                Ok(Some(true)) => 1 + ty.min_value_len(&src[1..]),
+                _ => src.len() + 1,
+            },
+            Self::Result { ok, err } => match { src }.get_result_status() {
+                Ok(Some(true)) => 1 + min_payload_len(ok.as_deref(), &src[1..]),
+                Ok(Some(false)) => 1 + min_payload_len(err.as_deref(), &src[1..]),
+                _ => src.len() + 1,
+            },
+            Self::Flags(names) => names.len().div_ceil(8),
+            _ => src.len() + 1,
+        }
+    }
+}
+
+/// Codec for dynamic [`Value`]s of a particular [`Type`]
+///
+/// Decoding scans `src` without materialising anything until a complete value is buffered.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValueCodec {
+    ty: Type,
+    need: usize,
+}
+
+impl ValueCodec {
+    /// Constructs a new codec for values of type `ty`
+    pub fn new(ty: Type) -> Self {
+        Self { ty, need: 0 }
+    }
+
+    /// Returns the type of values handled by this codec
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /// Returns the type of values handled by this codec, consuming the codec
+    pub fn into_inner(self) -> Type {
+        self.ty
+    }
+}
+
+impl Encoder<&Value> for ValueCodec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: &Value, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        item.encode(&self.ty, dst)
+    }
+}
+
+impl Encoder<Value> for ValueCodec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: Value, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        item.encode(&self.ty, dst)
+    }
+}
+
+impl Decoder for ValueCodec {
+    type Item = Value;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(n) = skip_value_len(&self.ty, &mut self.need, src)? else {
+            return Ok(None);
+        };
+        let Some((v, _)) = self.ty.decode_borrowed(&src[..n])? else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "value shorter than its scanned length",
+            ));
+        };
+        src.advance(n);
+        Ok(Some(v))
+    }
+}
+
+impl SkipValue for ValueCodec {
+    fn value_len(&self, src: &[u8]) -> std::io::Result<Option<usize>> {
+        self.ty.value_len(src)
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        self.ty.min_value_len(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn value() {
+        let ty = Type::Variant(vec![
+            ("a".into(), None),
+            ("b".into(), Some(Type::List(Box::new(Type::U16)))),
+        ]);
+        let v = Value::Variant(
+            "b".into(),
+            Some(Box::new(Value::List(vec![Value::U16(1), Value::U16(300)]))),
+        );
+        let mut buf = BytesMut::default();
+        ValueCodec::new(ty.clone())
+            .encode(&v, &mut buf)
+            .expect("failed to encode variant");
+        assert_eq!(buf.as_ref(), b"\x01\x02\x01\xac\x02");
+        for i in 0..buf.len() {
+            assert_eq!(
+                ty.decode_borrowed(&buf[..i])
+                    .expect("failed to decode variant"),
+                None
+            );
+        }
+        assert_eq!(ty.value_len(&buf).expect("failed to skip variant"), Some(5));
+        assert_eq!(
+            ValueCodec::new(ty.clone())
+                .decode(&mut buf)
+                .expect("failed to decode variant"),
+            Some(v)
+        );
+
+        let tuple_ty = Type::Tuple(vec![Type::List(Box::new(Type::String)), Type::Bool]);
+        let tuple = Value::Tuple(vec![
+            Value::List(vec![
+                Value::String("a".repeat(200)),
+                Value::String("b".repeat(300)),
+            ]),
+            Value::Bool(true),
+        ]);
+        let mut buf = BytesMut::default();
+        tuple
+            .encode(&tuple_ty, &mut buf)
+            .expect("failed to encode tuple");
+        assert_eq!(tuple_ty.min_value_len(&buf[..3]), 1 + 2 + 200);
+        assert_eq!(tuple_ty.min_value_len(&buf[..205]), 1 + 2 + 200 + 2 + 300);
+
+        let mut dec = ValueCodec::new(tuple_ty);
+        let mut src = BytesMut::default();
+        for (i, b) in buf.iter().enumerate() {
+            src.extend_from_slice(&[*b]);
+            let decoded = dec.decode(&mut src).expect("failed to decode tuple");
+            if i + 1 < buf.len() {
+                assert_eq!(decoded, None);
+            } else {
+                assert_eq!(decoded.as_ref(), Some(&tuple));
+            }
+        }
+        assert!(src.is_empty());
+
+        Value::Variant("c".into(), None)
+            .encode(&ty, &mut buf)
+            .expect_err("encode should have failed, since `c` is not a case");
+        Value::Variant("b".into(), None)
+            .encode(&ty, &mut buf)
+            .expect_err("encode should have failed, since `b` has a payload");
+        Value::U32(1)
+            .encode(&ty, &mut buf)
+            .expect_err("encode should have failed, since the value is not a variant");
+        ty.decode_borrowed(b"\x02")
+            .expect_err("decode should have failed, since `2` is not a valid discriminant");
+        Type::Flags(vec!["a".into()])
+            .decode_borrowed(b"\x02")
+            .expect_err("decode should have failed, since an unknown flag is set");
+    }
+}
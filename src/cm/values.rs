@@ -1,16 +1,40 @@
+#[cfg(feature = "tokio")]
 use ::core::future::Future;
-
+#[cfg(feature = "tokio")]
+use ::core::mem;
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use bytes::{Buf, BufMut, Bytes};
+#[cfg(feature = "tokio")]
+use leb128_tokio::Leb128Encoder;
 use leb128_tokio::{
-    Leb128DecoderI16, Leb128DecoderI32, Leb128DecoderI64, Leb128DecoderU16, Leb128DecoderU32,
-    Leb128DecoderU64, Leb128Encoder,
+    leb128_len_i16, leb128_len_i32, leb128_len_i64, leb128_len_u16, leb128_len_u32, leb128_len_u64,
+    BufMutLeb128Ext as _, DecodeLeb128Bulk, Leb128DecoderI16, Leb128DecoderI32, Leb128DecoderI64,
+    Leb128DecoderU16, Leb128DecoderU32, Leb128DecoderU64,
 };
+#[cfg(feature = "tokio")]
 use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
-use tokio_util::bytes::{Buf as _, BufMut as _, Bytes, BytesMut};
+#[cfg(feature = "tokio")]
+use tokio_util::bytes::BytesMut;
+#[cfg(feature = "tokio")]
 use tokio_util::codec::{Decoder, Encoder};
+use utf8_tokio::BufMutUtf8Ext as _;
+#[cfg(feature = "tokio")]
 use utf8_tokio::Utf8Codec;
 
-use crate::CoreNameEncoder;
+use crate::{
+    core_vec_len_len, decode_core_vec_len, min_core_vec_len, CoreNameEncoder, CoreVecDecoderBytes,
+    DecodeBorrowed, EncodedLen, Error, ErrorKind, SkipValue, WasmEncode,
+};
 
+#[cfg(feature = "tokio")]
 macro_rules! ensure_capacity {
     ($src:ident, $n:expr) => {
         if let Some(n) = $n.checked_sub($src.len()) {
@@ -24,6 +48,7 @@ macro_rules! ensure_capacity {
 
 macro_rules! impl_encode_copy_ref {
     ($enc:ident, $t:ty) => {
+        #[cfg(feature = "tokio")]
         impl Encoder<&$t> for $enc {
             type Error = std::io::Error;
 
@@ -36,6 +61,7 @@ macro_rules! impl_encode_copy_ref {
             }
         }
 
+        #[cfg(feature = "tokio")]
         impl Encoder<&&$t> for $enc {
             type Error = std::io::Error;
 
@@ -52,6 +78,7 @@ macro_rules! impl_encode_copy_ref {
 
 macro_rules! impl_encode_str {
     ($enc:ident, $t:ty) => {
+        #[cfg(feature = "tokio")]
         impl Encoder<$t> for $enc {
             type Error = std::io::Error;
 
@@ -64,6 +91,7 @@ macro_rules! impl_encode_str {
             }
         }
 
+        #[cfg(feature = "tokio")]
         impl Encoder<&$t> for $enc {
             type Error = std::io::Error;
 
@@ -78,7 +106,49 @@ macro_rules! impl_encode_str {
     };
 }
 
+macro_rules! impl_encoded_len_copy {
+    ($enc:ident, $t:ty, $n:literal) => {
+        impl EncodedLen<$t> for $enc {
+            fn encoded_len(&self, _: &$t) -> usize {
+                $n
+            }
+        }
+
+        impl_encoded_len_copy!($enc, $t);
+    };
+    ($enc:ident, $t:ty, $f:path) => {
+        impl EncodedLen<$t> for $enc {
+            fn encoded_len(&self, item: &$t) -> usize {
+                $f(*item)
+            }
+        }
+
+        impl_encoded_len_copy!($enc, $t);
+    };
+    ($enc:ident, $t:ty) => {
+        impl EncodedLen<&$t> for $enc {
+            fn encoded_len(&self, item: &&$t) -> usize {
+                self.encoded_len(*item)
+            }
+        }
+
+        impl EncodedLen<&&$t> for $enc {
+            fn encoded_len(&self, item: &&&$t) -> usize {
+                self.encoded_len(**item)
+            }
+        }
+    };
+}
+
+/// Component model value extensions for [`AsyncRead`]
+///
+/// The methods of this trait are not cancellation safe, see
+/// [`ValueReader`](crate::ValueReader#cancel-safety).
+#[cfg(feature = "tokio")]
 pub trait AsyncReadValue: AsyncRead {
+    /// Reads `bool`
+    ///
+    /// This method is not cancellation safe, see [`ValueReader`](crate::ValueReader#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "bool"))
@@ -99,6 +169,9 @@ pub trait AsyncReadValue: AsyncRead {
         }
     }
 
+    /// Reads `option` status, [`true`] if the option is `some`
+    ///
+    /// This method is not cancellation safe, see [`ValueReader`](crate::ValueReader#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "option"))
@@ -119,6 +192,9 @@ pub trait AsyncReadValue: AsyncRead {
         }
     }
 
+    /// Reads `result` status, [`true`] if the result is `ok`
+    ///
+    /// This method is not cancellation safe, see [`ValueReader`](crate::ValueReader#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "result"))
@@ -140,8 +216,10 @@ pub trait AsyncReadValue: AsyncRead {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<T: AsyncRead> AsyncReadValue for T {}
 
+#[cfg(feature = "tokio")]
 pub trait AsyncWriteValue: AsyncWrite {
     #[cfg_attr(
         feature = "tracing",
@@ -180,11 +258,118 @@ pub trait AsyncWriteValue: AsyncWrite {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<T: AsyncWrite> AsyncWriteValue for T {}
 
+/// Component model value extensions for [`Buf`]
+pub trait BufValueExt: Buf {
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed
+    fn get_bool(&mut self) -> Result<Option<bool>, Error> {
+        if !self.has_remaining() {
+            return Ok(None);
+        }
+        match self.get_u8() {
+            0 => Ok(Some(false)),
+            1 => Ok(Some(true)),
+            n => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid bool value byte `{n}`"),
+            )),
+        }
+    }
+
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed
+    fn get_option_status(&mut self) -> Result<Option<bool>, Error> {
+        if !self.has_remaining() {
+            return Ok(None);
+        }
+        match self.get_u8() {
+            0 => Ok(Some(false)),
+            1 => Ok(Some(true)),
+            n => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid option status byte value `{n}`"),
+            )),
+        }
+    }
+
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed
+    fn get_result_status(&mut self) -> Result<Option<bool>, Error> {
+        if !self.has_remaining() {
+            return Ok(None);
+        }
+        match self.get_u8() {
+            0 => Ok(Some(true)),
+            1 => Ok(Some(false)),
+            n => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid result status byte value `{n}`"),
+            )),
+        }
+    }
+}
+
+impl<T: Buf + ?Sized> BufValueExt for T {}
+
+/// Component model value extensions for [`BufMut`]
+pub trait BufMutValueExt: BufMut {
+    /// Puts `bool`
+    fn put_bool(&mut self, v: bool) {
+        self.put_u8(v.into());
+    }
+
+    /// Puts `option` status
+    fn put_option_status<T>(&mut self, v: Option<T>) {
+        self.put_u8(v.is_some().into());
+    }
+
+    /// Puts `result` status
+    fn put_result_status<T, E>(&mut self, v: Result<T, E>) {
+        self.put_u8(v.is_err().into());
+    }
+}
+
+impl<T: BufMut + ?Sized> BufMutValueExt for T {}
+
+/// Codec for `()`, which is encoded as zero bytes, e.g. for payload-less `result` arms
+/// and variant cases
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct UnitCodec;
+
+#[cfg(feature = "tokio")]
+impl Encoder<()> for UnitCodec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, (): (), _: &mut BytesMut) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Encoder<&()> for UnitCodec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, (): &(), _: &mut BytesMut) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl_encoded_len_copy!(UnitCodec, (), 0);
+
+#[cfg(feature = "tokio")]
+impl Decoder for UnitCodec {
+    type Item = ();
+    type Error = std::io::Error;
+
+    fn decode(&mut self, _: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(Some(()))
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct BoolCodec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<bool> for BoolCodec {
     type Error = std::io::Error;
 
@@ -197,7 +382,9 @@ impl Encoder<bool> for BoolCodec {
 }
 
 impl_encode_copy_ref!(BoolCodec, bool);
+impl_encoded_len_copy!(BoolCodec, bool, 1);
 
+#[cfg(feature = "tokio")]
 impl Decoder for BoolCodec {
     type Item = bool;
     type Error = std::io::Error;
@@ -219,6 +406,7 @@ impl Decoder for BoolCodec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct S8Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<i8> for S8Codec {
     type Error = std::io::Error;
 
@@ -231,7 +419,9 @@ impl Encoder<i8> for S8Codec {
 }
 
 impl_encode_copy_ref!(S8Codec, i8);
+impl_encoded_len_copy!(S8Codec, i8, 1);
 
+#[cfg(feature = "tokio")]
 impl Decoder for S8Codec {
     type Item = i8;
     type Error = std::io::Error;
@@ -246,6 +436,7 @@ impl Decoder for S8Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct U8Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<u8> for U8Codec {
     type Error = std::io::Error;
 
@@ -258,7 +449,9 @@ impl Encoder<u8> for U8Codec {
 }
 
 impl_encode_copy_ref!(U8Codec, u8);
+impl_encoded_len_copy!(U8Codec, u8, 1);
 
+#[cfg(feature = "tokio")]
 impl Decoder for U8Codec {
     type Item = u8;
     type Error = std::io::Error;
@@ -276,6 +469,7 @@ impl Decoder for U8Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct S16Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<i16> for S16Codec {
     type Error = std::io::Error;
 
@@ -286,7 +480,9 @@ impl Encoder<i16> for S16Codec {
 }
 
 impl_encode_copy_ref!(S16Codec, i16);
+impl_encoded_len_copy!(S16Codec, i16, leb128_len_i16);
 
+#[cfg(feature = "tokio")]
 impl Decoder for S16Codec {
     type Item = i16;
     type Error = std::io::Error;
@@ -300,6 +496,7 @@ impl Decoder for S16Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct U16Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<u16> for U16Codec {
     type Error = std::io::Error;
 
@@ -310,7 +507,9 @@ impl Encoder<u16> for U16Codec {
 }
 
 impl_encode_copy_ref!(U16Codec, u16);
+impl_encoded_len_copy!(U16Codec, u16, leb128_len_u16);
 
+#[cfg(feature = "tokio")]
 impl Decoder for U16Codec {
     type Item = u16;
     type Error = std::io::Error;
@@ -324,6 +523,7 @@ impl Decoder for U16Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct S32Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<i32> for S32Codec {
     type Error = std::io::Error;
 
@@ -334,7 +534,9 @@ impl Encoder<i32> for S32Codec {
 }
 
 impl_encode_copy_ref!(S32Codec, i32);
+impl_encoded_len_copy!(S32Codec, i32, leb128_len_i32);
 
+#[cfg(feature = "tokio")]
 impl Decoder for S32Codec {
     type Item = i32;
     type Error = std::io::Error;
@@ -348,6 +550,7 @@ impl Decoder for S32Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct U32Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<u32> for U32Codec {
     type Error = std::io::Error;
 
@@ -358,7 +561,9 @@ impl Encoder<u32> for U32Codec {
 }
 
 impl_encode_copy_ref!(U32Codec, u32);
+impl_encoded_len_copy!(U32Codec, u32, leb128_len_u32);
 
+#[cfg(feature = "tokio")]
 impl Decoder for U32Codec {
     type Item = u32;
     type Error = std::io::Error;
@@ -372,6 +577,7 @@ impl Decoder for U32Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct S64Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<i64> for S64Codec {
     type Error = std::io::Error;
 
@@ -382,7 +588,9 @@ impl Encoder<i64> for S64Codec {
 }
 
 impl_encode_copy_ref!(S64Codec, i64);
+impl_encoded_len_copy!(S64Codec, i64, leb128_len_i64);
 
+#[cfg(feature = "tokio")]
 impl Decoder for S64Codec {
     type Item = i64;
     type Error = std::io::Error;
@@ -396,6 +604,7 @@ impl Decoder for S64Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct U64Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<u64> for U64Codec {
     type Error = std::io::Error;
 
@@ -406,7 +615,9 @@ impl Encoder<u64> for U64Codec {
 }
 
 impl_encode_copy_ref!(U64Codec, u64);
+impl_encoded_len_copy!(U64Codec, u64, leb128_len_u64);
 
+#[cfg(feature = "tokio")]
 impl Decoder for U64Codec {
     type Item = u64;
     type Error = std::io::Error;
@@ -420,6 +631,7 @@ impl Decoder for U64Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct F32Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<f32> for F32Codec {
     type Error = std::io::Error;
 
@@ -432,7 +644,9 @@ impl Encoder<f32> for F32Codec {
 }
 
 impl_encode_copy_ref!(F32Codec, f32);
+impl_encoded_len_copy!(F32Codec, f32, 4);
 
+#[cfg(feature = "tokio")]
 impl Decoder for F32Codec {
     type Item = f32;
     type Error = std::io::Error;
@@ -447,6 +661,7 @@ impl Decoder for F32Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct F64Codec;
 
+#[cfg(feature = "tokio")]
 impl Encoder<f64> for F64Codec {
     type Error = std::io::Error;
 
@@ -459,7 +674,9 @@ impl Encoder<f64> for F64Codec {
 }
 
 impl_encode_copy_ref!(F64Codec, f64);
+impl_encoded_len_copy!(F64Codec, f64, 8);
 
+#[cfg(feature = "tokio")]
 impl Decoder for F64Codec {
     type Item = f64;
     type Error = std::io::Error;
@@ -474,6 +691,7 @@ impl Decoder for F64Codec {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct PrimValEncoder;
 
+#[cfg(feature = "tokio")]
 impl Encoder<bool> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -482,6 +700,7 @@ impl Encoder<bool> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<i8> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -494,6 +713,7 @@ impl Encoder<i8> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<u8> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -506,6 +726,7 @@ impl Encoder<u8> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<i16> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -518,6 +739,7 @@ impl Encoder<i16> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<u16> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -530,6 +752,7 @@ impl Encoder<u16> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<i32> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -542,6 +765,7 @@ impl Encoder<i32> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<u32> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -554,6 +778,7 @@ impl Encoder<u32> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<i64> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -566,6 +791,7 @@ impl Encoder<i64> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<u64> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -578,6 +804,7 @@ impl Encoder<u64> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<f32> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -590,6 +817,7 @@ impl Encoder<f32> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<f64> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -602,6 +830,7 @@ impl Encoder<f64> for PrimValEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<char> for PrimValEncoder {
     type Error = std::io::Error;
 
@@ -617,6 +846,7 @@ impl Encoder<char> for PrimValEncoder {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct FlagEncoder;
 
+#[cfg(feature = "tokio")]
 impl Encoder<u8> for FlagEncoder {
     type Error = std::io::Error;
 
@@ -631,6 +861,7 @@ impl Encoder<u8> for FlagEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<u16> for FlagEncoder {
     type Error = std::io::Error;
 
@@ -645,6 +876,7 @@ impl Encoder<u16> for FlagEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<u32> for FlagEncoder {
     type Error = std::io::Error;
 
@@ -659,6 +891,7 @@ impl Encoder<u32> for FlagEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<u64> for FlagEncoder {
     type Error = std::io::Error;
 
@@ -673,6 +906,7 @@ impl Encoder<u64> for FlagEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<u128> for FlagEncoder {
     type Error = std::io::Error;
 
@@ -687,6 +921,7 @@ impl Encoder<u128> for FlagEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<Vec<u8>> for FlagEncoder {
     type Error = std::io::Error;
 
@@ -700,6 +935,7 @@ impl Encoder<Vec<u8>> for FlagEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<&[u8]> for FlagEncoder {
     type Error = std::io::Error;
 
@@ -713,6 +949,7 @@ impl Encoder<&[u8]> for FlagEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<Bytes> for FlagEncoder {
     type Error = std::io::Error;
 
@@ -726,6 +963,7 @@ impl Encoder<Bytes> for FlagEncoder {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl Encoder<&Bytes> for FlagEncoder {
     type Error = std::io::Error;
 
@@ -742,6 +980,7 @@ impl Encoder<&Bytes> for FlagEncoder {
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct FlagDecoder<const N: usize>;
 
+#[cfg(feature = "tokio")]
 impl<const N: usize> Decoder for FlagDecoder<N> {
     type Item = Bytes;
     type Error = std::io::Error;
@@ -751,7 +990,7 @@ impl<const N: usize> Decoder for FlagDecoder<N> {
         tracing::instrument(level = "trace", skip_all, fields(dst, ty = "flags"))
     )]
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let n = if N % 8 == 0 { N / 8 } else { N / 8 + 1 };
+        let n = N.div_ceil(8);
         if src.len() < n {
             ensure_capacity!(src, n);
         }
@@ -780,11 +1019,223 @@ impl_encode_copy_ref!(FlagEncoder, u128);
 
 impl_encode_str!(PrimValEncoder, &str);
 impl_encode_str!(PrimValEncoder, String);
+impl_encode_str!(PrimValEncoder, Box<str>);
+impl_encode_str!(PrimValEncoder, Rc<str>);
+impl_encode_str!(PrimValEncoder, Arc<str>);
+impl_encode_str!(PrimValEncoder, Cow<'_, str>);
+
+impl_encoded_len_copy!(PrimValEncoder, bool, 1);
+impl_encoded_len_copy!(PrimValEncoder, i8, 1);
+impl_encoded_len_copy!(PrimValEncoder, u8, 1);
+impl_encoded_len_copy!(PrimValEncoder, i16, leb128_len_i16);
+impl_encoded_len_copy!(PrimValEncoder, u16, leb128_len_u16);
+impl_encoded_len_copy!(PrimValEncoder, i32, leb128_len_i32);
+impl_encoded_len_copy!(PrimValEncoder, u32, leb128_len_u32);
+impl_encoded_len_copy!(PrimValEncoder, i64, leb128_len_i64);
+impl_encoded_len_copy!(PrimValEncoder, u64, leb128_len_u64);
+impl_encoded_len_copy!(PrimValEncoder, f32, 4);
+impl_encoded_len_copy!(PrimValEncoder, f64, 8);
+impl_encoded_len_copy!(PrimValEncoder, char, char::len_utf8);
+
+impl_encoded_len_copy!(FlagEncoder, u8, 1);
+impl_encoded_len_copy!(FlagEncoder, u16, 2);
+impl_encoded_len_copy!(FlagEncoder, u32, 4);
+impl_encoded_len_copy!(FlagEncoder, u64, 8);
+impl_encoded_len_copy!(FlagEncoder, u128, 16);
+
+impl EncodedLen<[u8]> for FlagEncoder {
+    fn encoded_len(&self, item: &[u8]) -> usize {
+        item.len()
+    }
+}
+
+impl EncodedLen<&[u8]> for FlagEncoder {
+    fn encoded_len(&self, item: &&[u8]) -> usize {
+        item.len()
+    }
+}
+
+impl EncodedLen<Vec<u8>> for FlagEncoder {
+    fn encoded_len(&self, item: &Vec<u8>) -> usize {
+        item.len()
+    }
+}
+
+impl EncodedLen<Bytes> for FlagEncoder {
+    fn encoded_len(&self, item: &Bytes) -> usize {
+        item.len()
+    }
+}
+
+impl EncodedLen<&Bytes> for FlagEncoder {
+    fn encoded_len(&self, item: &&Bytes) -> usize {
+        item.len()
+    }
+}
+
+impl EncodedLen<str> for PrimValEncoder {
+    fn encoded_len(&self, item: &str) -> usize {
+        CoreNameEncoder.encoded_len(item)
+    }
+}
+
+impl EncodedLen<&str> for PrimValEncoder {
+    fn encoded_len(&self, item: &&str) -> usize {
+        CoreNameEncoder.encoded_len(item)
+    }
+}
+
+impl EncodedLen<&&str> for PrimValEncoder {
+    fn encoded_len(&self, item: &&&str) -> usize {
+        CoreNameEncoder.encoded_len(item)
+    }
+}
+
+impl EncodedLen<String> for PrimValEncoder {
+    fn encoded_len(&self, item: &String) -> usize {
+        CoreNameEncoder.encoded_len(item)
+    }
+}
+
+impl EncodedLen<&String> for PrimValEncoder {
+    fn encoded_len(&self, item: &&String) -> usize {
+        CoreNameEncoder.encoded_len(item)
+    }
+}
+
+macro_rules! impl_encoded_len_str {
+    ($($t:ty),+) => {
+        $(
+            impl EncodedLen<$t> for PrimValEncoder {
+                fn encoded_len(&self, item: &$t) -> usize {
+                    CoreNameEncoder.encoded_len(item)
+                }
+            }
+
+            impl EncodedLen<&$t> for PrimValEncoder {
+                fn encoded_len(&self, item: &&$t) -> usize {
+                    CoreNameEncoder.encoded_len(item)
+                }
+            }
+        )+
+    };
+}
+
+impl_encoded_len_str!(Box<str>, Rc<str>, Arc<str>, Cow<'_, str>);
+
+macro_rules! impl_wasm_encode_copy {
+    ($enc:ty, $t:ty, |$dst:ident, $x:ident| $put:expr) => {
+        impl WasmEncode<$t> for $enc {
+            fn put_buf(&mut self, $x: $t, $dst: &mut impl BufMut) -> Result<(), Error> {
+                $put;
+                Ok(())
+            }
+        }
+
+        impl WasmEncode<&$t> for $enc {
+            fn put_buf(&mut self, item: &$t, dst: &mut impl BufMut) -> Result<(), Error> {
+                self.put_buf(*item, dst)
+            }
+        }
+
+        impl WasmEncode<&&$t> for $enc {
+            fn put_buf(&mut self, item: &&$t, dst: &mut impl BufMut) -> Result<(), Error> {
+                self.put_buf(**item, dst)
+            }
+        }
+    };
+}
+
+impl WasmEncode<()> for UnitCodec {
+    fn put_buf(&mut self, (): (), _: &mut impl BufMut) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl WasmEncode<&()> for UnitCodec {
+    fn put_buf(&mut self, (): &(), _: &mut impl BufMut) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl_wasm_encode_copy!(BoolCodec, bool, |dst, x| dst.put_bool(x));
+impl_wasm_encode_copy!(S8Codec, i8, |dst, x| dst.put_i8(x));
+impl_wasm_encode_copy!(U8Codec, u8, |dst, x| dst.put_u8(x));
+impl_wasm_encode_copy!(S16Codec, i16, |dst, x| dst.put_i16_leb128(x));
+impl_wasm_encode_copy!(U16Codec, u16, |dst, x| dst.put_u16_leb128(x));
+impl_wasm_encode_copy!(S32Codec, i32, |dst, x| dst.put_i32_leb128(x));
+impl_wasm_encode_copy!(U32Codec, u32, |dst, x| dst.put_u32_leb128(x));
+impl_wasm_encode_copy!(S64Codec, i64, |dst, x| dst.put_i64_leb128(x));
+impl_wasm_encode_copy!(U64Codec, u64, |dst, x| dst.put_u64_leb128(x));
+impl_wasm_encode_copy!(F32Codec, f32, |dst, x| dst.put_f32_le(x));
+impl_wasm_encode_copy!(F64Codec, f64, |dst, x| dst.put_f64_le(x));
+
+impl_wasm_encode_copy!(PrimValEncoder, bool, |dst, x| dst.put_bool(x));
+impl_wasm_encode_copy!(PrimValEncoder, i8, |dst, x| dst.put_i8(x));
+impl_wasm_encode_copy!(PrimValEncoder, u8, |dst, x| dst.put_u8(x));
+impl_wasm_encode_copy!(PrimValEncoder, i16, |dst, x| dst.put_i16_leb128(x));
+impl_wasm_encode_copy!(PrimValEncoder, u16, |dst, x| dst.put_u16_leb128(x));
+impl_wasm_encode_copy!(PrimValEncoder, i32, |dst, x| dst.put_i32_leb128(x));
+impl_wasm_encode_copy!(PrimValEncoder, u32, |dst, x| dst.put_u32_leb128(x));
+impl_wasm_encode_copy!(PrimValEncoder, i64, |dst, x| dst.put_i64_leb128(x));
+impl_wasm_encode_copy!(PrimValEncoder, u64, |dst, x| dst.put_u64_leb128(x));
+impl_wasm_encode_copy!(PrimValEncoder, f32, |dst, x| dst.put_f32_le(x));
+impl_wasm_encode_copy!(PrimValEncoder, f64, |dst, x| dst.put_f64_le(x));
+impl_wasm_encode_copy!(PrimValEncoder, char, |dst, x| dst.put_char_utf8(x));
+
+impl_wasm_encode_copy!(FlagEncoder, u8, |dst, x| dst.put_u8(x));
+impl_wasm_encode_copy!(FlagEncoder, u16, |dst, x| dst.put_u16_le(x));
+impl_wasm_encode_copy!(FlagEncoder, u32, |dst, x| dst.put_u32_le(x));
+impl_wasm_encode_copy!(FlagEncoder, u64, |dst, x| dst.put_u64_le(x));
+impl_wasm_encode_copy!(FlagEncoder, u128, |dst, x| dst.put_u128_le(x));
+
+macro_rules! impl_wasm_encode_bytes {
+    ($($t:ty),+) => {
+        $(
+            impl WasmEncode<$t> for FlagEncoder {
+                fn put_buf(&mut self, item: $t, dst: &mut impl BufMut) -> Result<(), Error> {
+                    dst.put_slice(&item);
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_wasm_encode_bytes!(Vec<u8>, &[u8], Bytes, &Bytes);
+
+macro_rules! impl_wasm_encode_str {
+    ($($t:ty),+) => {
+        $(
+            impl WasmEncode<$t> for PrimValEncoder {
+                fn put_buf(&mut self, item: $t, dst: &mut impl BufMut) -> Result<(), Error> {
+                    CoreNameEncoder.put_buf(item, dst)
+                }
+            }
+        )+
+    };
+}
+
+impl_wasm_encode_str!(
+    &str,
+    &&str,
+    String,
+    &String,
+    Box<str>,
+    &Box<str>,
+    Rc<str>,
+    &Rc<str>,
+    Arc<str>,
+    &Arc<str>,
+    Cow<'_, str>,
+    &Cow<'_, str>
+);
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct TupleEncoder<T>(pub T);
 
 #[derive(Debug)]
+#[cfg_attr(not(feature = "tokio"), allow(dead_code))]
 pub struct TupleDecoder<C, V> {
     dec: C,
     v: V,
@@ -825,6 +1276,7 @@ macro_rules! impl_tuple_codec {
             }
         }
 
+        #[cfg(feature = "tokio")]
         impl<E, $($vt, $ct),+> Encoder<($($vt),+,)> for TupleEncoder<($($ct),+,)>
         where
             E: From<std::io::Error>,
@@ -847,6 +1299,7 @@ macro_rules! impl_tuple_codec {
             }
         }
 
+        #[cfg(feature = "tokio")]
         impl<'a, E, $($vt, $ct),+> Encoder<&'a ($($vt),+,)> for TupleEncoder<($($ct),+,)>
         where
             E: From<std::io::Error>,
@@ -869,6 +1322,47 @@ macro_rules! impl_tuple_codec {
             }
         }
 
+        impl<$($vt, $ct),+> EncodedLen<($($vt),+,)> for TupleEncoder<($($ct),+,)>
+        where
+            $($ct: EncodedLen<$vt>),+
+        {
+            fn encoded_len(&self, ($($vn),+,): &($($vt),+,)) -> usize {
+                let ($(ref $cn),+,) = self.0;
+                0 $(+ $cn.encoded_len($vn))+
+            }
+        }
+
+        impl<$($vt, $ct),+> WasmEncode<($($vt),+,)> for TupleEncoder<($($ct),+,)>
+        where
+            $($ct: WasmEncode<$vt>),+
+        {
+            fn put_buf(
+                &mut self,
+                ($($vn),+,): ($($vt),+,),
+                dst: &mut impl BufMut,
+            ) -> Result<(), Error> {
+                let ($(ref mut $cn),+,) = self.0;
+                $($cn.put_buf($vn, dst)?;)+
+                Ok(())
+            }
+        }
+
+        impl<'a, $($vt, $ct),+> WasmEncode<&'a ($($vt),+,)> for TupleEncoder<($($ct),+,)>
+        where
+            $($ct: WasmEncode<&'a $vt> + EncodedLen<$vt>),+
+        {
+            fn put_buf(
+                &mut self,
+                ($($vn),+,): &'a ($($vt),+,),
+                dst: &mut impl BufMut,
+            ) -> Result<(), Error> {
+                let ($(ref mut $cn),+,) = self.0;
+                $($cn.put_buf($vn, dst)?;)+
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = "tokio")]
         impl<$($ct),+> Default for TupleDecoder<($($ct),+,), ($(Option<$ct::Item>),+,)>
         where
             $($ct: Decoder + Default),+,
@@ -881,6 +1375,7 @@ macro_rules! impl_tuple_codec {
             }
         }
 
+        #[cfg(feature = "tokio")]
         impl<E, $($ct),+> Decoder for TupleDecoder<($($ct),+,), ($(Option<$ct::Item>),+,)>
         where
             E: From<std::io::Error>,
@@ -910,6 +1405,59 @@ macro_rules! impl_tuple_codec {
                     Ok(Some(($($vn.take().unwrap()),+,)))
             }
         }
+
+        #[cfg(feature = "tokio")]
+        impl<$($ct),+> DecodeBorrowed for TupleDecoder<($($ct),+,), ($(Option<$ct::Item>),+,)>
+        where
+            $($ct: Decoder + DecodeBorrowed),+,
+        {
+            type Value<'a> = ($($ct::Value<'a>),+,);
+
+            fn decode_borrowed<'a>(
+                &self,
+                src: &'a [u8],
+            ) -> std::io::Result<Option<(Self::Value<'a>, usize)>> {
+                let ($(ref $cn),+,) = self.dec;
+                let mut n = 0;
+                $(
+                    let Some(($vn, k)) = $cn.decode_borrowed(&src[n..])? else {
+                        return Ok(None);
+                    };
+                    n += k;
+                )+
+                Ok(Some((($($vn),+,), n)))
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl<$($ct),+> SkipValue for TupleDecoder<($($ct),+,), ($(Option<$ct::Item>),+,)>
+        where
+            $($ct: Decoder + SkipValue),+,
+        {
+            fn value_len(&self, src: &[u8]) -> std::io::Result<Option<usize>> {
+                let ($(ref $cn),+,) = self.dec;
+                let mut n = 0;
+                $(
+                    let Some(k) = $cn.value_len(&src[n..])? else {
+                        return Ok(None);
+                    };
+                    n += k;
+                )+
+                Ok(Some(n))
+            }
+
+            fn min_value_len(&self, src: &[u8]) -> usize {
+                let ($(ref $cn),+,) = self.dec;
+                let mut n = 0;
+                $(
+                    match $cn.value_len(&src[n..]) {
+                        Ok(Some(k)) => n += k,
+                        _ => return n + $cn.min_value_len(&src[n..]),
+                    }
+                )+
+                n.max(src.len() + 1)
+            }
+        }
     };
 }
 
@@ -1025,9 +1573,20 @@ impl_tuple_codec!(
     C0, C1, C2, C3, C4, C5, C6, C7, C8, C9, C10, C11, C12, C13, C14, C15
 );
 
+impl<'a, C, T> EncodedLen<&'a T> for TupleEncoder<C>
+where
+    T: ?Sized,
+    Self: EncodedLen<T>,
+{
+    fn encoded_len(&self, item: &&'a T) -> usize {
+        self.encoded_len(*item)
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct OptionEncoder<T>(pub T);
 
+#[cfg(feature = "tokio")]
 impl<C, T> Encoder<Option<T>> for OptionEncoder<C>
 where
     C: Encoder<T>,
@@ -1050,6 +1609,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<'a, C, T> Encoder<&'a Option<T>> for OptionEncoder<C>
 where
     C: Encoder<&'a T>,
@@ -1072,7 +1632,46 @@ where
     }
 }
 
+impl<C, T> EncodedLen<Option<T>> for OptionEncoder<C>
+where
+    C: EncodedLen<T>,
+{
+    fn encoded_len(&self, v: &Option<T>) -> usize {
+        v.as_ref().map_or(1, |v| 1 + self.0.encoded_len(v))
+    }
+}
+
+impl<'a, C, T> EncodedLen<&'a Option<T>> for OptionEncoder<C>
+where
+    C: EncodedLen<T>,
+{
+    fn encoded_len(&self, v: &&'a Option<T>) -> usize {
+        self.encoded_len(*v)
+    }
+}
+
+impl<C, T> WasmEncode<Option<T>> for OptionEncoder<C>
+where
+    C: WasmEncode<T>,
+{
+    fn put_buf(&mut self, v: Option<T>, dst: &mut impl BufMut) -> Result<(), Error> {
+        dst.put_option_status(v.as_ref());
+        v.map_or(Ok(()), |v| self.0.put_buf(v, dst))
+    }
+}
+
+impl<'a, C, T> WasmEncode<&'a Option<T>> for OptionEncoder<C>
+where
+    C: WasmEncode<&'a T> + EncodedLen<T>,
+{
+    fn put_buf(&mut self, v: &'a Option<T>, dst: &mut impl BufMut) -> Result<(), Error> {
+        dst.put_option_status(v.as_ref());
+        v.as_ref().map_or(Ok(()), |v| self.0.put_buf(v, dst))
+    }
+}
+
 #[derive(Debug, Default)]
+#[cfg_attr(not(feature = "tokio"), allow(dead_code))]
 pub struct OptionDecoder<T> {
     dec: T,
     is_some: bool,
@@ -1093,6 +1692,7 @@ impl<T> OptionDecoder<T> {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<T> Decoder for OptionDecoder<T>
 where
     T: Decoder,
@@ -1135,6 +1735,34 @@ pub struct ResultEncoder<O, E> {
     pub err: E,
 }
 
+impl<O, E> ResultEncoder<O, E> {
+    pub fn new(ok: O, err: E) -> Self {
+        Self { ok, err }
+    }
+}
+
+impl ResultEncoder<UnitCodec, UnitCodec> {
+    /// Returns an encoder for `result` without payloads
+    pub fn empty() -> Self {
+        Self::new(UnitCodec, UnitCodec)
+    }
+}
+
+impl<O> ResultEncoder<O, UnitCodec> {
+    /// Returns an encoder for `result<T>`, i.e. `result` without an `error` payload
+    pub fn ok_only(ok: O) -> Self {
+        Self::new(ok, UnitCodec)
+    }
+}
+
+impl<E> ResultEncoder<UnitCodec, E> {
+    /// Returns an encoder for `result<_, E>`, i.e. `result` without an `ok` payload
+    pub fn err_only(err: E) -> Self {
+        Self::new(UnitCodec, err)
+    }
+}
+
+#[cfg(feature = "tokio")]
 impl<CO, O, CE, E> Encoder<Result<O, E>> for ResultEncoder<CO, CE>
 where
     CO: Encoder<O>,
@@ -1164,6 +1792,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<'a, CO, O, CE, E> Encoder<&'a Result<O, E>> for ResultEncoder<CO, CE>
 where
     CO: Encoder<&'a O>,
@@ -1193,7 +1822,59 @@ where
     }
 }
 
+impl<CO, O, CE, E> EncodedLen<Result<O, E>> for ResultEncoder<CO, CE>
+where
+    CO: EncodedLen<O>,
+    CE: EncodedLen<E>,
+{
+    fn encoded_len(&self, v: &Result<O, E>) -> usize {
+        match v {
+            Ok(v) => 1 + self.ok.encoded_len(v),
+            Err(v) => 1 + self.err.encoded_len(v),
+        }
+    }
+}
+
+impl<'a, CO, O, CE, E> EncodedLen<&'a Result<O, E>> for ResultEncoder<CO, CE>
+where
+    CO: EncodedLen<O>,
+    CE: EncodedLen<E>,
+{
+    fn encoded_len(&self, v: &&'a Result<O, E>) -> usize {
+        self.encoded_len(*v)
+    }
+}
+
+impl<CO, O, CE, E> WasmEncode<Result<O, E>> for ResultEncoder<CO, CE>
+where
+    CO: WasmEncode<O>,
+    CE: WasmEncode<E>,
+{
+    fn put_buf(&mut self, v: Result<O, E>, dst: &mut impl BufMut) -> Result<(), Error> {
+        dst.put_result_status(v.as_ref());
+        match v {
+            Ok(v) => self.ok.put_buf(v, dst),
+            Err(v) => self.err.put_buf(v, dst),
+        }
+    }
+}
+
+impl<'a, CO, O, CE, E> WasmEncode<&'a Result<O, E>> for ResultEncoder<CO, CE>
+where
+    CO: WasmEncode<&'a O> + EncodedLen<O>,
+    CE: WasmEncode<&'a E> + EncodedLen<E>,
+{
+    fn put_buf(&mut self, v: &'a Result<O, E>, dst: &mut impl BufMut) -> Result<(), Error> {
+        dst.put_result_status(v.as_ref());
+        match v {
+            Ok(v) => self.ok.put_buf(v, dst),
+            Err(v) => self.err.put_buf(v, dst),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
+#[cfg_attr(not(feature = "tokio"), allow(dead_code))]
 pub struct ResultDecoder<O, E> {
     ok: O,
     err: E,
@@ -1224,6 +1905,28 @@ impl<O, E> ResultDecoder<O, E> {
     }
 }
 
+impl ResultDecoder<UnitCodec, UnitCodec> {
+    /// Returns a decoder for `result` without payloads
+    pub fn empty() -> Self {
+        Self::new(UnitCodec, UnitCodec)
+    }
+}
+
+impl<O> ResultDecoder<O, UnitCodec> {
+    /// Returns a decoder for `result<T>`, i.e. `result` without an `error` payload
+    pub fn ok_only(ok: O) -> Self {
+        Self::new(ok, UnitCodec)
+    }
+}
+
+impl<E> ResultDecoder<UnitCodec, E> {
+    /// Returns a decoder for `result<_, E>`, i.e. `result` without an `ok` payload
+    pub fn err_only(err: E) -> Self {
+        Self::new(UnitCodec, err)
+    }
+}
+
+#[cfg(feature = "tokio")]
 impl<O, E> Decoder for ResultDecoder<O, E>
 where
     O: Decoder,
@@ -1276,13 +1979,711 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::CoreNameDecoder;
-
-    use super::*;
+/// `list<T>` decoder for LEB128-encoded integer element types, i.e. `u16`, `s16`, `u32`,
+/// `s32`, `u64` and `s64`, which decodes all buffered elements in bulk
+#[derive(Debug)]
+#[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+pub struct Leb128ListDecoder<T> {
+    ret: Vec<T>,
+    cap: usize,
+}
 
-    #[test_log::test]
+impl<T> Default for Leb128ListDecoder<T> {
+    fn default() -> Self {
+        Self {
+            ret: Vec::default(),
+            cap: 0,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> Decoder for Leb128ListDecoder<T>
+where
+    T: DecodeLeb128Bulk,
+{
+    type Item = Vec<T>;
+    type Error = std::io::Error;
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self), fields(ty = "list"))
+    )]
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.cap == 0 {
+            let Some(len) = Leb128DecoderU32.decode(src)? else {
+                return Ok(None);
+            };
+            if len == 0 {
+                return Ok(Some(Vec::default()));
+            }
+            let len: usize = len
+                .try_into()
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+            self.ret = Vec::with_capacity(len.min(src.len()));
+            self.cap = len;
+        }
+        let n = self.ret.len();
+        let k = T::decode_leb128_bulk(src, self.cap, &mut self.ret)?;
+        src.advance(k);
+        self.cap -= self.ret.len() - n;
+        if self.cap > 0 {
+            src.reserve(1);
+            return Ok(None);
+        }
+        Ok(Some(mem::take(&mut self.ret)))
+    }
+}
+
+macro_rules! impl_list_codec {
+    ($codec:ident, $t:ty, $size:literal) => {
+        #[doc = concat!("`list<", stringify!($t), ">` codec, which copies elements in bulk")]
+        #[doc = ""]
+        #[doc = "On little-endian targets encoding and decoding is a single copy of the list contents."]
+        #[derive(Debug, Default)]
+        #[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+        pub struct $codec(usize);
+
+        #[cfg(feature = "tokio")]
+        impl Encoder<&[$t]> for $codec {
+            type Error = std::io::Error;
+
+            #[cfg_attr(
+                feature = "tracing",
+                tracing::instrument(level = "trace", skip_all, fields(dst, ty = "list"))
+            )]
+            fn encode(&mut self, item: &[$t], dst: &mut BytesMut) -> Result<(), Self::Error> {
+                let n = u32::try_from(item.len())
+                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+                dst.reserve(leb128_len_u32(n) + item.len() * $size);
+                Leb128Encoder.encode(n, dst)?;
+                #[cfg(target_endian = "little")]
+                dst.extend_from_slice(bytemuck::cast_slice(item));
+                #[cfg(target_endian = "big")]
+                for x in item {
+                    dst.extend_from_slice(&x.to_le_bytes());
+                }
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl Encoder<&&[$t]> for $codec {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: &&[$t], dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode(*item, dst)
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl<const N: usize> Encoder<[$t; N]> for $codec {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: [$t; N], dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode(item.as_slice(), dst)
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl<const N: usize> Encoder<&[$t; N]> for $codec {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: &[$t; N], dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode(item.as_slice(), dst)
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl Encoder<Vec<$t>> for $codec {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: Vec<$t>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode(item.as_slice(), dst)
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl Encoder<&Vec<$t>> for $codec {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: &Vec<$t>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode(item.as_slice(), dst)
+            }
+        }
+
+        impl EncodedLen<[$t]> for $codec {
+            fn encoded_len(&self, item: &[$t]) -> usize {
+                core_vec_len_len(item.len()) + item.len() * $size
+            }
+        }
+
+        impl EncodedLen<Vec<$t>> for $codec {
+            fn encoded_len(&self, item: &Vec<$t>) -> usize {
+                self.encoded_len(item.as_slice())
+            }
+        }
+
+        impl EncodedLen<&[$t]> for $codec {
+            fn encoded_len(&self, item: &&[$t]) -> usize {
+                self.encoded_len(*item)
+            }
+        }
+
+        impl WasmEncode<&[$t]> for $codec {
+            fn put_buf(&mut self, item: &[$t], dst: &mut impl BufMut) -> Result<(), Error> {
+                let n = u32::try_from(item.len())
+                    .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+                dst.put_u32_leb128(n);
+                #[cfg(target_endian = "little")]
+                dst.put_slice(bytemuck::cast_slice(item));
+                #[cfg(target_endian = "big")]
+                for x in item {
+                    dst.put_slice(&x.to_le_bytes());
+                }
+                Ok(())
+            }
+        }
+
+        impl WasmEncode<Vec<$t>> for $codec {
+            fn put_buf(&mut self, item: Vec<$t>, dst: &mut impl BufMut) -> Result<(), Error> {
+                self.put_buf(item.as_slice(), dst)
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl Decoder for $codec {
+            type Item = Vec<$t>;
+            type Error = std::io::Error;
+
+            #[cfg_attr(
+                feature = "tracing",
+                tracing::instrument(level = "trace", skip(self), fields(ty = "list"))
+            )]
+            fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+                if self.0 == 0 {
+                    let Some(len) = Leb128DecoderU32.decode(src)? else {
+                        return Ok(None);
+                    };
+                    if len == 0 {
+                        return Ok(Some(Vec::default()));
+                    }
+                    self.0 = len
+                        .try_into()
+                        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+                }
+                let n = self.0.checked_mul($size).ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "list length overflow")
+                })?;
+                if let Some(k) = n.checked_sub(src.len()).filter(|k| *k > 0) {
+                    // the length prefix is untrusted, so only reserve a bounded amount up front
+                    const CHUNK: usize = 1 << 16;
+                    src.reserve(k.min(CHUNK));
+                    return Ok(None);
+                }
+                let mut ret = vec![<$t>::default(); self.0];
+                #[cfg(target_endian = "little")]
+                bytemuck::cast_slice_mut(&mut ret).copy_from_slice(&src[..n]);
+                #[cfg(target_endian = "big")]
+                for (x, b) in ret.iter_mut().zip(src[..n].as_chunks::<$size>().0) {
+                    *x = <$t>::from_le_bytes(*b);
+                }
+                src.advance(n);
+                self.0 = 0;
+                Ok(Some(ret))
+            }
+        }
+    };
+}
+
+impl_list_codec!(U8ListCodec, u8, 1);
+impl_list_codec!(S8ListCodec, i8, 1);
+impl_list_codec!(F32ListCodec, f32, 4);
+impl_list_codec!(F64ListCodec, f64, 8);
+
+macro_rules! impl_decode_borrowed_delegate {
+    ($dec:ident, $t:ty, $inner:expr) => {
+        impl DecodeBorrowed for $dec {
+            type Value<'a> = $t;
+
+            fn decode_borrowed<'a>(
+                &self,
+                src: &'a [u8],
+            ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+                $inner.decode_borrowed(src)
+            }
+        }
+    };
+}
+
+impl_decode_borrowed_delegate!(S16Codec, i16, Leb128DecoderI16);
+impl_decode_borrowed_delegate!(U16Codec, u16, Leb128DecoderU16);
+impl_decode_borrowed_delegate!(S32Codec, i32, Leb128DecoderI32);
+impl_decode_borrowed_delegate!(U32Codec, u32, Leb128DecoderU32);
+impl_decode_borrowed_delegate!(S64Codec, i64, Leb128DecoderI64);
+impl_decode_borrowed_delegate!(U64Codec, u64, Leb128DecoderU64);
+impl_decode_borrowed_delegate!(U8ListCodec, &'a [u8], CoreVecDecoderBytes::default());
+
+macro_rules! impl_decode_borrowed_le {
+    ($dec:ident, $t:ty) => {
+        impl DecodeBorrowed for $dec {
+            type Value<'a> = $t;
+
+            fn decode_borrowed<'a>(
+                &self,
+                src: &'a [u8],
+            ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+                let Some((buf, _)) = src.split_first_chunk() else {
+                    return Ok(None);
+                };
+                Ok(Some((<$t>::from_le_bytes(*buf), size_of::<$t>())))
+            }
+        }
+    };
+}
+
+impl_decode_borrowed_le!(S8Codec, i8);
+impl_decode_borrowed_le!(U8Codec, u8);
+impl_decode_borrowed_le!(F32Codec, f32);
+impl_decode_borrowed_le!(F64Codec, f64);
+
+impl DecodeBorrowed for BoolCodec {
+    type Value<'a> = bool;
+
+    fn decode_borrowed<'a>(
+        &self,
+        mut src: &'a [u8],
+    ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        Ok(src.get_bool()?.map(|v| (v, 1)))
+    }
+}
+
+impl DecodeBorrowed for UnitCodec {
+    type Value<'a> = ();
+
+    fn decode_borrowed<'a>(&self, _: &'a [u8]) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        Ok(Some(((), 0)))
+    }
+}
+
+impl<const N: usize> DecodeBorrowed for FlagDecoder<N> {
+    type Value<'a> = &'a [u8];
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        let n = N.div_ceil(8);
+        Ok(src.get(..n).map(|buf| (buf, n)))
+    }
+}
+
+impl DecodeBorrowed for S8ListCodec {
+    type Value<'a> = &'a [i8];
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        let Some((buf, n)) = CoreVecDecoderBytes::default().decode_borrowed(src)? else {
+            return Ok(None);
+        };
+        Ok(Some((bytemuck::cast_slice(buf), n)))
+    }
+}
+
+impl<T> DecodeBorrowed for OptionDecoder<T>
+where
+    T: DecodeBorrowed,
+{
+    type Value<'a> = Option<T::Value<'a>>;
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        match (&src[..]).get_option_status()? {
+            None => Ok(None),
+            Some(false) => Ok(Some((None, 1))),
+            Some(true) => {
+                let Some((v, n)) = self.dec.decode_borrowed(&src[1..])? else {
+                    return Ok(None);
+                };
+                Ok(Some((Some(v), n + 1)))
+            }
+        }
+    }
+}
+
+impl<O, E> DecodeBorrowed for ResultDecoder<O, E>
+where
+    O: DecodeBorrowed,
+    E: DecodeBorrowed,
+{
+    type Value<'a> = Result<O::Value<'a>, E::Value<'a>>;
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        match (&src[..]).get_result_status()? {
+            None => Ok(None),
+            Some(true) => {
+                let Some((v, n)) = self.ok.decode_borrowed(&src[1..])? else {
+                    return Ok(None);
+                };
+                Ok(Some((Ok(v), n + 1)))
+            }
+            Some(false) => {
+                let Some((v, n)) = self.err.decode_borrowed(&src[1..])? else {
+                    return Ok(None);
+                };
+                Ok(Some((Err(v), n + 1)))
+            }
+        }
+    }
+}
+
+impl<T> DecodeBorrowed for Leb128ListDecoder<T>
+where
+    T: DecodeLeb128Bulk,
+{
+    type Value<'a> = Vec<T>;
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        let Some((len, n)) = decode_core_vec_len(src)? else {
+            return Ok(None);
+        };
+        let mut ret = Vec::with_capacity(len.min(src.len() - n));
+        let k = T::decode_leb128_bulk(&src[n..], len, &mut ret)?;
+        if ret.len() < len {
+            return Ok(None);
+        }
+        Ok(Some((ret, n + k)))
+    }
+}
+
+macro_rules! impl_skip_value_borrowed {
+    ($($dec:ty),+ $(,)?) => {
+        $(
+            impl SkipValue for $dec {
+                fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error> {
+                    Ok(self.decode_borrowed(src)?.map(|(_, n)| n))
+                }
+            }
+        )+
+    };
+}
+
+impl_skip_value_borrowed!(
+    UnitCodec, BoolCodec, S8Codec, U8Codec, S16Codec, U16Codec, S32Codec, U32Codec, S64Codec,
+    U64Codec, F32Codec, F64Codec,
+);
+
+impl<const N: usize> SkipValue for FlagDecoder<N> {
+    fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error> {
+        Ok(self.decode_borrowed(src)?.map(|(_, n)| n))
+    }
+
+    fn min_value_len(&self, _: &[u8]) -> usize {
+        N.div_ceil(8)
+    }
+}
+
+macro_rules! impl_skip_value_list {
+    ($codec:ident, $size:literal) => {
+        impl SkipValue for $codec {
+            fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error> {
+                let Some((len, n)) = decode_core_vec_len(src)? else {
+                    return Ok(None);
+                };
+                let n = len
+                    .checked_mul($size)
+                    .and_then(|len| len.checked_add(n))
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "list length overflow"))?;
+                Ok((n <= src.len()).then_some(n))
+            }
+
+            fn min_value_len(&self, src: &[u8]) -> usize {
+                min_core_vec_len(src, $size)
+            }
+        }
+    };
+}
+
+impl_skip_value_list!(U8ListCodec, 1);
+impl_skip_value_list!(S8ListCodec, 1);
+impl_skip_value_list!(F32ListCodec, 4);
+impl_skip_value_list!(F64ListCodec, 8);
+
+impl<T> SkipValue for OptionDecoder<T>
+where
+    T: SkipValue,
+{
+    fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error> {
+        match (&src[..]).get_option_status()? {
+            None => Ok(None),
+            Some(false) => Ok(Some(1)),
+            Some(true) => Ok(self.dec.value_len(&src[1..])?.map(|n| n + 1)),
+        }
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        match (&src[..]).get_option_status() {
+            Ok(Some(true)) => 1 + self.dec.min_value_len(&src[1..]),
+            _ => src.len() + 1,
+        }
+    }
+}
+
+impl<O, E> SkipValue for ResultDecoder<O, E>
+where
+    O: SkipValue,
+    E: SkipValue,
+{
+    fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error> {
+        match (&src[..]).get_result_status()? {
+            None => Ok(None),
+            Some(true) => Ok(self.ok.value_len(&src[1..])?.map(|n| n + 1)),
+            Some(false) => Ok(self.err.value_len(&src[1..])?.map(|n| n + 1)),
+        }
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        match (&src[..]).get_result_status() {
+            Ok(Some(true)) => 1 + self.ok.min_value_len(&src[1..]),
+            Ok(Some(false)) => 1 + self.err.min_value_len(&src[1..]),
+            _ => src.len() + 1,
+        }
+    }
+}
+
+impl<T> SkipValue for Leb128ListDecoder<T>
+where
+    T: DecodeLeb128Bulk,
+{
+    fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error> {
+        const CHUNK: usize = 64;
+
+        let Some((mut len, mut n)) = decode_core_vec_len(src)? else {
+            return Ok(None);
+        };
+        let mut buf = Vec::with_capacity(len.min(CHUNK));
+        while len > 0 {
+            buf.clear();
+            n += T::decode_leb128_bulk(&src[n..], len.min(CHUNK), &mut buf)?;
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            len -= buf.len();
+        }
+        Ok(Some(n))
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        const CHUNK: usize = 64;
+
+        let Ok(Some((mut len, mut n))) = decode_core_vec_len(src) else {
+            return src.len() + 1;
+        };
+        // each remaining element is encoded in at least one byte
+        let mut buf = Vec::with_capacity(len.min(CHUNK));
+        while len > 0 {
+            buf.clear();
+            match T::decode_leb128_bulk(&src[n..], len.min(CHUNK), &mut buf) {
+                Ok(k) if !buf.is_empty() => n += k,
+                _ => break,
+            }
+            len -= buf.len();
+        }
+        n.saturating_add(len).max(src.len() + 1)
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use leb128_tokio::BufLeb128Ext as _;
+    use utf8_tokio::BufUtf8Ext as _;
+
+    use crate::{
+        BufCoreExt as _, BufMutCoreExt as _, CoreNameDecoder, CoreVecDecoder, CoreVecEncoder,
+        RawValueDecoder, SkipDecoder,
+    };
+
+    use super::*;
+
+    #[test_log::test]
+    fn buf() {
+        let mut buf = vec![];
+        buf.put_bool(true);
+        buf.put_option_status(Some(()));
+        buf.put_result_status(Result::<(), ()>::Err(()));
+        buf.put_i16_leb128(-2);
+        buf.put_f32_le(1.5);
+        buf.put_char_utf8('И');
+        buf.put_core_name("test").expect("failed to put `test`");
+
+        let mut buf = Bytes::from(buf);
+        assert_eq!(buf.get_bool().unwrap(), Some(true));
+        assert_eq!(buf.get_option_status().unwrap(), Some(true));
+        assert_eq!(buf.get_result_status().unwrap(), Some(false));
+        assert_eq!(buf.get_i16_leb128().unwrap(), Some(-2));
+        assert_eq!(buf.get_f32_le(), 1.5);
+        assert_eq!(buf.get_char_utf8().unwrap(), Some('И'));
+        assert_eq!(buf.get_core_name().unwrap().as_deref(), Some("test"));
+        assert_eq!(buf.get_bool().unwrap(), None);
+
+        [0x02]
+            .as_slice()
+            .get_bool()
+            .expect_err("bool get should have failed, since it is not 0 or 1");
+    }
+
+    #[test_log::test]
+    fn bulk_list() {
+        let values: Vec<f32> = (0..1000u16).map(|i| f32::from(i) * -0.5).collect();
+        let mut buf = BytesMut::default();
+        F32ListCodec::default()
+            .encode(values.as_slice(), &mut buf)
+            .expect("failed to encode list");
+        let mut expected = BytesMut::default();
+        crate::CoreVecEncoder(F32Codec)
+            .encode(values.as_slice(), &mut expected)
+            .expect("failed to encode list");
+        assert_eq!(buf, expected);
+        assert_eq!(
+            F32ListCodec::default().encoded_len(values.as_slice()),
+            buf.len()
+        );
+
+        let mut dec = F32ListCodec::default();
+        let mut src = buf.split_to(buf.len() - 1);
+        assert_eq!(dec.decode(&mut src).expect("failed to decode list"), None);
+        src.unsplit(buf);
+        let v = dec.decode(&mut src).expect("failed to decode list");
+        assert_eq!(v, Some(values));
+        assert!(src.is_empty());
+
+        let mut buf = BytesMut::default();
+        F64ListCodec::default()
+            .encode([1.5, f64::MIN, f64::INFINITY], &mut buf)
+            .expect("failed to encode list");
+        S8ListCodec::default()
+            .encode(&[-1, 2, i8::MIN], &mut buf)
+            .expect("failed to encode list");
+        U8ListCodec::default()
+            .encode(vec![], &mut buf)
+            .expect("failed to encode list");
+        let v = F64ListCodec::default()
+            .decode(&mut buf)
+            .expect("failed to decode list");
+        assert_eq!(v, Some(vec![1.5, f64::MIN, f64::INFINITY]));
+        assert_eq!(buf.as_ref(), b"\x03\xff\x02\x80\0");
+        let v = S8ListCodec::default()
+            .decode(&mut buf)
+            .expect("failed to decode list");
+        assert_eq!(v, Some(vec![-1, 2, i8::MIN]));
+        let v = U8ListCodec::default()
+            .decode(&mut buf)
+            .expect("failed to decode list");
+        assert_eq!(v, Some(vec![]));
+
+        let mut buf = BytesMut::from(b"\xff\xff\xff\xff\x0f".as_slice());
+        let v = F64ListCodec::default()
+            .decode(&mut buf)
+            .expect("failed to decode list");
+        assert_eq!(v, None);
+        assert!(buf.capacity() < 1 << 20);
+    }
+
+    #[test_log::test]
+    fn leb128_list() {
+        let mut buf = BytesMut::from(b"\xff\xff\xff\xff\x0f".as_slice());
+        let mut dec = Leb128ListDecoder::<u64>::default();
+        let v = dec.decode(&mut buf).expect("failed to decode list");
+        assert_eq!(v, None);
+        assert!(dec.ret.capacity() < 5);
+        assert!(buf.capacity() < 1 << 20);
+
+        let values: Vec<i64> = (-300..300).map(|i| i * i * i).collect();
+        let mut buf = BytesMut::default();
+        crate::CoreVecEncoder(S64Codec)
+            .encode(values.as_slice(), &mut buf)
+            .expect("failed to encode list");
+        let mut dec = Leb128ListDecoder::<i64>::default();
+        let mut src = BytesMut::default();
+        for chunk in buf.chunks(7) {
+            src.extend_from_slice(chunk);
+            if let Some(v) = dec.decode(&mut src).expect("failed to decode list") {
+                assert_eq!(v, values);
+                assert!(src.is_empty());
+                return;
+            }
+        }
+        panic!("short list read");
+    }
+
+    #[test_log::test]
+    fn encoded_len() {
+        let mut enc = TupleEncoder((
+            BoolCodec,
+            PrimValEncoder,
+            CoreNameEncoder,
+            S64Codec,
+            OptionEncoder(F64Codec),
+            ResultEncoder {
+                ok: FlagEncoder,
+                err: CoreNameEncoder,
+            },
+            crate::CoreVecEncoder(PrimValEncoder),
+        ));
+        let v = (
+            true,
+            'И',
+            "test",
+            -0x80i64,
+            Some(1.0f64),
+            Result::<u16, &str>::Ok(0xffff),
+            vec!["foo", "ƒ𐍈Ő"],
+        );
+        let n = enc.encoded_len(&v);
+        let mut buf = BytesMut::default();
+        enc.encode(&v, &mut buf).expect("failed to encode tuple");
+        assert_eq!(n, buf.len());
+        assert_eq!(n, 1 + 2 + 5 + 2 + 9 + 3 + 14);
+
+        let mut fixed = [0; 36];
+        let mut dst = fixed.as_mut_slice();
+        enc.encode_buf(&v, &mut dst)
+            .expect("failed to encode tuple into slice");
+        assert!(dst.is_empty());
+        assert_eq!(fixed.as_slice(), buf);
+        enc.encode_buf(&v, &mut [0; 35].as_mut_slice())
+            .expect_err("encoding should have failed due to insufficient capacity");
+
+        let mut fixed = [0; 11];
+        let mut dst = fixed.as_mut_slice();
+        F32ListCodec::default()
+            .encode_buf([1.5f32, -2.0].as_slice(), &mut dst)
+            .expect("failed to encode list into slice");
+        U8ListCodec::default()
+            .encode_buf(vec![0x2a], &mut dst)
+            .expect("failed to encode list into slice");
+        assert!(dst.is_empty());
+        let mut buf = BytesMut::default();
+        F32ListCodec::default()
+            .encode([1.5f32, -2.0].as_slice(), &mut buf)
+            .expect("failed to encode list");
+        U8ListCodec::default()
+            .encode(vec![0x2a], &mut buf)
+            .expect("failed to encode list");
+        assert_eq!(fixed.as_slice(), buf);
+    }
+
+    #[test_log::test]
     fn tuple() {
         let mut buf = BytesMut::default();
         TupleEncoder((
@@ -1326,4 +2727,219 @@ mod tests {
         assert_eq!(d, 0x42);
         assert_eq!(e, Ok(true));
     }
+
+    #[test_log::test]
+    fn borrowed() {
+        let mut buf = BytesMut::default();
+        TupleEncoder((
+            U32Codec,
+            CoreNameEncoder,
+            OptionEncoder(U8ListCodec::default()),
+            ResultEncoder {
+                ok: BoolCodec,
+                err: CoreNameEncoder,
+            },
+            CoreVecEncoder(CoreNameEncoder),
+        ))
+        .encode(
+            (
+                0x42u32,
+                "foo",
+                Some(b"\x01\x02".as_slice()),
+                Result::<bool, _>::Err("bar"),
+                ["baz", "ƒ𐍈Ő"],
+            ),
+            &mut buf,
+        )
+        .expect("failed to encode tuple");
+        let dec = TupleDecoder::<
+            (
+                U32Codec,
+                CoreNameDecoder,
+                OptionDecoder<U8ListCodec>,
+                ResultDecoder<BoolCodec, CoreNameDecoder>,
+                CoreVecDecoder<CoreNameDecoder>,
+            ),
+            _,
+        >::default();
+        let ((a, b, c, d, e), n) = dec
+            .decode_borrowed(&buf)
+            .expect("failed to decode tuple")
+            .expect("short tuple read");
+        assert_eq!(n, buf.len());
+        assert_eq!(a, 0x42);
+        assert_eq!(b, "foo");
+        assert_eq!(c, Some(b"\x01\x02".as_slice()));
+        assert_eq!(d, Err("bar"));
+        assert_eq!(e, ["baz", "ƒ𐍈Ő"]);
+        assert!(b.as_ptr() >= buf.as_ptr() && b.as_ptr() < buf[buf.len()..].as_ptr());
+
+        for i in 0..buf.len() {
+            assert!(dec
+                .decode_borrowed(&buf[..i])
+                .expect("failed to decode short tuple")
+                .is_none());
+        }
+
+        let v = FlagDecoder::<9>
+            .decode_borrowed(b"\x01\x02\x03")
+            .expect("failed to decode flags");
+        assert_eq!(v, Some((b"\x01\x02".as_slice(), 2)));
+        let v = S8ListCodec::default()
+            .decode_borrowed(b"\x02\xff\x01")
+            .expect("failed to decode list");
+        assert_eq!(v, Some(([-1i8, 1].as_slice(), 3)));
+        let v = Leb128ListDecoder::<u32>::default()
+            .decode_borrowed(b"\x02\x80\x01\x02")
+            .expect("failed to decode list");
+        assert_eq!(v, Some((vec![0x80, 2], 4)));
+        BoolCodec
+            .decode_borrowed(b"\x02")
+            .expect_err("bool decode should have failed, since 2 is not a valid bool");
+        OptionDecoder::<U8Codec>::default()
+            .decode_borrowed(b"\x02")
+            .expect_err("option decode should have failed, since 2 is not a valid status");
+        CoreNameDecoder::default()
+            .decode_borrowed(b"\x01\xff")
+            .expect_err("name decode should have failed, since it is not valid UTF-8");
+        U32Codec
+            .decode_borrowed(b"\xff\xff\xff\xff\x1f")
+            .expect_err("u32 decode should have failed, since it encoded 35 bits");
+
+        fn assert_error_kind<T>(mut dec: T, src: &[u8])
+        where
+            T: Decoder<Error = std::io::Error> + DecodeBorrowed,
+            T::Item: std::fmt::Debug,
+            for<'a> T::Value<'a>: std::fmt::Debug,
+        {
+            let borrowed = dec
+                .decode_borrowed(src)
+                .expect_err("borrowed decode should have failed");
+            let owned = dec
+                .decode(&mut BytesMut::from(src))
+                .expect_err("owned decode should have failed");
+            assert_eq!(borrowed.kind(), owned.kind(), "{src:02x?}");
+        }
+        assert_error_kind(CoreNameDecoder::default(), b"\x01\xff");
+        assert_error_kind(
+            CoreVecDecoder::<CoreNameDecoder>::default(),
+            b"\x01\x01\xff",
+        );
+        assert_error_kind(BoolCodec, b"\x02");
+        assert_error_kind(OptionDecoder::<U8Codec>::default(), b"\x02");
+        assert_error_kind(utf8_tokio::Utf8Codec, b"\xff");
+        assert_error_kind(U32Codec, b"\xff\xff\xff\xff\x1f");
+    }
+
+    #[test_log::test]
+    fn skip() {
+        let mut buf = BytesMut::default();
+        TupleEncoder((
+            OptionEncoder(F32Codec),
+            ResultEncoder {
+                ok: Leb128Encoder,
+                err: CoreNameEncoder,
+            },
+            F64ListCodec::default(),
+            FlagEncoder,
+        ))
+        .encode(
+            (
+                Some(1.5f32),
+                Result::<u64, &str>::Ok(u64::MAX),
+                [1.0f64, 2.0],
+                0x0102u16,
+            ),
+            &mut buf,
+        )
+        .expect("failed to encode tuple");
+        let mut list = BytesMut::default();
+        CoreVecEncoder(Leb128Encoder)
+            .encode((0..200u32).map(|i| i << 10).collect::<Vec<_>>(), &mut list)
+            .expect("failed to encode list");
+        buf.extend_from_slice(&list);
+        let expected = buf.clone();
+
+        let mut dec = RawValueDecoder::new(TupleDecoder::<
+            (
+                OptionDecoder<F32Codec>,
+                ResultDecoder<U64Codec, CoreNameDecoder>,
+                F64ListCodec,
+                FlagDecoder<16>,
+            ),
+            _,
+        >::default());
+        for i in 0..expected.len() - list.len() {
+            let v = dec
+                .decode(&mut BytesMut::from(&expected[..i]))
+                .expect("failed to capture short tuple");
+            assert_eq!(v, None);
+        }
+        let v = dec
+            .decode(&mut buf)
+            .expect("failed to capture tuple")
+            .expect("short tuple read");
+        assert_eq!(v, expected[..expected.len() - list.len()]);
+
+        let v = Leb128ListDecoder::<u32>::default()
+            .value_len(&buf[..buf.len() - 1])
+            .expect("failed to skip short list");
+        assert_eq!(v, None);
+        SkipDecoder::new(Leb128ListDecoder::<u32>::default())
+            .decode(&mut buf)
+            .expect("failed to skip list")
+            .expect("short list read");
+        assert!(buf.is_empty());
+
+        SkipDecoder::new(ResultDecoder::<BoolCodec, BoolCodec>::default())
+            .decode(&mut BytesMut::from(b"\x02\x01".as_slice()))
+            .expect_err("result skip should have failed, since 2 is not a valid status");
+
+        let mut buf = BytesMut::from(b"\xff\xff\xff\xff\x0f".as_slice());
+        let v = SkipDecoder::new(F64ListCodec::default())
+            .decode(&mut buf)
+            .expect("failed to skip list");
+        assert_eq!(v, None);
+        assert!(buf.capacity() < 1 << 20);
+    }
+
+    #[test_log::test]
+    fn unit() {
+        let mut buf = BytesMut::default();
+        ResultEncoder::empty()
+            .encode(Result::<(), ()>::Err(()), &mut buf)
+            .expect("failed to encode `result`");
+        ResultEncoder::ok_only(U32Codec)
+            .encode(Result::<_, ()>::Ok(42u32), &mut buf)
+            .expect("failed to encode `result<u32>`");
+        ResultEncoder::err_only(CoreNameEncoder)
+            .encode(&Result::<(), _>::Err("foo"), &mut buf)
+            .expect("failed to encode `result<_, string>`");
+        TupleEncoder((UnitCodec, BoolCodec))
+            .encode(((), true), &mut buf)
+            .expect("failed to encode tuple");
+        assert_eq!(buf.as_ref(), b"\x01\0\x2a\x01\x03foo\x01");
+        assert_eq!(
+            ResultEncoder::ok_only(U32Codec).encoded_len(&Result::<u32, ()>::Err(())),
+            1
+        );
+
+        let v = ResultDecoder::empty()
+            .decode(&mut buf)
+            .expect("failed to decode `result`");
+        assert_eq!(v, Some(Err(())));
+        let v = ResultDecoder::ok_only(U32Codec)
+            .decode(&mut buf)
+            .expect("failed to decode `result<u32>`");
+        assert_eq!(v, Some(Ok(42)));
+        let v = ResultDecoder::err_only(CoreNameDecoder::default())
+            .decode(&mut buf)
+            .expect("failed to decode `result<_, string>`");
+        assert_eq!(v, Some(Err("foo".to_string())));
+        let v = TupleDecoder::<(UnitCodec, BoolCodec), _>::default()
+            .decode(&mut buf)
+            .expect("failed to decode tuple");
+        assert_eq!(v, Some(((), true)));
+        assert!(buf.is_empty());
+    }
 }
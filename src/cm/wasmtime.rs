@@ -0,0 +1,576 @@
+//! Codec for [`wasmtime`] component model values.
+//!
+//! Values are encoded according to their [`Type`]. Handle values, i.e. resources, futures,
+//! streams and error contexts, cannot be represented in the binary encoding. Instead, they are
+//! stored in a handle table and encoded as a `u32` index into it.
+//!
+//! Decoding an owned handle, i.e. `own`, `future`, `stream` or `error-context`, takes it out of
+//! the table, so every owned handle can be decoded at most once. Only `borrow` handles may be
+//! referenced multiple times.
+
+use std::collections::BTreeSet;
+
+use ::wasmtime::component::{Type, Val};
+use tokio_util::bytes::{Buf as _, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+use utf8_tokio::Utf8Codec;
+
+use crate::cm::dynamic::{
+    decode, decode_flags, encode_discriminant, encode_flags, encode_len, encode_payload,
+    invalid_discriminant, type_mismatch, unknown_case,
+};
+use crate::cm::{
+    BoolCodec, BufMutValueExt as _, BufValueExt as _, F32Codec, F64Codec, S16Codec, S32Codec,
+    S64Codec, S8Codec, U16Codec, U32Codec, U64Codec, U8Codec,
+};
+use crate::{decode_core_vec_len, CoreNameDecoder, CoreNameEncoder, DecodeBorrowed};
+
+/// Returns `true` if handle value `v` is a valid value of type `ty`
+fn is_handle_of(ty: &Type, v: &Val) -> bool {
+    match (ty, v) {
+        (Type::Own(ty), Val::Resource(v)) => v.owned() && v.ty() == *ty,
+        (Type::Borrow(ty), Val::Resource(v)) => !v.owned() && v.ty() == *ty,
+        (Type::Future(..), Val::Future(..))
+        | (Type::Stream(..), Val::Stream(..))
+        | (Type::ErrorContext, Val::ErrorContext(..)) => true,
+        _ => false,
+    }
+}
+
+/// Encodes `v` of type `ty`.
+///
+/// Handle values are appended to `handles` and encoded as their index in it.
+/// On error, both `handles` and `dst` are left untouched.
+pub fn encode_val(
+    v: &Val,
+    ty: &Type,
+    handles: &mut Vec<Option<Val>>,
+    dst: &mut BytesMut,
+) -> std::io::Result<()> {
+    let handles_start = handles.len();
+    let start = dst.len();
+    if let Err(err) = put_val(v, ty, handles, dst) {
+        handles.truncate(handles_start);
+        dst.truncate(start);
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Like [`encode_val`], but leaves a partially encoded value on error
+fn put_val(
+    v: &Val,
+    ty: &Type,
+    handles: &mut Vec<Option<Val>>,
+    dst: &mut BytesMut,
+) -> std::io::Result<()> {
+    match (ty, v) {
+        (Type::Bool, Val::Bool(v)) => BoolCodec.encode(v, dst),
+        (Type::S8, Val::S8(v)) => S8Codec.encode(v, dst),
+        (Type::U8, Val::U8(v)) => U8Codec.encode(v, dst),
+        (Type::S16, Val::S16(v)) => S16Codec.encode(v, dst),
+        (Type::U16, Val::U16(v)) => U16Codec.encode(v, dst),
+        (Type::S32, Val::S32(v)) => S32Codec.encode(v, dst),
+        (Type::U32, Val::U32(v)) => U32Codec.encode(v, dst),
+        (Type::S64, Val::S64(v)) => S64Codec.encode(v, dst),
+        (Type::U64, Val::U64(v)) => U64Codec.encode(v, dst),
+        (Type::Float32, Val::Float32(v)) => F32Codec.encode(v, dst),
+        (Type::Float64, Val::Float64(v)) => F64Codec.encode(v, dst),
+        (Type::Char, Val::Char(v)) => Utf8Codec.encode(v, dst),
+        (Type::String, Val::String(v)) => CoreNameEncoder.encode(v, dst),
+        (Type::List(ty), Val::List(vs)) => {
+            let ty = ty.ty();
+            encode_len(vs.len(), dst)?;
+            for v in vs {
+                put_val(v, &ty, handles, dst)?;
+            }
+            Ok(())
+        }
+        (Type::Record(ty), Val::Record(vs)) if ty.fields().len() == vs.len() => {
+            for field in ty.fields() {
+                let (_, v) = vs.iter().find(|(k, _)| k == field.name).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("missing record field `{}`", field.name),
+                    )
+                })?;
+                put_val(v, &field.ty, handles, dst)?;
+            }
+            Ok(())
+        }
+        (Type::Tuple(ty), Val::Tuple(vs)) if ty.types().len() == vs.len() => {
+            for (ty, v) in ty.types().zip(vs) {
+                put_val(v, &ty, handles, dst)?;
+            }
+            Ok(())
+        }
+        (Type::Variant(ty), Val::Variant(name, v)) => {
+            let i = encode_discriminant(ty.cases().map(|case| case.name), name, dst)?;
+            let case = ty.cases().nth(i).ok_or_else(|| unknown_case(name))?;
+            encode_payload(case.ty.as_ref(), v.as_deref(), |ty, v| {
+                put_val(v, ty, handles, dst)
+            })
+        }
+        (Type::Enum(ty), Val::Enum(name)) => {
+            encode_discriminant(ty.names(), name, dst)?;
+            Ok(())
+        }
+        (Type::Option(ty), Val::Option(v)) => {
+            dst.reserve(1);
+            dst.put_option_status(v.as_ref());
+            if let Some(v) = v {
+                put_val(v, &ty.ty(), handles, dst)?;
+            }
+            Ok(())
+        }
+        (Type::Result(ty), Val::Result(v)) => {
+            dst.reserve(1);
+            dst.put_result_status(v.as_ref());
+            match v {
+                Ok(v) => encode_payload(ty.ok().as_ref(), v.as_deref(), |ty, v| {
+                    put_val(v, ty, handles, dst)
+                }),
+                Err(v) => encode_payload(ty.err().as_ref(), v.as_deref(), |ty, v| {
+                    put_val(v, ty, handles, dst)
+                }),
+            }
+        }
+        (Type::Flags(ty), Val::Flags(vs)) => encode_flags(|| ty.names(), vs, dst),
+        (ty, v) if is_handle_of(ty, v) => {
+            encode_len(handles.len(), dst)?;
+            handles.push(Some(v.clone()));
+            Ok(())
+        }
+        _ => Err(type_mismatch(ty, v)),
+    }
+}
+
+fn decode_payload(
+    ty: Option<&Type>,
+    handles: &[Option<Val>],
+    taken: &mut BTreeSet<usize>,
+    src: &[u8],
+) -> std::io::Result<Option<(Option<Box<Val>>, usize)>> {
+    let Some(ty) = ty else {
+        return Ok(Some((None, 0)));
+    };
+    let Some((v, n)) = get_val(ty, handles, taken, src)? else {
+        return Ok(None);
+    };
+    Ok(Some((Some(Box::new(v)), n)))
+}
+
+/// Decodes a value of type `ty` from `src`.
+/// Returns the value and number of bytes consumed or [`None`] if more data is needed.
+///
+/// Handle values are looked up by index in `handles`. Owned handles are taken out of `handles`
+/// once the whole value is decoded and decoding fails if one is referenced more than once.
+pub fn decode_val(
+    ty: &Type,
+    handles: &mut [Option<Val>],
+    src: &[u8],
+) -> std::io::Result<Option<(Val, usize)>> {
+    let mut taken = BTreeSet::new();
+    let Some((v, n)) = get_val(ty, handles, &mut taken, src)? else {
+        return Ok(None);
+    };
+    for i in taken {
+        handles[i] = None;
+    }
+    Ok(Some((v, n)))
+}
+
+/// Like [`decode_val`], but leaves `handles` untouched and records indices of owned handles in
+/// `taken` instead
+fn get_val(
+    ty: &Type,
+    handles: &[Option<Val>],
+    taken: &mut BTreeSet<usize>,
+    src: &[u8],
+) -> std::io::Result<Option<(Val, usize)>> {
+    macro_rules! decode_val {
+        ($ty:expr, $src:expr) => {{
+            let Some((v, n)) = get_val($ty, handles, taken, $src)? else {
+                return Ok(None);
+            };
+            (v, n)
+        }};
+    }
+
+    let (v, n) = match ty {
+        Type::Bool => {
+            let (v, n) = decode!(BoolCodec, src);
+            (Val::Bool(v), n)
+        }
+        Type::S8 => {
+            let (v, n) = decode!(S8Codec, src);
+            (Val::S8(v), n)
+        }
+        Type::U8 => {
+            let (v, n) = decode!(U8Codec, src);
+            (Val::U8(v), n)
+        }
+        Type::S16 => {
+            let (v, n) = decode!(S16Codec, src);
+            (Val::S16(v), n)
+        }
+        Type::U16 => {
+            let (v, n) = decode!(U16Codec, src);
+            (Val::U16(v), n)
+        }
+        Type::S32 => {
+            let (v, n) = decode!(S32Codec, src);
+            (Val::S32(v), n)
+        }
+        Type::U32 => {
+            let (v, n) = decode!(U32Codec, src);
+            (Val::U32(v), n)
+        }
+        Type::S64 => {
+            let (v, n) = decode!(S64Codec, src);
+            (Val::S64(v), n)
+        }
+        Type::U64 => {
+            let (v, n) = decode!(U64Codec, src);
+            (Val::U64(v), n)
+        }
+        Type::Float32 => {
+            let (v, n) = decode!(F32Codec, src);
+            (Val::Float32(v), n)
+        }
+        Type::Float64 => {
+            let (v, n) = decode!(F64Codec, src);
+            (Val::Float64(v), n)
+        }
+        Type::Char => {
+            let (v, n) = decode!(Utf8Codec, src);
+            (Val::Char(v), n)
+        }
+        Type::String => {
+            let (v, n) = decode!(CoreNameDecoder::default(), src);
+            (Val::String(v.to_string()), n)
+        }
+        Type::List(ty) => {
+            let Some((len, mut n)) = decode_core_vec_len(src)? else {
+                return Ok(None);
+            };
+            let ty = ty.ty();
+            let mut vs = Vec::with_capacity(len.min(src.len() - n));
+            for _ in 0..len {
+                let (v, k) = decode_val!(&ty, &src[n..]);
+                vs.push(v);
+                n += k;
+            }
+            (Val::List(vs), n)
+        }
+        Type::Record(ty) => {
+            let mut n = 0;
+            let mut vs = Vec::with_capacity(ty.fields().len());
+            for field in ty.fields() {
+                let (v, k) = decode_val!(&field.ty, &src[n..]);
+                vs.push((field.name.to_string(), v));
+                n += k;
+            }
+            (Val::Record(vs), n)
+        }
+        Type::Tuple(ty) => {
+            let mut n = 0;
+            let mut vs = Vec::with_capacity(ty.types().len());
+            for ty in ty.types() {
+                let (v, k) = decode_val!(&ty, &src[n..]);
+                vs.push(v);
+                n += k;
+            }
+            (Val::Tuple(vs), n)
+        }
+        Type::Variant(ty) => {
+            let (disc, n) = decode!(U32Codec, src);
+            let case = usize::try_from(disc)
+                .ok()
+                .and_then(|i| ty.cases().nth(i))
+                .ok_or_else(|| invalid_discriminant(disc))?;
+            let Some((v, k)) = decode_payload(case.ty.as_ref(), handles, taken, &src[n..])? else {
+                return Ok(None);
+            };
+            (Val::Variant(case.name.to_string(), v), n + k)
+        }
+        Type::Enum(ty) => {
+            let (disc, n) = decode!(U32Codec, src);
+            let name = usize::try_from(disc)
+                .ok()
+                .and_then(|i| ty.names().nth(i))
+                .ok_or_else(|| invalid_discriminant(disc))?;
+            (Val::Enum(name.to_string()), n)
+        }
+        Type::Option(ty) => {
+            let Some(is_some) = { src }.get_option_status()? else {
+                return Ok(None);
+            };
+            if is_some {
+                let (v, n) = decode_val!(&ty.ty(), &src[1..]);
+                (Val::Option(Some(Box::new(v))), n + 1)
+            } else {
+                (Val::Option(None), 1)
+            }
+        }
+        Type::Result(ty) => {
+            let Some(is_ok) = { src }.get_result_status()? else {
+                return Ok(None);
+            };
+            if is_ok {
+                let Some((v, n)) = decode_payload(ty.ok().as_ref(), handles, taken, &src[1..])?
+                else {
+                    return Ok(None);
+                };
+                (Val::Result(Ok(v)), n + 1)
+            } else {
+                let Some((v, n)) = decode_payload(ty.err().as_ref(), handles, taken, &src[1..])?
+                else {
+                    return Ok(None);
+                };
+                (Val::Result(Err(v)), n + 1)
+            }
+        }
+        Type::Flags(ty) => {
+            let Some((vs, n)) = decode_flags(|| ty.names(), src)? else {
+                return Ok(None);
+            };
+            (Val::Flags(vs), n)
+        }
+        Type::Own(..)
+        | Type::Borrow(..)
+        | Type::Future(..)
+        | Type::Stream(..)
+        | Type::ErrorContext => {
+            let (idx, n) = decode!(U32Codec, src);
+            let (i, v) = usize::try_from(idx)
+                .ok()
+                .filter(|i| !taken.contains(i))
+                .and_then(|i| Some((i, handles.get(i)?.as_ref()?)))
+                .filter(|(_, v)| is_handle_of(ty, v))
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("invalid handle index `{idx}` for type `{ty:?}`"),
+                    )
+                })?;
+            if !matches!(ty, Type::Borrow(..)) {
+                taken.insert(i);
+            }
+            (v.clone(), n)
+        }
+    };
+    Ok(Some((v, n)))
+}
+
+/// Codec for [`Val`]s of a particular [`Type`]
+///
+/// The handle table is owned by the codec and shared by all values it encodes or decodes:
+/// encoding appends every handle to it, so indices keep growing across calls until the table is
+/// taken with [`ValCodec::take_handles`]. Decoding takes owned handles out of the table, leaving
+/// [`None`] in their place.
+#[derive(Clone, Debug)]
+pub struct ValCodec {
+    ty: Type,
+    handles: Vec<Option<Val>>,
+}
+
+impl ValCodec {
+    /// Constructs a new codec for values of type `ty` with an empty handle table
+    pub fn new(ty: Type) -> Self {
+        Self::with_handles(ty, Vec::default())
+    }
+
+    /// Constructs a new codec for values of type `ty` with handle table `handles`
+    pub fn with_handles(ty: Type, handles: Vec<Option<Val>>) -> Self {
+        Self { ty, handles }
+    }
+
+    /// Returns the handle table
+    pub fn handles(&self) -> &[Option<Val>] {
+        &self.handles
+    }
+
+    /// Returns the handle table, consuming the codec
+    pub fn into_handles(self) -> Vec<Option<Val>> {
+        self.handles
+    }
+
+    /// Returns the handle table, leaving an empty one in its place
+    pub fn take_handles(&mut self) -> Vec<Option<Val>> {
+        core::mem::take(&mut self.handles)
+    }
+}
+
+impl Encoder<&Val> for ValCodec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: &Val, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        encode_val(item, &self.ty, &mut self.handles, dst)
+    }
+}
+
+impl Encoder<Val> for ValCodec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: Val, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        encode_val(&item, &self.ty, &mut self.handles, dst)
+    }
+}
+
+impl Decoder for ValCodec {
+    type Item = Val;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some((v, n)) = decode_val(&self.ty, &mut self.handles, src)? else {
+            return Ok(None);
+        };
+        src.advance(n);
+        Ok(Some(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::wasmtime::component::{Component, Linker, Resource, ResourceAny, ResourceType};
+    use ::wasmtime::{Engine, Store};
+
+    use super::*;
+
+    struct R;
+
+    #[test_log::test]
+    fn val() -> ::wasmtime::Result<()> {
+        let engine = Engine::default();
+        let component = Component::new(
+            &engine,
+            r#"(component
+                (import "r" (type $r (sub resource)))
+                (type $v' (variant (case "a") (case "b" u16)))
+                (export $v "v" (type $v'))
+                (type $e' (enum "x" "y"))
+                (export $e "e" (type $e'))
+                (type $fl' (flags "p" "q" "r" "s" "t" "u" "v" "w" "z"))
+                (export $fl "fl" (type $fl'))
+                (type $rec' (record
+                    (field "b" bool)
+                    (field "s8" s8)
+                    (field "u64" u64)
+                    (field "f32" f32)
+                    (field "f64" f64)
+                    (field "c" char)
+                    (field "s" string)
+                    (field "l" (list s16))
+                    (field "t" (tuple u8 s32))
+                    (field "v" $v)
+                    (field "e" $e)
+                    (field "o" (option string))
+                    (field "res" (result u8 (error string)))
+                    (field "fl" $fl)
+                    (field "own" (own $r))
+                    (field "other" (own $r))
+                ))
+                (export $rec "rec" (type $rec'))
+                (core module $m
+                    (memory (export "mem") 1)
+                    (func (export "realloc") (param i32 i32 i32 i32) (result i32) unreachable)
+                    (func (export "f") (param i32))
+                )
+                (core instance $i (instantiate $m))
+                (func (export "f") (param "x" $rec)
+                    (canon lift (core func $i "f") (memory $i "mem") (realloc (func $i "realloc")))
+                )
+            )"#,
+        )?;
+        let mut linker = Linker::new(&engine);
+        linker
+            .root()
+            .resource("r", ResourceType::host::<R>(), |_, _| Ok(()))?;
+        let mut store = Store::new(&engine, ());
+        let instance = linker.instantiate(&mut store, &component)?;
+        let f = instance
+            .get_func(&mut store, "f")
+            .expect("function `f` not found");
+        let (_, ty) = f
+            .ty(&store)
+            .params()
+            .next()
+            .expect("function `f` has no parameters");
+
+        let own = ResourceAny::try_from_resource(Resource::<R>::new_own(42), &mut store)?;
+        let other = ResourceAny::try_from_resource(Resource::<R>::new_own(43), &mut store)?;
+        let v = Val::Record(vec![
+            ("b".into(), Val::Bool(true)),
+            ("s8".into(), Val::S8(-2)),
+            ("u64".into(), Val::U64(300)),
+            ("f32".into(), Val::Float32(1.5)),
+            ("f64".into(), Val::Float64(-0.25)),
+            ("c".into(), Val::Char('ß')),
+            ("s".into(), Val::String("foo".into())),
+            ("l".into(), Val::List(vec![Val::S16(-1), Val::S16(64)])),
+            ("t".into(), Val::Tuple(vec![Val::U8(7), Val::S32(-65)])),
+            (
+                "v".into(),
+                Val::Variant("b".into(), Some(Box::new(Val::U16(1)))),
+            ),
+            ("e".into(), Val::Enum("y".into())),
+            ("o".into(), Val::Option(None)),
+            (
+                "res".into(),
+                Val::Result(Err(Some(Box::new(Val::String("bar".into()))))),
+            ),
+            ("fl".into(), Val::Flags(vec!["q".into(), "z".into()])),
+            ("own".into(), Val::Resource(own)),
+            ("other".into(), Val::Resource(other)),
+        ]);
+
+        let mut buf = BytesMut::default();
+        let mut enc = ValCodec::new(ty.clone());
+        enc.encode(&v, &mut buf)?;
+        let mut expected = b"\x01\xfe\xac\x02".to_vec();
+        expected.extend_from_slice(&1.5f32.to_le_bytes());
+        expected.extend_from_slice(&(-0.25f64).to_le_bytes());
+        expected.extend_from_slice(b"\xc3\x9f\x03foo\x02\x7f\xc0\x00\x07\xbf\x7f");
+        expected.extend_from_slice(b"\x01\x01\x01\x00\x01\x03bar\x02\x01\x00\x01");
+        assert_eq!(buf.as_ref(), expected);
+        let handles = enc.take_handles();
+        assert_eq!(
+            handles,
+            [Some(Val::Resource(own)), Some(Val::Resource(other))]
+        );
+        assert!(enc.handles().is_empty());
+
+        let mut dec = ValCodec::with_handles(ty.clone(), handles);
+        for i in 0..buf.len() {
+            assert_eq!(dec.decode(&mut BytesMut::from(&buf[..i]))?, None);
+        }
+        assert_eq!(dec.decode(&mut buf)?, Some(v.clone()));
+        assert!(buf.is_empty());
+        assert_eq!(dec.handles(), [None, None]);
+
+        dec.decode(&mut BytesMut::from(&expected[..]))
+            .expect_err("decode should have failed, since the handles were already taken");
+        let mut handles = vec![Some(Val::Resource(own)), Some(Val::Resource(other))];
+        let mut reused = expected.clone();
+        *reused.last_mut().unwrap() = 0;
+        decode_val(&ty, &mut handles, &reused)
+            .expect_err("decode should have failed, since `own` handle 0 is used twice");
+        assert_eq!(
+            handles,
+            [Some(Val::Resource(own)), Some(Val::Resource(other))]
+        );
+
+        let Val::Record(mut fields) = v else {
+            unreachable!()
+        };
+        fields[15].1 = Val::U32(0);
+        let mut handles = vec![];
+        let mut buf = BytesMut::from(b"\xff".as_slice());
+        encode_val(&Val::Record(fields), &ty, &mut handles, &mut buf)
+            .expect_err("encode should have failed, since `u32` is not a valid `own` handle");
+        assert!(handles.is_empty());
+        assert_eq!(buf.as_ref(), b"\xff");
+        Ok(())
+    }
+}
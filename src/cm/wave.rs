@@ -0,0 +1,671 @@
+//! [WebAssembly Value Encoding (WAVE)](https://github.com/bytecodealliance/wasm-tools/tree/main/crates/wasm-wave)
+//! text format for dynamic component model [`Value`]s
+
+use core::fmt::{self, Display, Write as _};
+
+use crate::cm::{Type, Value};
+
+const KEYWORDS: [&str; 8] = ["true", "false", "some", "none", "ok", "err", "inf", "nan"];
+
+/// Formats `v` as WAVE text
+pub fn to_string(v: &Value) -> String {
+    v.to_string()
+}
+
+/// Parses WAVE text `s` as a value of type `ty`
+pub fn from_str(ty: &Type, s: &str) -> std::io::Result<Value> {
+    let mut p = Parser { src: s, pos: 0 };
+    let v = p.value(ty)?;
+    p.skip_whitespace();
+    if p.pos != s.len() {
+        return Err(p.error("unexpected trailing input"));
+    }
+    Ok(v)
+}
+
+struct Label<'a>(&'a str);
+
+impl Display for Label<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if KEYWORDS.contains(&self.0) {
+            f.write_char('%')?;
+        }
+        f.write_str(self.0)
+    }
+}
+
+fn write_char_escaped(f: &mut fmt::Formatter<'_>, c: char, quote: char) -> fmt::Result {
+    match c {
+        '\\' => f.write_str("\\\\"),
+        '\t' => f.write_str("\\t"),
+        '\n' => f.write_str("\\n"),
+        '\r' => f.write_str("\\r"),
+        c if c == quote => write!(f, "\\{c}"),
+        c if c.is_control() => write!(f, "\\u{{{:x}}}", u32::from(c)),
+        c => f.write_char(c),
+    }
+}
+
+macro_rules! write_float {
+    ($f:expr, $v:expr) => {
+        if $v.is_nan() {
+            $f.write_str("nan")
+        } else if $v.is_infinite() {
+            $f.write_str(if $v.is_sign_positive() { "inf" } else { "-inf" })
+        } else {
+            write!($f, "{}", $v)
+        }
+    };
+}
+
+fn write_payload(f: &mut fmt::Formatter<'_>, v: Option<&Value>) -> fmt::Result {
+    if let Some(v) = v {
+        write!(f, "({v})")?;
+    }
+    Ok(())
+}
+
+fn write_list<'a>(
+    f: &mut fmt::Formatter<'_>,
+    open: &str,
+    close: &str,
+    vs: impl IntoIterator<Item = &'a Value>,
+) -> fmt::Result {
+    f.write_str(open)?;
+    for (i, v) in vs.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{v}")?;
+    }
+    f.write_str(close)
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(v) => write!(f, "{v}"),
+            Self::S8(v) => write!(f, "{v}"),
+            Self::U8(v) => write!(f, "{v}"),
+            Self::S16(v) => write!(f, "{v}"),
+            Self::U16(v) => write!(f, "{v}"),
+            Self::S32(v) => write!(f, "{v}"),
+            Self::U32(v) => write!(f, "{v}"),
+            Self::S64(v) => write!(f, "{v}"),
+            Self::U64(v) => write!(f, "{v}"),
+            Self::F32(v) => write_float!(f, v),
+            Self::F64(v) => write_float!(f, v),
+            Self::Char(v) => {
+                f.write_char('\'')?;
+                write_char_escaped(f, *v, '\'')?;
+                f.write_char('\'')
+            }
+            Self::String(v) => {
+                f.write_char('"')?;
+                for c in v.chars() {
+                    write_char_escaped(f, c, '"')?;
+                }
+                f.write_char('"')
+            }
+            Self::List(vs) => write_list(f, "[", "]", vs),
+            Self::Record(vs) if vs.is_empty() => f.write_str("{:}"),
+            Self::Record(vs) => {
+                f.write_char('{')?;
+                for (i, (name, v)) in vs.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {v}", Label(name))?;
+                }
+                f.write_char('}')
+            }
+            Self::Tuple(vs) => write_list(f, "(", ")", vs),
+            Self::Variant(name, v) => {
+                write!(f, "{}", Label(name))?;
+                write_payload(f, v.as_deref())
+            }
+            Self::Enum(name) => write!(f, "{}", Label(name)),
+            Self::Option(None) => f.write_str("none"),
+            Self::Option(Some(v)) => write!(f, "some({v})"),
+            Self::Result(Ok(v)) => {
+                f.write_str("ok")?;
+                write_payload(f, v.as_deref())
+            }
+            Self::Result(Err(v)) => {
+                f.write_str("err")?;
+                write_payload(f, v.as_deref())
+            }
+            Self::Flags(vs) => {
+                f.write_char('{')?;
+                for (i, name) in vs.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", Label(name))?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// Returns `true` if `s` matches the WAVE number grammar,
+/// `-?(0|[1-9][0-9]*)([.][0-9]+)?([eE][-+]?[0-9]+)?`, fraction and exponent are only allowed
+/// if `float` is set
+fn is_number(s: &str, float: bool) -> bool {
+    fn digits(s: &str) -> (&str, &str) {
+        s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()))
+    }
+
+    let (int, rest) = digits(s.strip_prefix('-').unwrap_or(s));
+    if int.is_empty() || int.len() > 1 && int.starts_with('0') {
+        return false;
+    }
+    if rest.is_empty() {
+        return true;
+    }
+    if !float {
+        return false;
+    }
+    let rest = match rest.strip_prefix('.').map(digits) {
+        Some(("", _)) => return false,
+        Some((_, rest)) => rest,
+        None => rest,
+    };
+    if rest.is_empty() {
+        return true;
+    }
+    let Some(exp) = rest.strip_prefix(['e', 'E']) else {
+        return false;
+    };
+    let (exp, rest) = digits(exp.strip_prefix(['-', '+']).unwrap_or(exp));
+    !exp.is_empty() && rest.is_empty()
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, msg: impl Display) -> std::io::Error {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{msg} at offset {}", self.pos),
+        )
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if let Some(comment) = trimmed.strip_prefix("//") {
+                self.pos += 2 + comment.find('\n').unwrap_or(comment.len());
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Skips whitespace and consumes `c` if it is the next character
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> std::io::Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(format_args!("expected `{c}`")))
+        }
+    }
+
+    /// Parses a comma-separated sequence terminated by `close`, allowing a trailing comma
+    fn seq(
+        &mut self,
+        close: char,
+        mut f: impl FnMut(&mut Self) -> std::io::Result<()>,
+    ) -> std::io::Result<()> {
+        loop {
+            if self.eat(close) {
+                return Ok(());
+            }
+            f(self)?;
+            if !self.eat(',') {
+                return self.expect(close);
+            }
+        }
+    }
+
+    /// Parses a label, returning it without the `%` prefix, if any
+    fn label(&mut self) -> std::io::Result<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let (prefix, name) = match rest.strip_prefix('%') {
+            Some(name) => (1, name),
+            None => (0, rest),
+        };
+        let n = name
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+            .unwrap_or(name.len());
+        if n == 0 {
+            return Err(self.error("expected a label"));
+        }
+        self.pos += prefix + n;
+        Ok(&name[..n])
+    }
+
+    /// Parses a keyword, which, unlike a label, must not be prefixed with `%`
+    fn keyword(&mut self) -> std::io::Result<&'a str> {
+        self.skip_whitespace();
+        if self.peek() == Some('%') {
+            return Err(self.error("expected a keyword"));
+        }
+        self.label()
+    }
+
+    /// Parses a number token, which is validated by the caller
+    fn number(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let n = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && !matches!(c, '-' | '+' | '.'))
+            .unwrap_or(rest.len());
+        self.pos += n;
+        &rest[..n]
+    }
+
+    fn int<T: core::str::FromStr>(&mut self) -> std::io::Result<T> {
+        let start = self.pos;
+        let s = self.number();
+        match s.parse() {
+            Ok(v) if is_number(s, false) => Ok(v),
+            _ => {
+                self.pos = start;
+                Err(self.error(format_args!("invalid integer `{s}`")))
+            }
+        }
+    }
+
+    fn float<T: core::str::FromStr>(&mut self) -> std::io::Result<T> {
+        let start = self.pos;
+        let s = self.number();
+        let valid = match s.strip_prefix('-').unwrap_or(s) {
+            "nan" => s == "nan",
+            "inf" => true,
+            _ => is_number(s, true),
+        };
+        match s.parse() {
+            Ok(v) if valid => Ok(v),
+            _ => {
+                self.pos = start;
+                Err(self.error(format_args!("invalid float `{s}`")))
+            }
+        }
+    }
+
+    /// Parses a possibly-escaped character within a literal quoted by `quote`
+    fn char_escaped(&mut self, quote: char) -> std::io::Result<char> {
+        let mut chars = self.rest().chars();
+        let c = chars
+            .next()
+            .ok_or_else(|| self.error("unterminated literal"))?;
+        if c == quote {
+            return Err(self.error(format_args!("unescaped `{quote}`")));
+        }
+        if c != '\\' {
+            self.pos += c.len_utf8();
+            return Ok(c);
+        }
+        let c = match chars.next() {
+            Some('\\') => '\\',
+            Some('\'') => '\'',
+            Some('"') => '"',
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('u') => {
+                let rest = &self.rest()[2..];
+                let code = rest
+                    .strip_prefix('{')
+                    .and_then(|s| s.split_once('}'))
+                    .map(|(code, _)| code)
+                    .ok_or_else(|| self.error("invalid unicode escape"))?;
+                let c = u32::from_str_radix(code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("invalid unicode escape"))?;
+                self.pos += 4 + code.len();
+                return Ok(c);
+            }
+            _ => return Err(self.error("invalid escape")),
+        };
+        self.pos += 2;
+        Ok(c)
+    }
+
+    fn payload(&mut self, ty: Option<&Type>) -> std::io::Result<Option<Box<Value>>> {
+        let Some(ty) = ty else {
+            return Ok(None);
+        };
+        self.expect('(')?;
+        let v = self.value(ty)?;
+        self.expect(')')?;
+        Ok(Some(Box::new(v)))
+    }
+
+    fn value(&mut self, ty: &Type) -> std::io::Result<Value> {
+        self.skip_whitespace();
+        match ty {
+            Type::Bool => match self.keyword()? {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                _ => Err(self.error("expected `true` or `false`")),
+            },
+            Type::S8 => self.int().map(Value::S8),
+            Type::U8 => self.int().map(Value::U8),
+            Type::S16 => self.int().map(Value::S16),
+            Type::U16 => self.int().map(Value::U16),
+            Type::S32 => self.int().map(Value::S32),
+            Type::U32 => self.int().map(Value::U32),
+            Type::S64 => self.int().map(Value::S64),
+            Type::U64 => self.int().map(Value::U64),
+            Type::F32 => self.float().map(Value::F32),
+            Type::F64 => self.float().map(Value::F64),
+            Type::Char => {
+                self.expect('\'')?;
+                let c = self.char_escaped('\'')?;
+                if !self.rest().starts_with('\'') {
+                    return Err(self.error("expected `'`"));
+                }
+                self.pos += 1;
+                Ok(Value::Char(c))
+            }
+            Type::String => {
+                self.expect('"')?;
+                let mut s = String::new();
+                while !self.rest().starts_with('"') {
+                    s.push(self.char_escaped('"')?);
+                }
+                self.pos += 1;
+                Ok(Value::String(s))
+            }
+            Type::List(ty) => {
+                self.expect('[')?;
+                let mut vs = vec![];
+                self.seq(']', |p| {
+                    vs.push(p.value(ty)?);
+                    Ok(())
+                })?;
+                Ok(Value::List(vs))
+            }
+            Type::Record(fields) => {
+                self.expect('{')?;
+                let mut vs: Vec<Option<Value>> = vec![None; fields.len()];
+                if self.eat(':') {
+                    self.expect('}')?;
+                } else {
+                    self.seq('}', |p| {
+                        let name = p.label()?;
+                        let i = fields
+                            .iter()
+                            .position(|(field, _)| field == name)
+                            .ok_or_else(|| p.error(format_args!("unknown field `{name}`")))?;
+                        if vs[i].is_some() {
+                            return Err(p.error(format_args!("duplicate field `{name}`")));
+                        }
+                        p.expect(':')?;
+                        vs[i] = Some(p.value(&fields[i].1)?);
+                        Ok(())
+                    })?;
+                }
+                fields
+                    .iter()
+                    .zip(vs)
+                    .map(|((name, ty), v)| match (v, ty) {
+                        (Some(v), _) => Ok((name.clone(), v)),
+                        (None, Type::Option(_)) => Ok((name.clone(), Value::Option(None))),
+                        (None, _) => Err(self.error(format_args!("missing field `{name}`"))),
+                    })
+                    .collect::<std::io::Result<_>>()
+                    .map(Value::Record)
+            }
+            Type::Tuple(tys) => {
+                self.expect('(')?;
+                let mut vs = Vec::with_capacity(tys.len());
+                let mut tys = tys.iter();
+                self.seq(')', |p| {
+                    let ty = tys
+                        .next()
+                        .ok_or_else(|| p.error("too many tuple elements"))?;
+                    vs.push(p.value(ty)?);
+                    Ok(())
+                })?;
+                if tys.next().is_some() {
+                    return Err(self.error("too few tuple elements"));
+                }
+                Ok(Value::Tuple(vs))
+            }
+            Type::Variant(cases) => {
+                let name = self.label()?;
+                let (name, ty) = cases
+                    .iter()
+                    .find(|(case, _)| case == name)
+                    .ok_or_else(|| self.error(format_args!("unknown case `{name}`")))?;
+                let v = self.payload(ty.as_ref())?;
+                Ok(Value::Variant(name.clone(), v))
+            }
+            Type::Enum(cases) => {
+                let name = self.label()?;
+                let name = cases
+                    .iter()
+                    .find(|case| *case == name)
+                    .ok_or_else(|| self.error(format_args!("unknown case `{name}`")))?;
+                Ok(Value::Enum(name.clone()))
+            }
+            Type::Option(ty) => match self.keyword()? {
+                "none" => Ok(Value::Option(None)),
+                "some" => self.payload(Some(ty)).map(Value::Option),
+                _ => Err(self.error("expected `some` or `none`")),
+            },
+            Type::Result { ok, err } => match self.keyword()? {
+                "ok" => self.payload(ok.as_deref()).map(|v| Value::Result(Ok(v))),
+                "err" => self.payload(err.as_deref()).map(|v| Value::Result(Err(v))),
+                _ => Err(self.error("expected `ok` or `err`")),
+            },
+            Type::Flags(names) => {
+                self.expect('{')?;
+                let mut set = vec![false; names.len()];
+                self.seq('}', |p| {
+                    let name = p.label()?;
+                    let i = names
+                        .iter()
+                        .position(|flag| flag == name)
+                        .ok_or_else(|| p.error(format_args!("unknown flag `{name}`")))?;
+                    if set[i] {
+                        return Err(p.error(format_args!("duplicate flag `{name}`")));
+                    }
+                    set[i] = true;
+                    Ok(())
+                })?;
+                let vs = names
+                    .iter()
+                    .zip(set)
+                    .filter(|(_, set)| *set)
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                Ok(Value::Flags(vs))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_util::bytes::BytesMut;
+    use tokio_util::codec::{Decoder as _, Encoder as _};
+
+    use crate::cm::ValueCodec;
+
+    use super::*;
+
+    fn ty() -> Type {
+        Type::Record(vec![
+            ("name".into(), Type::String),
+            ("tags".into(), Type::List(Box::new(Type::String))),
+            (
+                "state".into(),
+                Type::Option(Box::new(Type::Result {
+                    ok: Some(Box::new(Type::U32)),
+                    err: None,
+                })),
+            ),
+            ("pos".into(), Type::Tuple(vec![Type::F64, Type::S8])),
+            ("initial".into(), Type::Char),
+            (
+                "kind".into(),
+                Type::Variant(vec![
+                    ("none".into(), None),
+                    ("named".into(), Some(Type::String)),
+                ]),
+            ),
+            (
+                "color".into(),
+                Type::Enum(vec!["red".into(), "green".into()]),
+            ),
+            (
+                "perms".into(),
+                Type::Flags((0..10).map(|i| format!("p{i}")).collect()),
+            ),
+            ("comment".into(), Type::Option(Box::new(Type::String))),
+        ])
+    }
+
+    #[test_log::test]
+    fn roundtrip() {
+        let ty = ty();
+        let v = from_str(
+            &ty,
+            r#"{
+                name: "x\t\"y\"\u{1F600}",
+                tags: ["a", "b",], // trailing comma
+                state: some(ok(3)),
+                pos: (-inf, -7),
+                initial: '\'',
+                kind: %none,
+                color: green,
+                perms: {p9, p1},
+            }"#,
+        )
+        .expect("failed to parse value");
+        assert_eq!(
+            v,
+            Value::Record(vec![
+                ("name".into(), Value::String("x\t\"y\"\u{1F600}".into())),
+                (
+                    "tags".into(),
+                    Value::List(vec![Value::String("a".into()), Value::String("b".into())])
+                ),
+                (
+                    "state".into(),
+                    Value::Option(Some(Box::new(Value::Result(Ok(Some(Box::new(
+                        Value::U32(3)
+                    )))))))
+                ),
+                (
+                    "pos".into(),
+                    Value::Tuple(vec![Value::F64(f64::NEG_INFINITY), Value::S8(-7)])
+                ),
+                ("initial".into(), Value::Char('\'')),
+                ("kind".into(), Value::Variant("none".into(), None)),
+                ("color".into(), Value::Enum("green".into())),
+                ("perms".into(), Value::Flags(vec!["p1".into(), "p9".into()])),
+                ("comment".into(), Value::Option(None)),
+            ])
+        );
+        let s = to_string(&v);
+        assert_eq!(
+            s,
+            r#"{name: "x\t\"y\"😀", tags: ["a", "b"], state: some(ok(3)), pos: (-inf, -7), initial: '\'', kind: %none, color: green, perms: {p1, p9}, comment: none}"#
+        );
+        assert_eq!(from_str(&ty, &s).expect("failed to parse value"), v);
+
+        let mut buf = BytesMut::default();
+        ValueCodec::new(ty.clone())
+            .encode(&v, &mut buf)
+            .expect("failed to encode value");
+        assert_eq!(
+            buf.as_ref(),
+            b"\x09x\t\"y\"\xf0\x9f\x98\x80\x02\x01a\x01b\x01\x00\x03\0\0\0\0\0\0\xf0\xff\xf9'\x00\x01\x02\x02\x00"
+        );
+        assert_eq!(
+            ValueCodec::new(ty.clone())
+                .decode(&mut buf)
+                .expect("failed to decode value"),
+            Some(v)
+        );
+        assert!(buf.is_empty());
+
+        assert_eq!(
+            to_string(&from_str(&Type::Record(vec![]), " {:} ").expect("failed to parse value")),
+            "{:}"
+        );
+        assert_eq!(
+            to_string(&from_str(&Type::F32, "nan").expect("failed to parse value")),
+            "nan"
+        );
+    }
+
+    #[test_log::test]
+    fn invalid() {
+        from_str(&Type::U8, "256").expect_err("parse should have failed, since 256 overflows u8");
+        from_str(&Type::F64, "infinity")
+            .expect_err("parse should have failed, since `infinity` is not valid WAVE");
+        from_str(&Type::Bool, "true false")
+            .expect_err("parse should have failed due to trailing input");
+        from_str(&Type::Tuple(vec![Type::U8, Type::U8]), "(1)")
+            .expect_err("parse should have failed, since the tuple is too short");
+        from_str(&ty(), r#"{name: "x"}"#)
+            .expect_err("parse should have failed, since required fields are missing");
+        for s in ["+1", "1.", "1.0", "1e3", "01", "-"] {
+            from_str(&Type::S32, s).expect_err(&format!(
+                "parse should have failed, since `{s}` is not an integer"
+            ));
+        }
+        for s in ["1.", ".5", "+1", "01", "1e", "1.5e+"] {
+            from_str(&Type::F64, s).expect_err(&format!(
+                "parse should have failed, since `{s}` is not a number"
+            ));
+        }
+        assert_eq!(
+            from_str(&Type::F64, "-0.5e-3").expect("failed to parse value"),
+            Value::F64(-0.5e-3)
+        );
+        from_str(&Type::Bool, "%true")
+            .expect_err("parse should have failed, since keywords cannot be prefixed with `%`");
+        from_str(&Type::Option(Box::new(Type::U8)), "%none")
+            .expect_err("parse should have failed, since keywords cannot be prefixed with `%`");
+        from_str(&Type::Flags(vec!["a".into(), "b".into()]), "{a, b, a}")
+            .expect_err("parse should have failed, since flag `a` is set twice");
+        from_str(&Type::Char, "'''")
+            .expect_err("parse should have failed, since `'` is not escaped");
+        let err = from_str(&Type::List(Box::new(Type::Char)), "['a', 'bc']")
+            .expect_err("parse should have failed, since `'bc'` is not a char");
+        assert_eq!(err.to_string(), "expected `'` at offset 8");
+    }
+}
@@ -1,16 +1,265 @@
+#[cfg(feature = "tokio")]
 use ::core::future::Future;
+#[cfg(feature = "tokio")]
+use ::core::marker::PhantomData;
+#[cfg(feature = "tokio")]
 use ::core::mem;
 use ::core::str;
 
-use std::sync::Arc;
-
-use leb128_tokio::{AsyncReadLeb128, Leb128DecoderU32, Leb128Encoder};
-use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
-use tokio_util::bytes::{BufMut as _, Bytes, BytesMut};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "tokio")]
+use std::io::IoSlice;
+
+use bytes::{Buf, BufMut, Bytes};
+use leb128_tokio::{
+    decode_i128_leb128, decode_i16_leb128, decode_i32_leb128, decode_i64_leb128, decode_i8_leb128,
+    decode_u128_leb128, decode_u16_leb128, decode_u32_leb128, decode_u64_leb128, decode_u8_leb128,
+    leb128_len_i128, leb128_len_i16, leb128_len_i32, leb128_len_i64, leb128_len_i8,
+    leb128_len_u128, leb128_len_u16, leb128_len_u32, leb128_len_u64, leb128_len_u8,
+    put_u128_leb128_padded, put_u16_leb128_padded, put_u32_leb128_padded, put_u64_leb128_padded,
+    put_u8_leb128_padded, put_zigzag_i128_leb128, put_zigzag_i16_leb128, put_zigzag_i32_leb128,
+    put_zigzag_i64_leb128, put_zigzag_i8_leb128, BufLeb128Ext as _, BufMutLeb128Ext as _,
+    Leb128DecoderI128, Leb128DecoderI16, Leb128DecoderI32, Leb128DecoderI64, Leb128DecoderI8,
+    Leb128DecoderU128, Leb128DecoderU16, Leb128DecoderU32, Leb128DecoderU64, Leb128DecoderU8,
+    Leb128Encoder, Leb128PaddedEncoder, ZigZagEncoder,
+};
+#[cfg(feature = "tokio")]
+use leb128_tokio::{put_u32_leb128, AsyncReadLeb128};
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt};
+#[cfg(feature = "tokio")]
+use tokio_util::bytes::BytesMut;
+#[cfg(feature = "tokio")]
 use tokio_util::codec::{Decoder, Encoder};
+use utf8_tokio::{decode_char_utf8, BufMutUtf8Ext as _, Utf8Codec};
+
+#[cfg(feature = "tokio")]
+use crate::READ_CHUNK_LEN;
+use crate::{EncodedLen, Error, ErrorKind};
+
+/// Encoding of values directly into arbitrary [`BufMut`] implementations, e.g. fixed `&mut [u8]`
+/// slices or pre-registered I/O buffers, without intermediate buffering.
+///
+/// Available capacity is determined by [`BufMut::remaining_mut`] and an error is returned
+/// if the [`EncodedLen`] of `item` does not fit, in which case `dst` is left untouched.
+/// Other errors, e.g. for lists longer than [`u32::MAX`], may leave a partially encoded value
+/// in `dst`.
+/// Note, that growable buffers, like [`Vec<u8>`], report (almost) unlimited capacity - use
+/// [`BufMut::limit`] to encode into the already allocated capacity only.
+pub trait WasmEncode<T>: EncodedLen<T> {
+    /// Writes `item` to `dst` without checking the remaining capacity of `dst` first.
+    /// Like [`BufMut::put_slice`], this panics if `dst` runs out of capacity.
+    fn put_buf(&mut self, item: T, dst: &mut impl BufMut) -> Result<(), Error>;
+
+    /// Encodes `item` into `dst`, failing if `dst` does not have enough remaining capacity
+    fn encode_buf(&mut self, item: T, dst: &mut impl BufMut) -> Result<(), Error> {
+        let n = self.encoded_len(&item);
+        if dst.remaining_mut() < n {
+            return Err(Error::new(
+                ErrorKind::WriteZero,
+                format!(
+                    "insufficient buffer capacity, {n} bytes required, {} available",
+                    dst.remaining_mut()
+                ),
+            ));
+        }
+        self.put_buf(item, dst)
+    }
+}
+
+macro_rules! impl_wasm_encode_copy {
+    ($enc:ty, $t:ty, |$dst:ident, $x:ident| $put:expr) => {
+        impl WasmEncode<$t> for $enc {
+            fn put_buf(&mut self, $x: $t, $dst: &mut impl BufMut) -> Result<(), Error> {
+                $put;
+                Ok(())
+            }
+        }
+
+        impl WasmEncode<&$t> for $enc {
+            fn put_buf(&mut self, item: &$t, dst: &mut impl BufMut) -> Result<(), Error> {
+                self.put_buf(*item, dst)
+            }
+        }
+
+        impl WasmEncode<&&$t> for $enc {
+            fn put_buf(&mut self, item: &&$t, dst: &mut impl BufMut) -> Result<(), Error> {
+                self.put_buf(**item, dst)
+            }
+        }
+    };
+}
+
+impl_wasm_encode_copy!(Leb128Encoder, u8, |dst, x| dst.put_u8_leb128(x));
+impl_wasm_encode_copy!(Leb128Encoder, u16, |dst, x| dst.put_u16_leb128(x));
+impl_wasm_encode_copy!(Leb128Encoder, u32, |dst, x| dst.put_u32_leb128(x));
+impl_wasm_encode_copy!(Leb128Encoder, u64, |dst, x| dst.put_u64_leb128(x));
+impl_wasm_encode_copy!(Leb128Encoder, u128, |dst, x| dst.put_u128_leb128(x));
+impl_wasm_encode_copy!(Leb128Encoder, i8, |dst, x| dst.put_i8_leb128(x));
+impl_wasm_encode_copy!(Leb128Encoder, i16, |dst, x| dst.put_i16_leb128(x));
+impl_wasm_encode_copy!(Leb128Encoder, i32, |dst, x| dst.put_i32_leb128(x));
+impl_wasm_encode_copy!(Leb128Encoder, i64, |dst, x| dst.put_i64_leb128(x));
+impl_wasm_encode_copy!(Leb128Encoder, i128, |dst, x| dst.put_i128_leb128(x));
+impl_wasm_encode_copy!(ZigZagEncoder, i8, |dst, x| dst
+    .put_slice(put_zigzag_i8_leb128(&mut Default::default(), x)));
+impl_wasm_encode_copy!(ZigZagEncoder, i16, |dst, x| dst
+    .put_slice(put_zigzag_i16_leb128(&mut Default::default(), x)));
+impl_wasm_encode_copy!(ZigZagEncoder, i32, |dst, x| dst
+    .put_slice(put_zigzag_i32_leb128(&mut Default::default(), x)));
+impl_wasm_encode_copy!(ZigZagEncoder, i64, |dst, x| dst
+    .put_slice(put_zigzag_i64_leb128(&mut Default::default(), x)));
+impl_wasm_encode_copy!(ZigZagEncoder, i128, |dst, x| dst
+    .put_slice(put_zigzag_i128_leb128(&mut Default::default(), x)));
+impl_wasm_encode_copy!(Utf8Codec, char, |dst, x| dst.put_char_utf8(x));
+
+macro_rules! impl_wasm_encode_padded {
+    ($t:ty, $f:ident) => {
+        impl<const N: usize> WasmEncode<$t> for Leb128PaddedEncoder<N> {
+            fn put_buf(&mut self, item: $t, dst: &mut impl BufMut) -> Result<(), Error> {
+                let mut buf = [0; N];
+                let buf = $f(&mut buf, item).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("value does not fit in {N} LEB128 bytes"),
+                    )
+                })?;
+                dst.put_slice(buf);
+                Ok(())
+            }
+        }
+
+        impl<const N: usize> WasmEncode<&$t> for Leb128PaddedEncoder<N> {
+            fn put_buf(&mut self, item: &$t, dst: &mut impl BufMut) -> Result<(), Error> {
+                self.put_buf(*item, dst)
+            }
+        }
+    };
+}
+
+impl_wasm_encode_padded!(u8, put_u8_leb128_padded);
+impl_wasm_encode_padded!(u16, put_u16_leb128_padded);
+impl_wasm_encode_padded!(u32, put_u32_leb128_padded);
+impl_wasm_encode_padded!(u64, put_u64_leb128_padded);
+impl_wasm_encode_padded!(u128, put_u128_leb128_padded);
+
+macro_rules! impl_encoded_len_leb128 {
+    ($t:ty, $f:ident) => {
+        impl EncodedLen<$t> for Leb128Encoder {
+            fn encoded_len(&self, item: &$t) -> usize {
+                $f(*item)
+            }
+        }
+
+        impl EncodedLen<&$t> for Leb128Encoder {
+            fn encoded_len(&self, item: &&$t) -> usize {
+                $f(**item)
+            }
+        }
+
+        impl EncodedLen<&&$t> for Leb128Encoder {
+            fn encoded_len(&self, item: &&&$t) -> usize {
+                $f(***item)
+            }
+        }
+    };
+}
 
+impl_encoded_len_leb128!(u8, leb128_len_u8);
+impl_encoded_len_leb128!(u16, leb128_len_u16);
+impl_encoded_len_leb128!(u32, leb128_len_u32);
+impl_encoded_len_leb128!(u64, leb128_len_u64);
+impl_encoded_len_leb128!(u128, leb128_len_u128);
+impl_encoded_len_leb128!(i8, leb128_len_i8);
+impl_encoded_len_leb128!(i16, leb128_len_i16);
+impl_encoded_len_leb128!(i32, leb128_len_i32);
+impl_encoded_len_leb128!(i64, leb128_len_i64);
+impl_encoded_len_leb128!(i128, leb128_len_i128);
+
+macro_rules! impl_encoded_len_padded {
+    ($t:ty) => {
+        impl<const N: usize> EncodedLen<$t> for Leb128PaddedEncoder<N> {
+            fn encoded_len(&self, _: &$t) -> usize {
+                N
+            }
+        }
+
+        impl<const N: usize> EncodedLen<&$t> for Leb128PaddedEncoder<N> {
+            fn encoded_len(&self, _: &&$t) -> usize {
+                N
+            }
+        }
+    };
+}
+
+impl_encoded_len_padded!(u8);
+impl_encoded_len_padded!(u16);
+impl_encoded_len_padded!(u32);
+impl_encoded_len_padded!(u64);
+impl_encoded_len_padded!(u128);
+
+macro_rules! impl_encoded_len_zigzag {
+    ($t:ty, $u:ty, $f:ident) => {
+        impl EncodedLen<$t> for ZigZagEncoder {
+            fn encoded_len(&self, item: &$t) -> usize {
+                $f(((*item << 1) ^ (*item >> (<$t>::BITS - 1))) as $u)
+            }
+        }
+
+        impl EncodedLen<&$t> for ZigZagEncoder {
+            fn encoded_len(&self, item: &&$t) -> usize {
+                self.encoded_len(*item)
+            }
+        }
+
+        impl EncodedLen<&&$t> for ZigZagEncoder {
+            fn encoded_len(&self, item: &&&$t) -> usize {
+                self.encoded_len(**item)
+            }
+        }
+    };
+}
+
+impl_encoded_len_zigzag!(i8, u8, leb128_len_u8);
+impl_encoded_len_zigzag!(i16, u16, leb128_len_u16);
+impl_encoded_len_zigzag!(i32, u32, leb128_len_u32);
+impl_encoded_len_zigzag!(i64, u64, leb128_len_u64);
+impl_encoded_len_zigzag!(i128, u128, leb128_len_u128);
+
+impl EncodedLen<char> for Utf8Codec {
+    fn encoded_len(&self, item: &char) -> usize {
+        item.len_utf8()
+    }
+}
+
+impl EncodedLen<&char> for Utf8Codec {
+    fn encoded_len(&self, item: &&char) -> usize {
+        item.len_utf8()
+    }
+}
+
+impl EncodedLen<&&char> for Utf8Codec {
+    fn encoded_len(&self, item: &&&char) -> usize {
+        item.len_utf8()
+    }
+}
+
+/// Core value extensions for [`AsyncRead`]
+///
+/// The methods of this trait are not cancellation safe, see
+/// [`ValueReader`](crate::ValueReader#cancel-safety).
+#[cfg(feature = "tokio")]
 pub trait AsyncReadCore: AsyncRead {
     /// Read [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names)
+    ///
+    /// This method is not cancellation safe, see [`ValueReader`](crate::ValueReader#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "name"))
@@ -21,15 +270,30 @@ pub trait AsyncReadCore: AsyncRead {
     {
         async move {
             let n = self.read_u32_leb128().await?;
-            s.reserve(n.try_into().unwrap_or(usize::MAX));
-            self.take(n.into()).read_to_string(s).await?;
+            let n = n.try_into().unwrap_or(usize::MAX);
+            // `n` is untrusted, so grow the buffer in bounded chunks as data arrives
+            let mut buf = Vec::default();
+            while buf.len() < n {
+                let k = buf.len();
+                buf.resize(n.min(k.saturating_add(READ_CHUNK_LEN)), 0);
+                let n = self.read(&mut buf[k..]).await?;
+                buf.truncate(k + n);
+                if n == 0 {
+                    break;
+                }
+            }
+            let buf = String::from_utf8(buf)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+            s.push_str(&buf);
             Ok(())
         }
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<T: AsyncRead> AsyncReadCore for T {}
 
+#[cfg(feature = "tokio")]
 pub trait AsyncWriteCore: AsyncWrite {
     /// Write [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names)
     #[cfg_attr(
@@ -37,23 +301,220 @@ pub trait AsyncWriteCore: AsyncWrite {
         tracing::instrument(level = "trace", ret, skip_all, fields(ty = "name"))
     )]
     fn write_core_name(&mut self, s: &str) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move { self.write_core_vec_bytes(s.as_bytes()).await }
+    }
+
+    /// Write [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+    /// of bytes. The payload is written directly from `buf` without copying it, using
+    /// vectored writes if supported by the writer.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all, fields(ty = "vec"))
+    )]
+    fn write_core_vec_bytes(&mut self, buf: &[u8]) -> impl Future<Output = std::io::Result<()>>
     where
         Self: Unpin,
     {
         async move {
-            let mut buf = BytesMut::with_capacity(5usize.saturating_add(s.len()));
-            CoreNameEncoder.encode(s, &mut buf)?;
-            self.write_all(&buf).await
+            let n = u32::try_from(buf.len())
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+            let mut len = [0; 5];
+            let len = put_u32_leb128(&mut len, n);
+            let mut w = self;
+            AsyncWriteExt::write_all_buf(&mut w, &mut Buf::chain(&*len, buf)).await
         }
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<T: AsyncWrite> AsyncWriteCore for T {}
 
+/// Segmented encoding output, which holds small encoded values, like LEB128 length headers,
+/// in a contiguous buffer and references large [`Bytes`] payloads without copying them.
+///
+/// [`Segments`] implements [`Buf`], so it can be written using
+/// [`write_all_buf`](tokio::io::AsyncWriteExt::write_all_buf), which uses vectored writes
+/// if supported by the writer.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Default)]
+pub struct Segments {
+    segs: VecDeque<Bytes>,
+    buf: BytesMut,
+}
+
+#[cfg(feature = "tokio")]
+impl Segments {
+    /// Payloads shorter than this are copied into the contiguous buffer
+    const COPY_THRESHOLD: usize = 256;
+
+    /// Encodes `item` using `enc` into the contiguous buffer
+    pub fn encode<T, E: Encoder<T>>(&mut self, enc: &mut E, item: T) -> Result<(), E::Error> {
+        enc.encode(item, &mut self.buf)
+    }
+
+    /// Appends `payload`, large payloads are appended as a separate segment without copying
+    pub fn put_bytes(&mut self, payload: Bytes) {
+        if payload.len() < Self::COPY_THRESHOLD {
+            self.buf.extend_from_slice(&payload);
+        } else {
+            self.flush();
+            self.segs.push_back(payload);
+        }
+    }
+
+    /// Returns the encoded segments
+    #[must_use]
+    pub fn into_segments(mut self) -> VecDeque<Bytes> {
+        self.flush();
+        self.segs
+    }
+
+    fn flush(&mut self) {
+        if !self.buf.is_empty() {
+            self.segs.push_back(self.buf.split().freeze());
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Buf for Segments {
+    fn remaining(&self) -> usize {
+        self.segs
+            .iter()
+            .map(Bytes::len)
+            .fold(self.buf.len(), usize::saturating_add)
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.segs.front().map_or(&self.buf, |seg| seg)
+    }
+
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let mut n = 0;
+        for (dst, seg) in dst.iter_mut().zip(
+            self.segs
+                .iter()
+                .map(Bytes::as_ref)
+                .chain([self.buf.as_ref()]),
+        ) {
+            if seg.is_empty() {
+                break;
+            }
+            *dst = IoSlice::new(seg);
+            n += 1;
+        }
+        n
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        while let Some(seg) = self.segs.front_mut() {
+            if cnt < seg.len() {
+                seg.advance(cnt);
+                return;
+            }
+            cnt -= seg.len();
+            self.segs.pop_front();
+        }
+        self.buf.advance(cnt);
+    }
+}
+
+/// Encoding of values into [`Segments`], which avoids copying large payloads
+#[cfg(feature = "tokio")]
+pub trait EncodeSegments<T> {
+    /// Encodes `item` into `dst`
+    fn encode_segments(&mut self, item: T, dst: &mut Segments) -> std::io::Result<()>;
+}
+
+#[cfg(feature = "tokio")]
+impl EncodeSegments<Bytes> for CoreVecEncoderBytes {
+    fn encode_segments(&mut self, item: Bytes, dst: &mut Segments) -> std::io::Result<()> {
+        let n = u32::try_from(item.len())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        dst.encode(&mut Leb128Encoder, n)?;
+        dst.put_bytes(item);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl EncodeSegments<Vec<u8>> for CoreVecEncoderBytes {
+    fn encode_segments(&mut self, item: Vec<u8>, dst: &mut Segments) -> std::io::Result<()> {
+        self.encode_segments(Bytes::from(item), dst)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl EncodeSegments<String> for CoreNameEncoder {
+    fn encode_segments(&mut self, item: String, dst: &mut Segments) -> std::io::Result<()> {
+        CoreVecEncoderBytes.encode_segments(item.into_bytes(), dst)
+    }
+}
+
+/// Core value extensions for [`Buf`]
+pub trait BufCoreExt: Buf {
+    /// Get [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names),
+    /// returns [`None`] and leaves the buffer untouched if more data is needed, see
+    /// [`get_core_vec_bytes`](Self::get_core_vec_bytes) for buffers exposing only part of the name
+    fn get_core_name(&mut self) -> Result<Option<String>, Error> {
+        let Some(buf) = self.get_core_vec_bytes()? else {
+            return Ok(None);
+        };
+        let s =
+            String::from_utf8(buf.into()).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        Ok(Some(s))
+    }
+
+    /// Get [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+    /// of bytes, returns [`None`] and leaves the buffer untouched if more data is needed.
+    ///
+    /// The length prefix must be exposed by [`Buf::chunks_vectored`], otherwise, e.g. for a
+    /// [`Take`](bytes::buf::Take) exposing only part of it, [`None`] is returned as well,
+    /// since the buffer cannot be inspected without advancing it.
+    fn get_core_vec_bytes(&mut self) -> Result<Option<Bytes>, Error> {
+        let Some((len, n)) = self.peek_u32_leb128()? else {
+            return Ok(None);
+        };
+        let len = len
+            .try_into()
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        if self.remaining() - n < len {
+            return Ok(None);
+        }
+        self.advance(n);
+        Ok(Some(self.copy_to_bytes(len)))
+    }
+}
+
+impl<T: Buf + ?Sized> BufCoreExt for T {}
+
+/// Core value extensions for [`BufMut`]
+pub trait BufMutCoreExt: BufMut {
+    /// Put [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names)
+    fn put_core_name(&mut self, s: &str) -> Result<(), Error> {
+        self.put_core_vec_bytes(s.as_bytes())
+    }
+
+    /// Put [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+    /// of bytes
+    fn put_core_vec_bytes(&mut self, buf: &[u8]) -> Result<(), Error> {
+        let n = u32::try_from(buf.len()).map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        self.put_u32_leb128(n);
+        self.put_slice(buf);
+        Ok(())
+    }
+}
+
+impl<T: BufMut + ?Sized> BufMutCoreExt for T {}
+
 /// [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names) encoder
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct CoreNameEncoder;
 
+#[cfg(feature = "tokio")]
 impl<T: AsRef<str>> Encoder<T> for CoreNameEncoder {
     type Error = std::io::Error;
 
@@ -63,17 +524,30 @@ impl<T: AsRef<str>> Encoder<T> for CoreNameEncoder {
         let n: u32 = len
             .try_into()
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
-        dst.reserve(len + 5 - n.leading_zeros() as usize / 7);
+        dst.reserve(leb128_len_u32(n) + len);
         Leb128Encoder.encode(n, dst)?;
         dst.put(item.as_bytes());
         Ok(())
     }
 }
 
+impl<T: AsRef<str> + ?Sized> EncodedLen<T> for CoreNameEncoder {
+    fn encoded_len(&self, item: &T) -> usize {
+        CoreVecEncoderBytes.encoded_len(item.as_ref().as_bytes())
+    }
+}
+
+impl<T: AsRef<str>> WasmEncode<T> for CoreNameEncoder {
+    fn put_buf(&mut self, item: T, dst: &mut impl BufMut) -> Result<(), Error> {
+        dst.put_core_name(item.as_ref())
+    }
+}
+
 /// [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names) decoder
 #[derive(Debug, Default)]
 pub struct CoreNameDecoder(CoreVecDecoderBytes);
 
+#[cfg(feature = "tokio")]
 impl Decoder for CoreNameDecoder {
     type Item = String;
     type Error = std::io::Error;
@@ -91,6 +565,123 @@ impl Decoder for CoreNameDecoder {
 /// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec) encoder
 pub struct CoreVecEncoder<E>(pub E);
 
+#[cfg(feature = "tokio")]
+impl<E> CoreVecEncoder<E> {
+    /// Encodes `item` like [`Encoder::encode`], but reserves its exact [`EncodedLen`] in `dst`
+    /// up front instead of estimating one byte per element
+    pub fn encode_exact<T>(
+        &mut self,
+        item: T,
+        dst: &mut BytesMut,
+    ) -> Result<(), <Self as Encoder<T>>::Error>
+    where
+        Self: Encoder<T> + EncodedLen<T>,
+    {
+        dst.reserve(self.encoded_len(&item));
+        self.encode(item, dst)
+    }
+
+    /// Encodes all elements of an [`ExactSizeIterator`], failing if the iterator
+    /// yields a different number of elements than reported by [`ExactSizeIterator::len`].
+    /// On error, `dst` is left untouched.
+    pub fn encode_iter<I>(&mut self, iter: I, dst: &mut BytesMut) -> std::io::Result<()>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        E: Encoder<I::Item>,
+        std::io::Error: From<E::Error>,
+    {
+        let mut iter = iter.into_iter();
+        let len = iter.len();
+        let n = u32::try_from(len)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        let start = dst.len();
+        dst.reserve(5 + len);
+        Leb128Encoder.encode(n, dst)?;
+        let mut n = 0;
+        for item in iter.by_ref().take(len) {
+            if let Err(err) = self.0.encode(item, dst) {
+                dst.truncate(start);
+                return Err(err.into());
+            }
+            n += 1;
+        }
+        if n != len {
+            dst.truncate(start);
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("iterator yielded {n} elements, but reported length of {len}"),
+            ));
+        }
+        if iter.next().is_some() {
+            dst.truncate(start);
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("iterator yielded more elements than reported length of {len}"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Wrapper for encoding an [`ExactSizeIterator`] as a
+/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+/// using [`CoreVecEncoder`]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExactSizeIter<I>(pub I);
+
+#[cfg(feature = "tokio")]
+impl<E, I> Encoder<ExactSizeIter<I>> for CoreVecEncoder<E>
+where
+    I: IntoIterator,
+    I::IntoIter: ExactSizeIterator,
+    E: Encoder<I::Item>,
+    std::io::Error: From<E::Error>,
+{
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: ExactSizeIter<I>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.encode_iter(item.0, dst)
+    }
+}
+
+macro_rules! impl_encode_collection {
+    (<$($p:ident $(: $b:path)?),+> $t:ty, $item:ty, $ref_item:ty) => {
+        #[cfg(feature = "tokio")]
+        impl<E, $($p $(: $b)?),+> Encoder<$t> for CoreVecEncoder<E>
+        where
+            E: Encoder<$item>,
+            std::io::Error: From<E::Error>,
+        {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: $t, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode_iter(item, dst)
+            }
+        }
+
+        #[cfg(feature = "tokio")]
+        impl<'a, E, $($p $(: $b)?),+> Encoder<&'a $t> for CoreVecEncoder<E>
+        where
+            E: Encoder<$ref_item>,
+            std::io::Error: From<E::Error>,
+        {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: &'a $t, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                self.encode_iter(item, dst)
+            }
+        }
+    };
+}
+
+impl_encode_collection!(<T> VecDeque<T>, T, &'a T);
+impl_encode_collection!(<T> BTreeSet<T>, T, &'a T);
+impl_encode_collection!(<T, S> HashSet<T, S>, T, &'a T);
+impl_encode_collection!(<K, V> BTreeMap<K, V>, (K, V), (&'a K, &'a V));
+impl_encode_collection!(<K, V, S> HashMap<K, V, S>, (K, V), (&'a K, &'a V));
+
+#[cfg(feature = "tokio")]
 impl<E, T, const N: usize> Encoder<[T; N]> for CoreVecEncoder<E>
 where
     E: Encoder<T>,
@@ -110,6 +701,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<E, T> Encoder<Vec<T>> for CoreVecEncoder<E>
 where
     E: Encoder<T>,
@@ -130,6 +722,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<E, T> Encoder<Box<[T]>> for CoreVecEncoder<E>
 where
     E: Encoder<T>,
@@ -142,6 +735,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<'a, E, T, const N: usize> Encoder<&'a [T; N]> for CoreVecEncoder<E>
 where
     E: Encoder<&'a T>,
@@ -161,6 +755,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<'a, E, T> Encoder<&'a [T]> for CoreVecEncoder<E>
 where
     E: Encoder<&'a T>,
@@ -181,6 +776,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<'a, 'b, E, T> Encoder<&'a &'b [T]> for CoreVecEncoder<E>
 where
     E: Encoder<&'b T>,
@@ -193,6 +789,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<'a, E, T> Encoder<&'a Vec<T>> for CoreVecEncoder<E>
 where
     E: Encoder<&'a T>,
@@ -205,6 +802,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<'a, 'b, E, T> Encoder<&'a &'b Vec<T>> for CoreVecEncoder<E>
 where
     E: Encoder<&'b T>,
@@ -217,6 +815,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<'a, E, T> Encoder<&'a Box<[T]>> for CoreVecEncoder<E>
 where
     E: Encoder<&'a T>,
@@ -230,6 +829,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<E, T> Encoder<Arc<[T]>> for CoreVecEncoder<E>
 where
     for<'a> E: Encoder<&'a T>,
@@ -251,6 +851,7 @@ where
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<'a, E, T> Encoder<&'a Arc<[T]>> for CoreVecEncoder<E>
 where
     E: Encoder<&'a T>,
@@ -264,98 +865,480 @@ where
     }
 }
 
-/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec) decoder
-#[derive(Debug)]
-pub struct CoreVecDecoder<T: Decoder> {
-    dec: T,
-    ret: Vec<T::Item>,
-    cap: usize,
+impl<E, T> EncodedLen<[T]> for CoreVecEncoder<E>
+where
+    E: EncodedLen<T>,
+{
+    fn encoded_len(&self, item: &[T]) -> usize {
+        item.iter().fold(core_vec_len_len(item.len()), |n, item| {
+            n + self.0.encoded_len(item)
+        })
+    }
 }
 
-impl<T> CoreVecDecoder<T>
+impl<E, T, const N: usize> EncodedLen<[T; N]> for CoreVecEncoder<E>
 where
-    T: Decoder,
+    E: EncodedLen<T>,
 {
-    pub fn new(decoder: T) -> Self {
-        Self {
-            dec: decoder,
-            ret: Vec::default(),
-            cap: 0,
-        }
+    fn encoded_len(&self, item: &[T; N]) -> usize {
+        self.encoded_len(item.as_slice())
     }
+}
 
-    pub fn into_inner(self) -> T {
-        self.dec
+impl<E, T> EncodedLen<Vec<T>> for CoreVecEncoder<E>
+where
+    E: EncodedLen<T>,
+{
+    fn encoded_len(&self, item: &Vec<T>) -> usize {
+        self.encoded_len(item.as_slice())
     }
 }
 
-impl<T> Default for CoreVecDecoder<T>
+impl<E, T> EncodedLen<Box<[T]>> for CoreVecEncoder<E>
 where
-    T: Decoder + Default,
+    E: EncodedLen<T>,
 {
-    fn default() -> Self {
-        Self::new(T::default())
+    fn encoded_len(&self, item: &Box<[T]>) -> usize {
+        self.encoded_len(item.as_ref())
     }
 }
 
-impl<T> Decoder for CoreVecDecoder<T>
+impl<E, T> EncodedLen<Arc<[T]>> for CoreVecEncoder<E>
 where
-    T: Decoder,
+    E: EncodedLen<T>,
 {
-    type Item = Vec<T::Item>;
-    type Error = T::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        if self.cap == 0 {
-            let Some(len) = Leb128DecoderU32.decode(src)? else {
-                return Ok(None);
-            };
-            if len == 0 {
-                return Ok(Some(Vec::default()));
-            }
-            let len = len
-                .try_into()
-                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
-            self.ret = Vec::with_capacity(len);
-            self.cap = len;
-        }
-        while self.cap > 0 {
-            let Some(v) = self.dec.decode(src)? else {
-                return Ok(None);
-            };
-            self.ret.push(v);
-            self.cap -= 1;
-        }
-        Ok(Some(mem::take(&mut self.ret)))
+    fn encoded_len(&self, item: &Arc<[T]>) -> usize {
+        self.encoded_len(item.as_ref())
     }
 }
 
-/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
-/// encoder optimized for vectors of byte-sized values
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
-pub struct CoreVecEncoderBytes;
+impl<E, T> EncodedLen<VecDeque<T>> for CoreVecEncoder<E>
+where
+    E: EncodedLen<T>,
+{
+    fn encoded_len(&self, item: &VecDeque<T>) -> usize {
+        item.iter().fold(core_vec_len_len(item.len()), |n, item| {
+            n + self.0.encoded_len(item)
+        })
+    }
+}
 
-impl<T: AsRef<[u8]>> Encoder<T> for CoreVecEncoderBytes {
-    type Error = std::io::Error;
+impl<E, T> EncodedLen<BTreeSet<T>> for CoreVecEncoder<E>
+where
+    E: EncodedLen<T>,
+{
+    fn encoded_len(&self, item: &BTreeSet<T>) -> usize {
+        item.iter().fold(core_vec_len_len(item.len()), |n, item| {
+            n + self.0.encoded_len(item)
+        })
+    }
+}
 
-    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        let item = item.as_ref();
-        let n = item.len();
-        let n = u32::try_from(n)
-            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
-        dst.reserve(item.len().saturating_add(5));
-        Leb128Encoder.encode(n, dst)?;
-        dst.extend_from_slice(item);
-        Ok(())
+#[cfg(feature = "std")]
+impl<E, T, S> EncodedLen<HashSet<T, S>> for CoreVecEncoder<E>
+where
+    E: EncodedLen<T>,
+{
+    fn encoded_len(&self, item: &HashSet<T, S>) -> usize {
+        item.iter().fold(core_vec_len_len(item.len()), |n, item| {
+            n + self.0.encoded_len(item)
+        })
     }
 }
 
-/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
-/// decoder optimized for vectors of byte-sized values
-#[derive(Debug, Default)]
-pub struct CoreVecDecoderBytes(usize);
+impl<E, K, V> EncodedLen<BTreeMap<K, V>> for CoreVecEncoder<E>
+where
+    for<'a> E: EncodedLen<(&'a K, &'a V)>,
+{
+    fn encoded_len(&self, item: &BTreeMap<K, V>) -> usize {
+        item.iter().fold(core_vec_len_len(item.len()), |n, item| {
+            n + self.0.encoded_len(&item)
+        })
+    }
+}
 
-impl Decoder for CoreVecDecoderBytes {
+#[cfg(feature = "std")]
+impl<E, K, V, S> EncodedLen<HashMap<K, V, S>> for CoreVecEncoder<E>
+where
+    for<'a> E: EncodedLen<(&'a K, &'a V)>,
+{
+    fn encoded_len(&self, item: &HashMap<K, V, S>) -> usize {
+        item.iter().fold(core_vec_len_len(item.len()), |n, item| {
+            n + self.0.encoded_len(&item)
+        })
+    }
+}
+
+/// The length is computed by iterating over a clone of the wrapped iterator
+impl<E, I> EncodedLen<ExactSizeIter<I>> for CoreVecEncoder<E>
+where
+    I: IntoIterator + Clone,
+    I::IntoIter: ExactSizeIterator,
+    E: EncodedLen<I::Item>,
+{
+    fn encoded_len(&self, item: &ExactSizeIter<I>) -> usize {
+        let iter = item.0.clone().into_iter();
+        let len = core_vec_len_len(iter.len());
+        iter.fold(len, |n, item| n + self.0.encoded_len(&item))
+    }
+}
+
+macro_rules! impl_encoded_len_ref {
+    ([$($p:tt)*] $t:ty) => {
+        impl<'a, $($p)*, E> EncodedLen<&'a $t> for CoreVecEncoder<E>
+        where
+            Self: EncodedLen<$t>,
+        {
+            fn encoded_len(&self, item: &&'a $t) -> usize {
+                self.encoded_len(*item)
+            }
+        }
+    };
+}
+
+impl_encoded_len_ref!([T][T]);
+impl_encoded_len_ref!([T, const N: usize] [T; N]);
+impl_encoded_len_ref!([T] Vec<T>);
+impl_encoded_len_ref!([T] Box<[T]>);
+impl_encoded_len_ref!([T] Arc<[T]>);
+impl_encoded_len_ref!([T] VecDeque<T>);
+impl_encoded_len_ref!([T] BTreeSet<T>);
+#[cfg(feature = "std")]
+impl_encoded_len_ref!([T, S] HashSet<T, S>);
+impl_encoded_len_ref!([K, V] BTreeMap<K, V>);
+#[cfg(feature = "std")]
+impl_encoded_len_ref!([K, V, S] HashMap<K, V, S>);
+impl_encoded_len_ref!(['b, T] &'b [T]);
+impl_encoded_len_ref!(['b, T] &'b Vec<T>);
+
+impl<E> CoreVecEncoder<E> {
+    /// Writes all elements of an [`ExactSizeIterator`] to `dst`, see [`WasmEncode::put_buf`]
+    fn put_iter<I>(&mut self, iter: I, dst: &mut impl BufMut) -> Result<(), Error>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        E: WasmEncode<I::Item>,
+    {
+        let mut iter = iter.into_iter();
+        let len = iter.len();
+        let n = u32::try_from(len).map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        dst.put_u32_leb128(n);
+        let mut n = 0;
+        for item in iter.by_ref().take(len) {
+            self.0.put_buf(item, dst)?;
+            n += 1;
+        }
+        if n != len || iter.next().is_some() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("iterator length does not match reported length of {len}"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_wasm_encode_collection {
+    ([$($p:tt)*] $t:ty, $item:ty) => {
+        impl<E, $($p)*> WasmEncode<$t> for CoreVecEncoder<E>
+        where
+            E: WasmEncode<$item>,
+        {
+            fn put_buf(&mut self, item: $t, dst: &mut impl BufMut) -> Result<(), Error> {
+                self.put_iter(item, dst)
+            }
+        }
+
+        impl<'a, E, $($p)*> WasmEncode<&'a $t> for CoreVecEncoder<E>
+        where
+            E: WasmEncode<&'a $item> + EncodedLen<$item>,
+        {
+            fn put_buf(&mut self, item: &'a $t, dst: &mut impl BufMut) -> Result<(), Error> {
+                self.put_iter(item, dst)
+            }
+        }
+    };
+}
+
+impl_wasm_encode_collection!([T, const N: usize] [T; N], T);
+impl_wasm_encode_collection!([T] Vec<T>, T);
+impl_wasm_encode_collection!([T] VecDeque<T>, T);
+impl_wasm_encode_collection!([T] BTreeSet<T>, T);
+#[cfg(feature = "std")]
+impl_wasm_encode_collection!([T, S] HashSet<T, S>, T);
+
+impl<E, T> WasmEncode<Box<[T]>> for CoreVecEncoder<E>
+where
+    E: WasmEncode<T>,
+{
+    fn put_buf(&mut self, item: Box<[T]>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(Vec::from(item), dst)
+    }
+}
+
+impl<E, T> WasmEncode<Arc<[T]>> for CoreVecEncoder<E>
+where
+    for<'a> E: WasmEncode<&'a T> + EncodedLen<T>,
+{
+    fn put_buf(&mut self, item: Arc<[T]>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(item.iter(), dst)
+    }
+}
+
+impl<'a, E, T> WasmEncode<&'a [T]> for CoreVecEncoder<E>
+where
+    E: WasmEncode<&'a T> + EncodedLen<T>,
+{
+    fn put_buf(&mut self, item: &'a [T], dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(item, dst)
+    }
+}
+
+impl<'a, 'b, E, T> WasmEncode<&'a &'b [T]> for CoreVecEncoder<E>
+where
+    E: WasmEncode<&'b T> + EncodedLen<T>,
+{
+    fn put_buf(&mut self, item: &'a &'b [T], dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(*item, dst)
+    }
+}
+
+impl<'a, 'b, E, T> WasmEncode<&'a &'b Vec<T>> for CoreVecEncoder<E>
+where
+    E: WasmEncode<&'b T> + EncodedLen<T>,
+{
+    fn put_buf(&mut self, item: &'a &'b Vec<T>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(*item, dst)
+    }
+}
+
+impl<'a, E, T> WasmEncode<&'a Box<[T]>> for CoreVecEncoder<E>
+where
+    E: WasmEncode<&'a T> + EncodedLen<T>,
+{
+    fn put_buf(&mut self, item: &'a Box<[T]>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(item.iter(), dst)
+    }
+}
+
+impl<'a, E, T> WasmEncode<&'a Arc<[T]>> for CoreVecEncoder<E>
+where
+    E: WasmEncode<&'a T> + EncodedLen<T>,
+{
+    fn put_buf(&mut self, item: &'a Arc<[T]>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(item.iter(), dst)
+    }
+}
+
+impl<E, K, V> WasmEncode<BTreeMap<K, V>> for CoreVecEncoder<E>
+where
+    E: WasmEncode<(K, V)> + for<'a> EncodedLen<(&'a K, &'a V)>,
+{
+    fn put_buf(&mut self, item: BTreeMap<K, V>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(item, dst)
+    }
+}
+
+impl<'a, E, K, V> WasmEncode<&'a BTreeMap<K, V>> for CoreVecEncoder<E>
+where
+    E: for<'b> WasmEncode<(&'b K, &'b V)>,
+{
+    fn put_buf(&mut self, item: &'a BTreeMap<K, V>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(item, dst)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E, K, V, S> WasmEncode<HashMap<K, V, S>> for CoreVecEncoder<E>
+where
+    E: WasmEncode<(K, V)> + for<'a> EncodedLen<(&'a K, &'a V)>,
+{
+    fn put_buf(&mut self, item: HashMap<K, V, S>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(item, dst)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, E, K, V, S> WasmEncode<&'a HashMap<K, V, S>> for CoreVecEncoder<E>
+where
+    E: for<'b> WasmEncode<(&'b K, &'b V)>,
+{
+    fn put_buf(&mut self, item: &'a HashMap<K, V, S>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(item, dst)
+    }
+}
+
+impl<E, I> WasmEncode<ExactSizeIter<I>> for CoreVecEncoder<E>
+where
+    I: IntoIterator + Clone,
+    I::IntoIter: ExactSizeIterator,
+    E: WasmEncode<I::Item>,
+{
+    fn put_buf(&mut self, item: ExactSizeIter<I>, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.put_iter(item.0, dst)
+    }
+}
+
+/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec) decoder
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct CoreVecDecoder<T: Decoder>(CoreVecCollectDecoder<T, Vec<T::Item>>);
+
+#[cfg(feature = "tokio")]
+impl<T> CoreVecDecoder<T>
+where
+    T: Decoder,
+{
+    pub fn new(decoder: T) -> Self {
+        Self(CoreVecCollectDecoder::new(decoder))
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> Default for CoreVecDecoder<T>
+where
+    T: Decoder + Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> Decoder for CoreVecDecoder<T>
+where
+    T: Decoder,
+{
+    type Item = Vec<T::Item>;
+    type Error = T::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.0.decode(src)
+    }
+}
+
+/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec) decoder,
+/// which collects elements into any [`FromIterator`] collection `C`, e.g.
+/// [`VecDeque`], [`BTreeSet`], [`Box<[T]>`](Box), [`Arc<[T]>`](Arc) or, given a tuple element
+/// decoder, [`HashMap`]
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct CoreVecCollectDecoder<T: Decoder, C> {
+    dec: T,
+    ret: Vec<T::Item>,
+    cap: usize,
+    _ty: PhantomData<fn() -> C>,
+}
+
+#[cfg(feature = "tokio")]
+impl<T, C> CoreVecCollectDecoder<T, C>
+where
+    T: Decoder,
+{
+    pub fn new(decoder: T) -> Self {
+        Self {
+            dec: decoder,
+            ret: Vec::default(),
+            cap: 0,
+            _ty: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.dec
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T, C> Default for CoreVecCollectDecoder<T, C>
+where
+    T: Decoder + Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T, C> Decoder for CoreVecCollectDecoder<T, C>
+where
+    T: Decoder,
+    C: FromIterator<T::Item>,
+{
+    type Item = C;
+    type Error = T::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.cap == 0 {
+            let Some(len) = Leb128DecoderU32.decode(src)? else {
+                return Ok(None);
+            };
+            if len == 0 {
+                return Ok(Some(C::from_iter([])));
+            }
+            let len: usize = len
+                .try_into()
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+            self.ret = Vec::with_capacity(len.min(src.len()));
+            self.cap = len;
+        }
+        while self.cap > 0 {
+            let Some(v) = self.dec.decode(src)? else {
+                return Ok(None);
+            };
+            self.ret.push(v);
+            self.cap -= 1;
+        }
+        Ok(Some(mem::take(&mut self.ret).into_iter().collect()))
+    }
+}
+
+/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+/// encoder optimized for vectors of byte-sized values
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct CoreVecEncoderBytes;
+
+#[cfg(feature = "tokio")]
+impl<T: AsRef<[u8]>> Encoder<T> for CoreVecEncoderBytes {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let item = item.as_ref();
+        let n = item.len();
+        let n = u32::try_from(n)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        dst.reserve(leb128_len_u32(n) + item.len());
+        Leb128Encoder.encode(n, dst)?;
+        dst.extend_from_slice(item);
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> EncodedLen<T> for CoreVecEncoderBytes {
+    fn encoded_len(&self, item: &T) -> usize {
+        let n = item.as_ref().len();
+        core_vec_len_len(n) + n
+    }
+}
+
+impl<T: AsRef<[u8]>> WasmEncode<T> for CoreVecEncoderBytes {
+    fn put_buf(&mut self, item: T, dst: &mut impl BufMut) -> Result<(), Error> {
+        dst.put_core_vec_bytes(item.as_ref())
+    }
+}
+
+/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+/// decoder optimized for vectors of byte-sized values
+#[derive(Debug, Default)]
+#[cfg_attr(not(feature = "tokio"), allow(dead_code))]
+pub struct CoreVecDecoderBytes(usize);
+
+#[cfg(feature = "tokio")]
+impl Decoder for CoreVecDecoderBytes {
     type Item = Bytes;
     type Error = std::io::Error;
 
@@ -383,12 +1366,369 @@ impl Decoder for CoreVecDecoderBytes {
     }
 }
 
-#[cfg(test)]
+/// Zero-copy decoding of values from contiguous input.
+///
+/// Names, strings and byte vectors are returned as slices borrowing from the input,
+/// which allows inspecting values (e.g. for routing) without allocating.
+/// Validation and overflow checks are identical to the corresponding [`Decoder`].
+pub trait DecodeBorrowed {
+    /// Decoded value, which may borrow from the input
+    type Value<'a>;
+
+    /// Decodes a value at the start of `src`.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn decode_borrowed<'a>(&self, src: &'a [u8])
+        -> Result<Option<(Self::Value<'a>, usize)>, Error>;
+}
+
+macro_rules! impl_decode_borrowed_leb128 {
+    ($dec:ident, $t:ty, $f:ident) => {
+        impl DecodeBorrowed for $dec {
+            type Value<'a> = $t;
+
+            fn decode_borrowed<'a>(
+                &self,
+                src: &'a [u8],
+            ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+                $f(src).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+            }
+        }
+    };
+}
+
+impl_decode_borrowed_leb128!(Leb128DecoderU8, u8, decode_u8_leb128);
+impl_decode_borrowed_leb128!(Leb128DecoderU16, u16, decode_u16_leb128);
+impl_decode_borrowed_leb128!(Leb128DecoderU32, u32, decode_u32_leb128);
+impl_decode_borrowed_leb128!(Leb128DecoderU64, u64, decode_u64_leb128);
+impl_decode_borrowed_leb128!(Leb128DecoderU128, u128, decode_u128_leb128);
+impl_decode_borrowed_leb128!(Leb128DecoderI8, i8, decode_i8_leb128);
+impl_decode_borrowed_leb128!(Leb128DecoderI16, i16, decode_i16_leb128);
+impl_decode_borrowed_leb128!(Leb128DecoderI32, i32, decode_i32_leb128);
+impl_decode_borrowed_leb128!(Leb128DecoderI64, i64, decode_i64_leb128);
+impl_decode_borrowed_leb128!(Leb128DecoderI128, i128, decode_i128_leb128);
+
+impl DecodeBorrowed for Utf8Codec {
+    type Value<'a> = char;
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        decode_char_utf8(src).map_err(|err| Error::new(ErrorKind::InvalidInput, err))
+    }
+}
+
+/// Returns the number of bytes the [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+/// length prefix for `len` elements is encoded as.
+/// Lengths exceeding [`u32::MAX`] fail to encode and are counted as the longest prefix.
+pub(crate) fn core_vec_len_len(len: usize) -> usize {
+    u32::try_from(len).map_or(5, leb128_len_u32)
+}
+
+/// Decodes a [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+/// length prefix at the start of `src`.
+/// Returns the element count and the encoded length of the prefix or [`None`] if more data is needed.
+pub(crate) fn decode_core_vec_len(src: &[u8]) -> Result<Option<(usize, usize)>, Error> {
+    let Some((len, n)) = Leb128DecoderU32.decode_borrowed(src)? else {
+        return Ok(None);
+    };
+    let len = len
+        .try_into()
+        .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+    Ok(Some((len, n)))
+}
+
+impl DecodeBorrowed for CoreVecDecoderBytes {
+    type Value<'a> = &'a [u8];
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        let Some((len, n)) = decode_core_vec_len(src)? else {
+            return Ok(None);
+        };
+        let Some(buf) = src[n..].get(..len) else {
+            return Ok(None);
+        };
+        Ok(Some((buf, n + len)))
+    }
+}
+
+impl DecodeBorrowed for CoreNameDecoder {
+    type Value<'a> = &'a str;
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> Result<Option<(Self::Value<'a>, usize)>, Error> {
+        let Some((buf, n)) = self.0.decode_borrowed(src)? else {
+            return Ok(None);
+        };
+        let s = str::from_utf8(buf).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        Ok(Some((s, n)))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> DecodeBorrowed for CoreVecDecoder<T>
+where
+    T: Decoder + DecodeBorrowed,
+{
+    type Value<'a> = Vec<T::Value<'a>>;
+
+    fn decode_borrowed<'a>(
+        &self,
+        src: &'a [u8],
+    ) -> std::io::Result<Option<(Self::Value<'a>, usize)>> {
+        let Some((len, mut n)) = decode_core_vec_len(src)? else {
+            return Ok(None);
+        };
+        let mut ret = Vec::with_capacity(len.min(src.len() - n));
+        for _ in 0..len {
+            let Some((v, k)) = self.0.dec.decode_borrowed(&src[n..])? else {
+                return Ok(None);
+            };
+            ret.push(v);
+            n += k;
+        }
+        Ok(Some((ret, n)))
+    }
+}
+
+/// Validation of values at the start of contiguous input without materialising them,
+/// used by [`SkipDecoder`] and [`RawValueDecoder`]
+pub trait SkipValue {
+    /// Returns the encoded length of the value at the start of `src`
+    /// or [`None`] if more data is needed.
+    /// Validation and overflow checks are identical to the corresponding [`Decoder`].
+    fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error>;
+
+    /// Returns a lower bound for the encoded length of the incomplete value at the start of
+    /// `src`, i.e. one for which [`value_len`](Self::value_len) returned [`None`].
+    /// [`SkipDecoder`] and [`RawValueDecoder`] do not scan `src` again until it holds at least
+    /// this many bytes. Defaults to `src.len() + 1`.
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        src.len() + 1
+    }
+}
+
+/// Returns a lower bound for the encoded length of a
+/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+/// of `size`-byte elements at the start of `src`
+pub(crate) fn min_core_vec_len(src: &[u8], size: usize) -> usize {
+    match decode_core_vec_len(src) {
+        Ok(Some((len, n))) => len.saturating_mul(size).saturating_add(n),
+        _ => src.len() + 1,
+    }
+}
+
+/// Returns a lower bound for the encoded length of a
+/// [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+/// of values skipped by `dec` at the start of `src`
+#[cfg(feature = "tokio")]
+pub(crate) fn min_core_vec_value_len(dec: &impl SkipValue, src: &[u8]) -> usize {
+    let Ok(Some((len, mut n))) = decode_core_vec_len(src) else {
+        return src.len() + 1;
+    };
+    for _ in 0..len {
+        match dec.value_len(&src[n..]) {
+            Ok(Some(k)) => n += k,
+            _ => return n + dec.min_value_len(&src[n..]),
+        }
+    }
+    src.len() + 1
+}
+
+macro_rules! impl_skip_value_borrowed {
+    ($($dec:ty),+ $(,)?) => {
+        $(
+            impl SkipValue for $dec {
+                fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error> {
+                    Ok(self.decode_borrowed(src)?.map(|(_, n)| n))
+                }
+            }
+        )+
+    };
+}
+
+impl_skip_value_borrowed!(
+    Leb128DecoderU8,
+    Leb128DecoderU16,
+    Leb128DecoderU32,
+    Leb128DecoderU64,
+    Leb128DecoderU128,
+    Leb128DecoderI8,
+    Leb128DecoderI16,
+    Leb128DecoderI32,
+    Leb128DecoderI64,
+    Leb128DecoderI128,
+    Utf8Codec,
+);
+
+impl SkipValue for CoreVecDecoderBytes {
+    fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error> {
+        Ok(self.decode_borrowed(src)?.map(|(_, n)| n))
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        min_core_vec_len(src, 1)
+    }
+}
+
+impl SkipValue for CoreNameDecoder {
+    fn value_len(&self, src: &[u8]) -> Result<Option<usize>, Error> {
+        Ok(self.decode_borrowed(src)?.map(|(_, n)| n))
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        min_core_vec_len(src, 1)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> SkipValue for CoreVecDecoder<T>
+where
+    T: Decoder + SkipValue,
+{
+    fn value_len(&self, src: &[u8]) -> std::io::Result<Option<usize>> {
+        self.0.value_len(src)
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        self.0.min_value_len(src)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T, C> SkipValue for CoreVecCollectDecoder<T, C>
+where
+    T: Decoder + SkipValue,
+{
+    fn value_len(&self, src: &[u8]) -> std::io::Result<Option<usize>> {
+        let Some((len, mut n)) = decode_core_vec_len(src)? else {
+            return Ok(None);
+        };
+        for _ in 0..len {
+            let Some(k) = self.dec.value_len(&src[n..])? else {
+                return Ok(None);
+            };
+            n += k;
+        }
+        Ok(Some(n))
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        min_core_vec_value_len(&self.dec, src)
+    }
+}
+
+/// Returns the encoded length of the value at the start of `src` or [`None`] if more data is
+/// needed, in which case the lower bound reported by [`SkipValue::min_value_len`] is stored in
+/// `need`. `src` is not scanned again until it holds `need` bytes.
+///
+/// `need` is derived from untrusted length prefixes, so it is not reserved in `src`.
+#[cfg(feature = "tokio")]
+pub(crate) fn skip_value_len(
+    dec: &impl SkipValue,
+    need: &mut usize,
+    src: &mut BytesMut,
+) -> std::io::Result<Option<usize>> {
+    if src.len() < *need {
+        return Ok(None);
+    }
+    let Some(n) = dec.value_len(src)? else {
+        *need = dec.min_value_len(src);
+        return Ok(None);
+    };
+    *need = 0;
+    Ok(Some(n))
+}
+
+/// Decoder, which advances past exactly one value of the wrapped decoder's type
+/// without materialising it
+#[cfg(feature = "tokio")]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SkipDecoder<T> {
+    dec: T,
+    need: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl<T> SkipDecoder<T> {
+    pub fn new(decoder: T) -> Self {
+        Self {
+            dec: decoder,
+            need: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.dec
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: SkipValue> Decoder for SkipDecoder<T> {
+    type Item = ();
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(n) = skip_value_len(&self.dec, &mut self.need, src)? else {
+            return Ok(None);
+        };
+        src.advance(n);
+        Ok(Some(()))
+    }
+}
+
+/// Decoder, which returns the exact encoded bytes of one value of the wrapped decoder's type,
+/// e.g. for byte-exact pass-through
+#[cfg(feature = "tokio")]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct RawValueDecoder<T> {
+    dec: T,
+    need: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl<T> RawValueDecoder<T> {
+    pub fn new(decoder: T) -> Self {
+        Self {
+            dec: decoder,
+            need: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.dec
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: SkipValue> Decoder for RawValueDecoder<T> {
+    type Item = Bytes;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(n) = skip_value_len(&self.dec, &mut self.need, src)? else {
+            return Ok(None);
+        };
+        Ok(Some(src.split_to(n).freeze()))
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
 mod tests {
     use futures::{SinkExt as _, TryStreamExt as _};
     use tokio_util::codec::{FramedRead, FramedWrite};
     use tracing::trace;
 
+    use std::borrow::Cow;
+    use std::rc::Rc;
+
+    use crate::cm::{PrimValEncoder, TupleDecoder, TupleEncoder};
+
     use super::*;
 
     #[test_log::test(tokio::test)]
@@ -401,6 +1741,14 @@ mod tests {
             .expect("failed to read string");
         assert_eq!(s, "test");
 
+        let mut s = String::new();
+        b"\xff\xff\xff\xff\x0ftest"
+            .as_slice()
+            .read_core_name(&mut s)
+            .await
+            .expect("failed to read truncated string");
+        assert_eq!(s, "test");
+
         let mut buf = vec![];
         buf.write_core_name("test")
             .await
@@ -468,6 +1816,443 @@ mod tests {
         assert_eq!(s, None);
     }
 
+    #[test_log::test]
+    fn buf() {
+        let mut buf = vec![];
+        buf.put_core_name("test").expect("failed to put `test`");
+        buf.put_core_vec_bytes(b"\x01\x02")
+            .expect("failed to put bytes");
+        assert_eq!(buf, b"\x04test\x02\x01\x02");
+
+        let mut buf = Bytes::from(buf);
+        let s = buf.get_core_name().expect("failed to get `test`");
+        assert_eq!(s.as_deref(), Some("test"));
+        let mut short = buf.slice(..2);
+        let v = short.get_core_vec_bytes().expect("failed to get bytes");
+        assert_eq!(v, None);
+        assert_eq!(short, b"\x02\x01".as_slice());
+        let v = buf.get_core_vec_bytes().expect("failed to get bytes");
+        assert_eq!(v.as_deref(), Some(b"\x01\x02".as_slice()));
+
+        let payload = [0x42; 0x80];
+        let mut buf = Buf::chain(
+            [0x80].as_slice(),
+            Buf::chain([0x01].as_slice(), &payload[..]),
+        );
+        let v = buf.get_core_vec_bytes().expect("failed to get bytes");
+        assert_eq!(v.as_deref(), Some(payload.as_slice()));
+        let mut buf = Buf::chain(
+            [0x80].as_slice(),
+            Buf::chain([0x01].as_slice(), &payload[..]),
+        )
+        .take(0x82);
+        let v = buf.get_core_vec_bytes().expect("failed to get bytes");
+        assert_eq!(v, None);
+        assert_eq!(buf.remaining(), 0x82);
+
+        b"\x01\xff"
+            .as_slice()
+            .get_core_name()
+            .expect_err("name get should have failed, since it is not valid UTF-8");
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn segments() {
+        let payload = Bytes::from(vec![0x42; 1000]);
+        let mut segs = Segments::default();
+        CoreNameEncoder
+            .encode_segments("foo".to_string(), &mut segs)
+            .expect("failed to encode name");
+        CoreVecEncoderBytes
+            .encode_segments(payload.clone(), &mut segs)
+            .expect("failed to encode bytes");
+        segs.encode(&mut Leb128Encoder, 42u8)
+            .expect("failed to encode u8");
+
+        let mut expected = BytesMut::default();
+        CoreNameEncoder
+            .encode("foo", &mut expected)
+            .expect("failed to encode name");
+        CoreVecEncoderBytes
+            .encode(&payload, &mut expected)
+            .expect("failed to encode bytes");
+        Leb128Encoder
+            .encode(42u8, &mut expected)
+            .expect("failed to encode u8");
+        assert_eq!(segs.remaining(), expected.len());
+
+        let mut iov = [IoSlice::new(&[]); 4];
+        assert_eq!(segs.chunks_vectored(&mut iov), 3);
+        assert_eq!(&*iov[0], b"\x03foo\xe8\x07");
+        assert_eq!(iov[1].as_ptr(), payload.as_ptr());
+        assert_eq!(&*iov[2], b"\x2a");
+
+        let mut buf = vec![];
+        buf.write_all_buf(&mut segs)
+            .await
+            .expect("failed to write segments");
+        assert_eq!(buf, expected);
+        assert_eq!(segs.remaining(), 0);
+
+        let mut buf = vec![];
+        buf.write_core_vec_bytes(&payload)
+            .await
+            .expect("failed to write bytes");
+        assert_eq!(buf, expected[4..expected.len() - 1]);
+    }
+
+    #[test_log::test]
+    fn encode_buf() {
+        let mut buf = [0u8; 8];
+        let mut dst = buf.as_mut_slice();
+        CoreNameEncoder
+            .encode_buf("test", &mut dst)
+            .expect("failed to encode name");
+        Leb128Encoder
+            .encode_buf(300u32, &mut dst)
+            .expect("failed to encode u32");
+        assert_eq!(dst.len(), 1);
+        let err = CoreNameEncoder
+            .encode_buf("a", &mut dst)
+            .expect_err("encoding should have failed due to insufficient capacity");
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+        Utf8Codec
+            .encode_buf('a', &mut dst)
+            .expect("failed to encode char");
+        assert_eq!(&buf, b"\x04test\xac\x02a");
+
+        let mut buf = Vec::with_capacity(4);
+        let cap = buf.capacity();
+        CoreVecEncoder(Leb128Encoder)
+            .encode_buf([1u8, 2, 3], &mut (&mut buf).limit(cap))
+            .expect("failed to encode vec");
+        CoreVecEncoder(Leb128Encoder)
+            .encode_buf([1u8, 2, 3, 4], &mut (&mut buf).limit(cap - 4))
+            .expect_err("encoding should have failed due to insufficient capacity");
+        assert_eq!(buf, b"\x03\x01\x02\x03");
+        assert_eq!(buf.capacity(), cap);
+    }
+
+    #[test_log::test]
+    fn encoded_len() {
+        fn assert_len<E, T>(mut enc: E, item: T)
+        where
+            E: Encoder<T, Error = std::io::Error> + EncodedLen<T>,
+        {
+            let n = enc.encoded_len(&item);
+            let mut buf = BytesMut::default();
+            enc.encode(item, &mut buf).expect("failed to encode");
+            assert_eq!(n, buf.len());
+        }
+
+        assert_len(CoreNameEncoder, "test");
+        assert_len(CoreNameEncoder, "a".repeat(300));
+        assert_len(CoreVecEncoderBytes, [0u8; 200]);
+        assert_len(CoreVecEncoder(CoreNameEncoder), ["foo", "", "ƒ𐍈Ő"]);
+        assert_len(CoreVecEncoder(CoreNameEncoder), &["foo", "", "ƒ𐍈Ő"]);
+        assert_len(CoreVecEncoder(Leb128Encoder), vec![0u64, 0x80, u64::MAX]);
+        assert_len(
+            CoreVecEncoder(Leb128Encoder),
+            Box::from([-1i32, -65, i32::MIN]),
+        );
+        assert_len(CoreVecEncoder(ZigZagEncoder), vec![-1i16, 64, i16::MIN]);
+        assert_len(CoreVecEncoder(Utf8Codec), vec!['$', 'И', '𐍈']);
+        assert_len(Leb128PaddedEncoder::<5>, 1u32);
+
+        let names = ["a".repeat(200), "b".repeat(300), "c".repeat(100)];
+        let mut buf = BytesMut::default();
+        CoreVecEncoder(CoreNameEncoder)
+            .encode_exact(&names, &mut buf)
+            .expect("failed to encode names");
+        assert_eq!(buf.capacity(), buf.len());
+
+        struct Doubled;
+
+        impl Encoder<u8> for Doubled {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: u8, dst: &mut BytesMut) -> std::io::Result<()> {
+                dst.extend_from_slice(&[item, item]);
+                Ok(())
+            }
+        }
+
+        let mut buf = BytesMut::default();
+        CoreVecEncoder(Doubled)
+            .encode(vec![1, 2], &mut buf)
+            .expect("failed to encode without `EncodedLen`");
+        assert_eq!(buf.as_ref(), b"\x02\x01\x01\x02\x02");
+    }
+
+    #[test_log::test]
+    fn skip() {
+        let mut buf = BytesMut::default();
+        CoreVecEncoder(CoreNameEncoder)
+            .encode(["foo", "", "ƒ𐍈Ő"], &mut buf)
+            .expect("failed to encode names");
+        CoreVecEncoderBytes
+            .encode(b"\x01\x02", &mut buf)
+            .expect("failed to encode bytes");
+        Leb128Encoder
+            .encode(300u32, &mut buf)
+            .expect("failed to encode u32");
+        let expected = buf.clone();
+
+        let mut short = BytesMut::from(&buf[..5]);
+        let v = SkipDecoder::new(CoreVecDecoder::<CoreNameDecoder>::default())
+            .decode(&mut short)
+            .expect("failed to skip short names");
+        assert_eq!(v, None);
+        assert_eq!(short.len(), 5);
+
+        SkipDecoder::new(CoreVecDecoder::<CoreNameDecoder>::default())
+            .decode(&mut buf)
+            .expect("failed to skip names")
+            .expect("short names read");
+        let v = RawValueDecoder::new(CoreVecDecoderBytes::default())
+            .decode(&mut buf)
+            .expect("failed to capture bytes");
+        assert_eq!(v.as_deref(), Some(b"\x02\x01\x02".as_slice()));
+        let v = Leb128DecoderU32
+            .decode(&mut buf)
+            .expect("failed to decode u32");
+        assert_eq!(v, Some(300));
+        assert!(buf.is_empty());
+
+        let mut buf = expected.clone();
+        let v = RawValueDecoder::new(CoreVecDecoder::<CoreNameDecoder>::default())
+            .decode(&mut buf)
+            .expect("failed to capture names")
+            .expect("short names read");
+        assert_eq!(v, expected[..v.len()]);
+        let v = CoreVecDecoder::<CoreNameDecoder>::default()
+            .decode(&mut BytesMut::from(&v[..]))
+            .expect("failed to decode names");
+        assert_eq!(
+            v,
+            Some(vec!["foo".to_string(), String::new(), "ƒ𐍈Ő".to_string()])
+        );
+
+        SkipDecoder::new(CoreNameDecoder::default())
+            .decode(&mut BytesMut::from(b"\x01\xff".as_slice()))
+            .expect_err("name skip should have failed, since it is not valid UTF-8");
+    }
+
+    #[test_log::test]
+    fn skip_incremental() {
+        /// Counts the number of times the wrapped value is scanned
+        #[derive(Default)]
+        struct Scans<T>(T, std::cell::Cell<usize>);
+
+        impl<T: SkipValue> SkipValue for Scans<T> {
+            fn value_len(&self, src: &[u8]) -> std::io::Result<Option<usize>> {
+                self.1.set(self.1.get() + 1);
+                self.0.value_len(src)
+            }
+
+            fn min_value_len(&self, src: &[u8]) -> usize {
+                self.0.min_value_len(src)
+            }
+        }
+
+        let names = [
+            "a".repeat(200),
+            "b".repeat(300),
+            String::new(),
+            "c".repeat(100),
+        ];
+        let mut expected = BytesMut::default();
+        CoreVecEncoder(CoreNameEncoder)
+            .encode(&names, &mut expected)
+            .expect("failed to encode names");
+
+        let mut dec = RawValueDecoder::new(Scans::<CoreVecDecoder<CoreNameDecoder>>::default());
+        let mut buf = BytesMut::default();
+        for (i, b) in expected.iter().enumerate() {
+            buf.put_u8(*b);
+            let v = dec.decode(&mut buf).expect("failed to capture names");
+            if i + 1 < expected.len() {
+                assert_eq!(v, None);
+            } else {
+                assert_eq!(v.as_deref(), Some(expected.as_ref()));
+            }
+        }
+        assert!(buf.is_empty());
+        // each name is scanned once its length prefix and once its contents are complete
+        assert!(dec.into_inner().1.get() <= 2 * names.len() + 2);
+
+        let mut expected = BytesMut::default();
+        CoreVecEncoderBytes
+            .encode([0x2a; 1000], &mut expected)
+            .expect("failed to encode bytes");
+        let mut dec = SkipDecoder::new(Scans::<CoreVecDecoderBytes>::default());
+        let mut buf = BytesMut::default();
+        for b in &expected[..expected.len() - 1] {
+            buf.put_u8(*b);
+            assert_eq!(dec.decode(&mut buf).expect("failed to skip bytes"), None);
+        }
+        assert!(buf.capacity() >= expected.len());
+        buf.put_u8(expected[expected.len() - 1]);
+        assert_eq!(
+            dec.decode(&mut buf).expect("failed to skip bytes"),
+            Some(())
+        );
+        assert!(buf.is_empty());
+        assert_eq!(dec.into_inner().1.get(), 3);
+    }
+
+    #[test_log::test]
+    fn collections() {
+        let mut buf = BytesMut::default();
+        let deque = VecDeque::from(["foo", "bar"]);
+        let set = BTreeSet::from([3u32, 1, 2]);
+        let map = BTreeMap::from([("a".to_string(), 1u8), ("b".to_string(), 2)]);
+        CoreVecEncoder(CoreNameEncoder)
+            .encode(&deque, &mut buf)
+            .expect("failed to encode deque");
+        CoreVecEncoder(Leb128Encoder)
+            .encode(set.clone(), &mut buf)
+            .expect("failed to encode set");
+        CoreVecEncoder(TupleEncoder((CoreNameEncoder, Leb128Encoder)))
+            .encode(&map, &mut buf)
+            .expect("failed to encode map");
+        CoreVecEncoder(CoreNameEncoder)
+            .encode(
+                ExactSizeIter(["baz"].iter().map(|s| s.to_uppercase())),
+                &mut buf,
+            )
+            .expect("failed to encode iterator");
+        assert_eq!(
+            CoreVecEncoder(CoreNameEncoder).encoded_len(&deque)
+                + CoreVecEncoder(Leb128Encoder).encoded_len(&set),
+            13
+        );
+        assert_eq!(
+            CoreVecEncoder(TupleEncoder((CoreNameEncoder, Leb128Encoder))).encoded_len(&map),
+            7
+        );
+        assert_eq!(
+            CoreVecEncoder(CoreNameEncoder)
+                .encoded_len(&ExactSizeIter(["baz"].iter().map(|s| s.to_uppercase()))),
+            5
+        );
+        let mut fixed = [0; 7];
+        CoreVecEncoder(TupleEncoder((CoreNameEncoder, Leb128Encoder)))
+            .encode_buf(
+                HashMap::from([("a".to_string(), 1u8)]),
+                &mut fixed.as_mut_slice(),
+            )
+            .expect("failed to encode map into slice");
+        assert_eq!(&fixed[..5], b"\x01\x01a\x01\x00");
+        assert_eq!(
+            buf.as_ref(),
+            b"\x02\x03foo\x03bar\x03\x01\x02\x03\x02\x01a\x01\x01b\x02\x01\x03BAZ"
+        );
+
+        let v = CoreVecCollectDecoder::<CoreNameDecoder, VecDeque<_>>::default()
+            .decode(&mut buf)
+            .expect("failed to decode deque");
+        assert_eq!(v, Some(VecDeque::from(["foo".into(), "bar".into()])));
+        let v = CoreVecCollectDecoder::<_, HashSet<_>>::new(Leb128DecoderU32)
+            .decode(&mut buf)
+            .expect("failed to decode set");
+        assert_eq!(v, Some(HashSet::from([1, 2, 3])));
+        let v = CoreVecCollectDecoder::<_, HashMap<_, _>>::new(TupleDecoder::<_, _>::new((
+            CoreNameDecoder::default(),
+            Leb128DecoderU8,
+        )))
+        .decode(&mut buf)
+        .expect("failed to decode map");
+        assert_eq!(
+            v,
+            Some(HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]))
+        );
+        let v = CoreVecCollectDecoder::<CoreNameDecoder, String>::default()
+            .decode(&mut buf)
+            .expect("failed to decode string");
+        assert_eq!(v.as_deref(), Some("BAZ"));
+        assert!(buf.is_empty());
+
+        CoreVecEncoder(Leb128Encoder)
+            .encode([1u8, 2], &mut buf)
+            .expect("failed to encode array");
+        CoreVecEncoder(Leb128Encoder)
+            .encode([3u8], &mut buf)
+            .expect("failed to encode array");
+        let v = CoreVecCollectDecoder::<_, Box<[_]>>::new(Leb128DecoderU8)
+            .decode(&mut buf)
+            .expect("failed to decode boxed slice");
+        assert_eq!(v, Some(Box::from([1, 2])));
+        let v = CoreVecCollectDecoder::<_, Arc<[_]>>::new(Leb128DecoderU8)
+            .decode(&mut buf)
+            .expect("failed to decode `Arc` slice");
+        assert_eq!(v, Some(Arc::from([3])));
+        assert!(buf.is_empty());
+
+        let mut buf = BytesMut::from(b"\xff\xff\xff\xff\x0f".as_slice());
+        let mut dec = CoreVecCollectDecoder::<CoreNameDecoder, Vec<_>>::default();
+        let v = dec.decode(&mut buf).expect("failed to decode length");
+        assert_eq!(v, None);
+        assert!(dec.ret.capacity() < 5);
+
+        let mut buf = BytesMut::default();
+        PrimValEncoder
+            .encode(Cow::Borrowed("a"), &mut buf)
+            .expect("failed to encode `Cow<str>`");
+        PrimValEncoder
+            .encode(&Rc::<str>::from("b"), &mut buf)
+            .expect("failed to encode `Rc<str>`");
+        PrimValEncoder
+            .encode(Box::<str>::from("c"), &mut buf)
+            .expect("failed to encode `Box<str>`");
+        assert_eq!(buf.as_ref(), b"\x01a\x01b\x01c");
+        assert_eq!(PrimValEncoder.encoded_len(&Rc::<str>::from("b")), 2);
+
+        struct Liar(core::ops::Range<u8>);
+
+        impl Iterator for Liar {
+            type Item = u8;
+
+            fn next(&mut self) -> Option<u8> {
+                self.0.next()
+            }
+        }
+
+        impl ExactSizeIterator for Liar {
+            fn len(&self) -> usize {
+                2
+            }
+        }
+
+        let mut buf = BytesMut::from(b"\x2a".as_slice());
+        CoreVecEncoder(Leb128Encoder)
+            .encode(ExactSizeIter(Liar(0..1)), &mut buf)
+            .expect_err("encoding should have failed, since the iterator yields too few items");
+        assert_eq!(buf.as_ref(), b"\x2a");
+        CoreVecEncoder(Leb128Encoder)
+            .encode(ExactSizeIter(Liar(0..3)), &mut buf)
+            .expect_err("encoding should have failed, since the iterator yields too many items");
+        assert_eq!(buf.as_ref(), b"\x2a");
+
+        struct OddEncoder;
+
+        impl Encoder<u8> for OddEncoder {
+            type Error = std::io::Error;
+
+            fn encode(&mut self, item: u8, dst: &mut BytesMut) -> Result<(), Self::Error> {
+                if item.is_multiple_of(2) {
+                    return Err(std::io::ErrorKind::InvalidInput.into());
+                }
+                Leb128Encoder.encode(item, dst)
+            }
+        }
+
+        CoreVecEncoder(OddEncoder)
+            .encode(ExactSizeIter([1, 2]), &mut buf)
+            .expect_err("encoding should have failed, since an element failed to encode");
+        assert_eq!(buf.as_ref(), b"\x2a");
+    }
+
     #[test_log::test(tokio::test)]
     async fn vec() {
         let mut tx = FramedWrite::new(Vec::new(), CoreVecEncoder(CoreNameEncoder));
@@ -0,0 +1,215 @@
+//! Extensions for [`futures_io`] readers and writers
+
+use ::core::future::{poll_fn, Future};
+use ::core::pin::Pin;
+
+pub use leb128_tokio::futures_io::*;
+pub use utf8_tokio::futures_io::*;
+
+use crate::cm::BufValueExt as _;
+use crate::READ_CHUNK_LEN;
+
+/// Core value extensions for [`futures_io::AsyncRead`], producing
+/// values identical to [`ReadCore`](crate::blocking::ReadCore)
+///
+/// The methods of this trait are not cancellation safe, see
+/// [`ValueReader`](crate::ValueReader#cancel-safety).
+pub trait AsyncReadCore: ::futures_io::AsyncRead {
+    /// Reads [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names)
+    ///
+    /// This method is not cancellation safe, see [`ValueReader`](crate::ValueReader#cancel-safety).
+    fn read_core_name(&mut self, s: &mut String) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let n = self.read_u32_leb128().await?;
+            let n = n.try_into().unwrap_or(usize::MAX);
+            // `n` is untrusted, so grow the buffer in bounded chunks as data arrives
+            let mut buf = Vec::default();
+            while buf.len() < n {
+                let k = buf.len();
+                buf.resize(n.min(k.saturating_add(READ_CHUNK_LEN)), 0);
+                let n = poll_fn(|cx| Pin::new(&mut *self).poll_read(cx, &mut buf[k..])).await?;
+                buf.truncate(k + n);
+                if n == 0 {
+                    break;
+                }
+            }
+            let buf = String::from_utf8(buf)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+            s.push_str(&buf);
+            Ok(())
+        }
+    }
+}
+
+impl<T: ::futures_io::AsyncRead + ?Sized> AsyncReadCore for T {}
+
+/// Core value extensions for [`futures_io::AsyncWrite`], producing
+/// bytes identical to [`WriteCore`](crate::blocking::WriteCore)
+pub trait AsyncWriteCore: ::futures_io::AsyncWrite {
+    /// Writes [`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names)
+    fn write_core_name(&mut self, s: &str) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        self.write_core_vec_bytes(s.as_bytes())
+    }
+
+    /// Writes [`core:vec`](https://webassembly.github.io/spec/core/binary/conventions.html#binary-vec)
+    /// of bytes
+    fn write_core_vec_bytes(&mut self, buf: &[u8]) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let n = u32::try_from(buf.len())
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+            self.write_u32_leb128(n).await?;
+            write_all(self, buf).await
+        }
+    }
+}
+
+impl<T: ::futures_io::AsyncWrite + ?Sized> AsyncWriteCore for T {}
+
+/// Component model value extensions for [`futures_io::AsyncRead`],
+/// producing values identical to [`ReadValue`](crate::blocking::ReadValue)
+///
+/// The methods of this trait are not cancellation safe, see
+/// [`ValueReader`](crate::ValueReader#cancel-safety).
+pub trait AsyncReadValue: ::futures_io::AsyncRead {
+    /// Reads `bool`
+    ///
+    /// This method is not cancellation safe, see [`ValueReader`](crate::ValueReader#cancel-safety).
+    fn read_bool(&mut self) -> impl Future<Output = std::io::Result<bool>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let mut b = [0];
+            read_exact(self, &mut b).await?;
+            b.as_slice().get_bool().map(Option::unwrap_or_default)
+        }
+    }
+
+    /// Reads `option` status, [`true`] if the option is `some`
+    ///
+    /// This method is not cancellation safe, see [`ValueReader`](crate::ValueReader#cancel-safety).
+    fn read_option_status(&mut self) -> impl Future<Output = std::io::Result<bool>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let mut b = [0];
+            read_exact(self, &mut b).await?;
+            b.as_slice()
+                .get_option_status()
+                .map(Option::unwrap_or_default)
+        }
+    }
+
+    /// Reads `result` status, [`true`] if the result is `ok`
+    ///
+    /// This method is not cancellation safe, see [`ValueReader`](crate::ValueReader#cancel-safety).
+    fn read_result_status(&mut self) -> impl Future<Output = std::io::Result<bool>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let mut b = [0];
+            read_exact(self, &mut b).await?;
+            b.as_slice()
+                .get_result_status()
+                .map(Option::unwrap_or_default)
+        }
+    }
+}
+
+impl<T: ::futures_io::AsyncRead + ?Sized> AsyncReadValue for T {}
+
+/// Component model value extensions for [`futures_io::AsyncWrite`],
+/// producing bytes identical to [`WriteValue`](crate::blocking::WriteValue)
+pub trait AsyncWriteValue: ::futures_io::AsyncWrite {
+    /// Writes `bool`
+    fn write_bool(&mut self, v: bool) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move { write_all(self, &[v.into()]).await }
+    }
+
+    /// Writes `option` status
+    fn write_option_status<T>(&mut self, v: Option<T>) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move { write_all(self, &[v.is_some().into()]).await }
+    }
+
+    /// Writes `result` status
+    fn write_result_status<T, E>(
+        &mut self,
+        v: Result<T, E>,
+    ) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move { write_all(self, &[v.is_err().into()]).await }
+    }
+}
+
+impl<T: ::futures_io::AsyncWrite + ?Sized> AsyncWriteValue for T {}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::io::Cursor;
+
+    use super::*;
+
+    #[test_log::test]
+    fn futures_io() {
+        block_on(run());
+    }
+
+    async fn run() {
+        let mut w = Cursor::new(vec![]);
+        w.write_core_name("foo")
+            .await
+            .expect("failed to write name");
+        w.write_u32_leb128(300).await.expect("failed to write u32");
+        w.write_char_utf8('ƒ').await.expect("failed to write char");
+        w.write_option_status(Some(()))
+            .await
+            .expect("failed to write option status");
+        w.write_result_status(Err::<(), ()>(()))
+            .await
+            .expect("failed to write result status");
+        w.write_bool(false).await.expect("failed to write bool");
+
+        let mut r = Cursor::new(w.into_inner());
+        let mut s = String::new();
+        r.read_core_name(&mut s).await.expect("failed to read name");
+        assert_eq!(s, "foo");
+        assert_eq!(r.read_u32_leb128().await.expect("failed to read u32"), 300);
+        assert_eq!(r.read_char_utf8().await.expect("failed to read char"), 'ƒ');
+        assert!(r
+            .read_option_status()
+            .await
+            .expect("failed to read option status"));
+        assert!(!r
+            .read_result_status()
+            .await
+            .expect("failed to read result status"));
+        assert!(!r.read_bool().await.expect("failed to read bool"));
+
+        let mut r = Cursor::new(b"\xff\xff\xff\xff\x0ffoo");
+        let mut s = String::new();
+        r.read_core_name(&mut s)
+            .await
+            .expect("failed to read truncated name");
+        assert_eq!(s, "foo");
+    }
+}
@@ -0,0 +1,404 @@
+use ::core::future::Future;
+
+use leb128_tokio::BufLeb128Ext as _;
+use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
+use tokio_util::bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+use utf8_tokio::BufUtf8Ext as _;
+
+use crate::cm::BufValueExt as _;
+use crate::BufCoreExt as _;
+
+/// Number of bytes reserved in the read buffer, if it has no spare capacity
+const READ_CAPACITY: usize = 8 * 1024;
+
+/// Extension trait driving any [`Decoder`] directly from an [`AsyncRead`]
+pub trait AsyncReadDecoder: AsyncRead {
+    /// Reads a single value using `dec`, reading data into `buf` until `dec` produces a value.
+    /// Bytes read past the end of the value are left in `buf`, so that subsequent reads may
+    /// use a different decoder.
+    ///
+    /// Returns [`std::io::ErrorKind::UnexpectedEof`] if the reader reaches EOF before a complete
+    /// value is read.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancellation safe, as long as `buf` and `dec` are reused for the
+    /// subsequent call. All bytes read are stored in `buf` and all decoding state in `dec`.
+    fn read_value<D>(
+        &mut self,
+        buf: &mut BytesMut,
+        dec: &mut D,
+    ) -> impl Future<Output = Result<D::Item, D::Error>>
+    where
+        Self: Unpin + Sized,
+        D: Decoder,
+        D::Error: From<std::io::Error>,
+    {
+        async move {
+            loop {
+                if let Some(v) = dec.decode(buf)? {
+                    return Ok(v);
+                }
+                if buf.capacity() == buf.len() {
+                    buf.reserve(READ_CAPACITY);
+                }
+                if self.read_buf(buf).await? == 0 {
+                    return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+                }
+            }
+        }
+    }
+}
+
+impl<T: AsyncRead> AsyncReadDecoder for T {}
+
+macro_rules! impl_read_buf {
+    ($($name:ident: $t:ty => $get:ident, $ty:literal;)+) => {
+        $(
+            #[doc = concat!("Reads ", $ty, ".")]
+            #[doc = ""]
+            #[doc = "# Cancel safety"]
+            #[doc = ""]
+            #[doc = "This method is cancellation safe. Partially read values are kept in the buffer."]
+            pub async fn $name(&mut self) -> std::io::Result<$t> {
+                loop {
+                    if let Some(v) = self.buf.$get()? {
+                        return Ok(v);
+                    }
+                    self.fill_buf().await?;
+                }
+            }
+        )+
+    };
+}
+
+/// Buffered reader, which stores all partially read data in an internal buffer, making
+/// all reads cancellation safe.
+///
+/// # Cancel safety
+///
+/// The `AsyncRead*` extension traits, e.g. [`AsyncReadCore`](crate::AsyncReadCore) and
+/// [`AsyncReadValue`](crate::cm::AsyncReadValue), are not cancellation safe. If a future
+/// returned by them is dropped before completion, bytes read so far are lost and the stream
+/// is left desynchronized.
+///
+/// All reads of [`ValueReader`] are cancellation safe. Dropping a read future, e.g. in a
+/// `tokio::select!` branch, does not lose any data and the next read resumes where the
+/// dropped one left off.
+#[derive(Debug)]
+pub struct ValueReader<R> {
+    r: R,
+    buf: BytesMut,
+}
+
+impl<R> ValueReader<R> {
+    /// Constructs a new [`ValueReader`]
+    pub fn new(r: R) -> Self {
+        Self::with_buffer(r, BytesMut::default())
+    }
+
+    /// Constructs a new [`ValueReader`] using `buf` as the read buffer. Contents of `buf`
+    /// are read before any data from `r`.
+    pub fn with_buffer(r: R, buf: BytesMut) -> Self {
+        Self { r, buf }
+    }
+
+    /// Returns the bytes read from the underlying reader, but not yet decoded
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Returns a reference to the underlying reader
+    pub fn get_ref(&self) -> &R {
+        &self.r
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    /// Note, that reading from it directly skips buffered data.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.r
+    }
+
+    /// Returns the underlying reader and the read buffer
+    pub fn into_parts(self) -> (R, BytesMut) {
+        (self.r, self.buf)
+    }
+}
+
+impl<R: AsyncRead + Unpin> ValueReader<R> {
+    /// Reads a single value using `dec`.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancellation safe, as long as `dec` is reused for the subsequent call.
+    /// All decoding state of partially read values is stored in `dec`.
+    pub async fn read_value<D>(&mut self, dec: &mut D) -> Result<D::Item, D::Error>
+    where
+        D: Decoder,
+        D::Error: From<std::io::Error>,
+    {
+        self.r.read_value(&mut self.buf, dec).await
+    }
+
+    impl_read_buf! {
+        read_u8_leb128: u8 => get_u8_leb128, "LEB128-encoded `u8`";
+        read_u16_leb128: u16 => get_u16_leb128, "LEB128-encoded `u16`";
+        read_u32_leb128: u32 => get_u32_leb128, "LEB128-encoded `u32`";
+        read_u64_leb128: u64 => get_u64_leb128, "LEB128-encoded `u64`";
+        read_u128_leb128: u128 => get_u128_leb128, "LEB128-encoded `u128`";
+        read_i8_leb128: i8 => get_i8_leb128, "LEB128-encoded `i8`";
+        read_i16_leb128: i16 => get_i16_leb128, "LEB128-encoded `i16`";
+        read_i32_leb128: i32 => get_i32_leb128, "LEB128-encoded `i32`";
+        read_i64_leb128: i64 => get_i64_leb128, "LEB128-encoded `i64`";
+        read_i128_leb128: i128 => get_i128_leb128, "LEB128-encoded `i128`";
+        read_char_utf8: char => get_char_utf8, "UTF-8-encoded `char`";
+        read_core_name: String => get_core_name, "[`core:name`](https://webassembly.github.io/spec/core/binary/values.html#names)";
+        read_bool: bool => get_bool, "`bool`";
+        read_option_status: bool => get_option_status, "`option` status, [`true`] if the option is `some`";
+        read_result_status: bool => get_result_status, "`result` status, [`true`] if the result is `ok`";
+    }
+
+    async fn fill_buf(&mut self) -> std::io::Result<()> {
+        if self.buf.capacity() == self.buf.len() {
+            self.buf.reserve(READ_CAPACITY);
+        }
+        if self.r.read_buf(&mut self.buf).await? == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(())
+    }
+}
+
+/// [`ValueWriter`] statistics
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValueWriterStats {
+    /// Total number of bytes encoded into the buffer
+    pub bytes_buffered: u64,
+    /// Total number of bytes written to the underlying writer
+    pub bytes_written: u64,
+    /// Number of times the buffer was flushed to the underlying writer
+    pub flushes: u64,
+}
+
+/// Buffered writer, which encodes values into a reusable buffer and writes them to
+/// the underlying [`AsyncWrite`] in batches, once the buffer reaches the flush threshold
+/// or [`flush`](Self::flush) is called.
+#[derive(Debug)]
+pub struct ValueWriter<W> {
+    w: W,
+    buf: BytesMut,
+    threshold: usize,
+    stats: ValueWriterStats,
+}
+
+impl<W> ValueWriter<W> {
+    /// Default flush threshold in bytes
+    pub const DEFAULT_THRESHOLD: usize = 8 * 1024;
+
+    /// Constructs a new [`ValueWriter`] with [`Self::DEFAULT_THRESHOLD`]
+    pub fn new(w: W) -> Self {
+        Self::with_threshold(w, Self::DEFAULT_THRESHOLD)
+    }
+
+    /// Constructs a new [`ValueWriter`], which flushes the buffer once it holds at least
+    /// `threshold` bytes
+    pub fn with_threshold(w: W, threshold: usize) -> Self {
+        Self {
+            w,
+            buf: BytesMut::with_capacity(threshold),
+            threshold,
+            stats: ValueWriterStats::default(),
+        }
+    }
+
+    /// Returns the bytes buffered, but not yet written
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Returns the writer statistics
+    pub fn stats(&self) -> ValueWriterStats {
+        self.stats
+    }
+
+    /// Returns a reference to the underlying writer
+    pub fn get_ref(&self) -> &W {
+        &self.w
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    /// Note, that writing to it directly may interleave with buffered data.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.w
+    }
+
+    /// Returns the underlying writer, discarding any buffered data
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+}
+
+impl<W: AsyncWrite + Unpin> ValueWriter<W> {
+    /// Encodes `item` using `enc` and flushes the buffer to the underlying writer if the flush
+    /// threshold is reached. If encoding fails, nothing is buffered.
+    pub async fn write_value<T, E>(&mut self, enc: &mut E, item: T) -> Result<(), E::Error>
+    where
+        E: Encoder<T>,
+        E::Error: From<std::io::Error>,
+    {
+        let n = self.buf.len();
+        if let Err(err) = enc.encode(item, &mut self.buf) {
+            self.buf.truncate(n);
+            return Err(err);
+        }
+        self.stats.bytes_buffered += (self.buf.len() - n) as u64;
+        if self.buf.len() >= self.threshold {
+            self.flush_buf().await?;
+        }
+        Ok(())
+    }
+
+    /// Writes all buffered data and flushes the underlying writer
+    pub async fn flush(&mut self) -> std::io::Result<()> {
+        self.flush_buf().await?;
+        self.w.flush().await
+    }
+
+    async fn flush_buf(&mut self) -> std::io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        self.stats.flushes += 1;
+        while !self.buf.is_empty() {
+            let n = self.w.write_buf(&mut self.buf).await?;
+            if n == 0 {
+                return Err(std::io::ErrorKind::WriteZero.into());
+            }
+            self.stats.bytes_written += n as u64;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cm::{F32Codec, U32Codec};
+    use crate::{CoreNameDecoder, CoreNameEncoder, Leb128Encoder};
+
+    use super::*;
+
+    #[test_log::test(tokio::test)]
+    async fn read_value() {
+        let mut r: &[u8] = b"\x03foo\xac\x02\x00\x00\x80\x3f\x03ba";
+        let mut buf = BytesMut::default();
+        let s = r
+            .read_value(&mut buf, &mut CoreNameDecoder::default())
+            .await
+            .expect("failed to read name");
+        assert_eq!(s, "foo");
+        assert!(!buf.is_empty());
+        let v = r
+            .read_value(&mut buf, &mut U32Codec)
+            .await
+            .expect("failed to read u32");
+        assert_eq!(v, 300);
+        let v = r
+            .read_value(&mut buf, &mut F32Codec)
+            .await
+            .expect("failed to read f32");
+        assert_eq!(v, 1.0);
+        let err = r
+            .read_value(&mut buf, &mut CoreNameDecoder::default())
+            .await
+            .expect_err("reading truncated name should have failed");
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn reader() {
+        let (mut tx, rx) = tokio::io::duplex(64);
+        let mut r = ValueReader::new(rx);
+        {
+            let fut = r.read_core_name();
+            tokio::pin!(fut);
+            tx.write_all(b"\x03f").await.expect("failed to write");
+            tokio::select! {
+                biased;
+                _ = &mut fut => panic!("name should not have been read"),
+                () = tokio::task::yield_now() => {}
+            }
+        }
+        assert_eq!(r.buffer(), b"\x03f");
+        tx.write_all(b"oo\xac\x02\x01")
+            .await
+            .expect("failed to write");
+        let s = r.read_core_name().await.expect("failed to read name");
+        assert_eq!(s, "foo");
+        let v = r.read_u32_leb128().await.expect("failed to read u32");
+        assert_eq!(v, 300);
+        assert!(r.read_bool().await.expect("failed to read bool"));
+
+        let mut dec = CoreNameDecoder::default();
+        {
+            let fut = r.read_value(&mut dec);
+            tokio::pin!(fut);
+            tx.write_all(b"\x04ba").await.expect("failed to write");
+            tokio::select! {
+                biased;
+                _ = &mut fut => panic!("name should not have been read"),
+                () = tokio::task::yield_now() => {}
+            }
+        }
+        tx.write_all(b"rr").await.expect("failed to write");
+        let s = r.read_value(&mut dec).await.expect("failed to read name");
+        assert_eq!(s, "barr");
+        drop(tx);
+        let err = r
+            .read_option_status()
+            .await
+            .expect_err("reading past EOF should have failed");
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn writer() {
+        let mut w = ValueWriter::with_threshold(vec![], 8);
+        w.write_value(&mut CoreNameEncoder, "foo")
+            .await
+            .expect("failed to write name");
+        w.write_value(&mut Leb128Encoder, 300u32)
+            .await
+            .expect("failed to write u32");
+        assert_eq!(w.buffer(), b"\x03foo\xac\x02");
+        assert!(w.get_ref().is_empty());
+        assert_eq!(
+            w.stats(),
+            ValueWriterStats {
+                bytes_buffered: 6,
+                bytes_written: 0,
+                flushes: 0,
+            }
+        );
+
+        w.write_value(&mut F32Codec, 1.0)
+            .await
+            .expect("failed to write f32");
+        assert!(w.buffer().is_empty());
+        assert_eq!(w.get_ref(), b"\x03foo\xac\x02\0\0\x80\x3f");
+
+        w.write_value(&mut CoreNameEncoder, "bar")
+            .await
+            .expect("failed to write name");
+        w.flush().await.expect("failed to flush");
+        assert_eq!(
+            w.stats(),
+            ValueWriterStats {
+                bytes_buffered: 14,
+                bytes_written: 14,
+                flushes: 2,
+            }
+        );
+        assert_eq!(w.into_inner(), b"\x03foo\xac\x02\0\0\x80\x3f\x03bar");
+    }
+}
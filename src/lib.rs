@@ -1,13 +1,116 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::module_name_repetitions)]
 
+extern crate alloc;
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
 mod core;
+#[cfg(feature = "tokio")]
+mod io;
+#[cfg(feature = "tokio")]
+mod map;
+
+#[cfg(feature = "std")]
+pub mod blocking;
+
+#[cfg(feature = "futures-io")]
+pub mod futures_io;
 
 /// [Component model](https://component-model.bytecodealliance.org/) codec
 pub mod cm;
 
 pub use core::*;
+#[cfg(feature = "tokio")]
+pub use io::*;
 pub use leb128_tokio::*;
+#[cfg(feature = "tokio")]
+pub use map::*;
 pub use utf8_tokio::*;
 
+pub use bytes;
+#[cfg(feature = "tokio")]
 pub use tokio;
+#[cfg(feature = "tokio")]
 pub use tokio_util;
+
+/// Maximum number of bytes read at once into a buffer sized by an untrusted length prefix
+#[cfg(feature = "std")]
+pub(crate) const READ_CHUNK_LEN: usize = 8192;
+
+/// Exact length of values encoded by an encoder, which can be used to pre-size buffers,
+/// fill length headers in advance or enforce size quotas before serializing
+pub trait EncodedLen<T: ?Sized> {
+    /// Returns the exact number of bytes `item` is encoded as
+    fn encoded_len(&self, item: &T) -> usize;
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use bytes::Buf as _;
+
+    use crate::cm::{
+        BoolCodec, BufMutValueExt as _, BufValueExt as _, OptionDecoder, ResultDecoder, U32Codec,
+        U8ListCodec,
+    };
+    use crate::{
+        BufCoreExt as _, BufMutCoreExt as _, BufMutLeb128Ext as _, CoreNameDecoder,
+        CoreNameEncoder, DecodeBorrowed as _, EncodedLen as _, Leb128Encoder, SkipValue as _,
+    };
+
+    #[test_log::test]
+    fn alloc() {
+        let mut buf = Vec::new();
+        buf.put_bool(true);
+        buf.put_core_name("test").expect("failed to put `test`");
+        buf.put_option_status(Some(()));
+        buf.put_u32_leb128(300);
+        buf.put_result_status(Result::<(), ()>::Err(()));
+        buf.put_core_vec_bytes(b"\x01\x02")
+            .expect("failed to put bytes");
+        assert_eq!(buf, b"\x01\x04test\x01\xac\x02\x01\x02\x01\x02".as_slice());
+        assert_eq!(CoreNameEncoder.encoded_len("test"), 5);
+        assert_eq!(Leb128Encoder.encoded_len(&300u32), 2);
+
+        let (v, n) = BoolCodec
+            .decode_borrowed(&buf)
+            .expect("failed to decode bool")
+            .expect("bool incomplete");
+        assert!(v);
+        assert_eq!(n, 1);
+        let (v, n) = CoreNameDecoder::default()
+            .decode_borrowed(&buf[1..])
+            .expect("failed to decode name")
+            .expect("name incomplete");
+        assert_eq!(v, "test");
+        assert_eq!(n, 5);
+        let dec = OptionDecoder::new(U32Codec);
+        assert_eq!(
+            dec.decode_borrowed(&buf[6..])
+                .expect("failed to decode option"),
+            Some((Some(300), 3))
+        );
+        assert_eq!(dec.value_len(&buf[6..8]).expect("failed to skip"), None);
+        let dec = ResultDecoder::err_only(U8ListCodec::default());
+        assert_eq!(
+            dec.value_len(&buf[9..]).expect("failed to skip result"),
+            Some(4)
+        );
+
+        let mut src = buf.as_slice().chain(&[][..]);
+        assert_eq!(src.get_bool().expect("failed to get bool"), Some(true));
+        assert_eq!(
+            src.get_core_name().expect("failed to get name").as_deref(),
+            Some("test")
+        );
+        let mut src = [0x80].chain(&b"\x01a"[..]).take(3);
+        assert_eq!(
+            src.get_core_name()
+                .expect("failed to get partially exposed name"),
+            None
+        );
+        assert_eq!(src.remaining(), 3);
+    }
+}
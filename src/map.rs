@@ -0,0 +1,256 @@
+use tokio_util::bytes::{BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{EncodedLen, Error, SkipValue, WasmEncode};
+
+/// Encoder, which maps values using a function before encoding them with the wrapped encoder,
+/// constructed by [`EncoderExt::map_encode`].
+///
+/// [`EncodedLen`], and hence [`WasmEncode`], are only implemented for [`Fn`] functions and
+/// [`Clone`] values, since the length is computed by applying the function to a clone of the
+/// value. Encoders computing the length of a value before encoding it, e.g.
+/// [`CoreVecEncoder`](crate::CoreVecEncoder), hence call the function twice per value.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct MapEncoder<C, F> {
+    enc: C,
+    f: F,
+}
+
+impl<C, F> MapEncoder<C, F> {
+    /// Returns an encoder, which applies `f` to values before encoding them with `encoder`
+    pub fn new(encoder: C, f: F) -> Self {
+        Self { enc: encoder, f }
+    }
+
+    /// Returns the wrapped encoder
+    pub fn into_inner(self) -> C {
+        self.enc
+    }
+}
+
+impl<C, F, T, U> Encoder<T> for MapEncoder<C, F>
+where
+    C: Encoder<U>,
+    F: FnMut(T) -> U,
+{
+    type Error = C::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        self.enc.encode((self.f)(item), dst)
+    }
+}
+
+impl<C, F, T, U> EncodedLen<T> for MapEncoder<C, F>
+where
+    C: EncodedLen<U>,
+    F: Fn(T) -> U,
+    T: Clone,
+{
+    fn encoded_len(&self, item: &T) -> usize {
+        self.enc.encoded_len(&(self.f)(item.clone()))
+    }
+}
+
+impl<C, F, T, U> WasmEncode<T> for MapEncoder<C, F>
+where
+    C: WasmEncode<U>,
+    F: Fn(T) -> U,
+    T: Clone,
+{
+    fn put_buf(&mut self, item: T, dst: &mut impl BufMut) -> Result<(), Error> {
+        self.enc.put_buf((self.f)(item), dst)
+    }
+}
+
+/// Decoder, which maps values decoded by the wrapped decoder using a function,
+/// constructed by [`DecoderExt::map`]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct MapDecoder<C, F> {
+    dec: C,
+    f: F,
+}
+
+impl<C, F> MapDecoder<C, F> {
+    /// Returns a decoder, which applies `f` to values decoded by `decoder`
+    pub fn new(decoder: C, f: F) -> Self {
+        Self { dec: decoder, f }
+    }
+
+    /// Returns the wrapped decoder
+    pub fn into_inner(self) -> C {
+        self.dec
+    }
+}
+
+impl<C, F, T> Decoder for MapDecoder<C, F>
+where
+    C: Decoder,
+    F: FnMut(C::Item) -> T,
+{
+    type Item = T;
+    type Error = C::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(v) = self.dec.decode(src)? else {
+            return Ok(None);
+        };
+        Ok(Some((self.f)(v)))
+    }
+}
+
+impl<C: SkipValue, F> SkipValue for MapDecoder<C, F> {
+    fn value_len(&self, src: &[u8]) -> std::io::Result<Option<usize>> {
+        self.dec.value_len(src)
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        self.dec.min_value_len(src)
+    }
+}
+
+/// Decoder, which maps values decoded by the wrapped decoder using a fallible function,
+/// constructed by [`DecoderExt::try_map`].
+///
+/// Errors returned by the function are converted into [`std::io::ErrorKind::InvalidData`]
+/// errors.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TryMapDecoder<C, F> {
+    dec: C,
+    f: F,
+}
+
+impl<C, F> TryMapDecoder<C, F> {
+    /// Returns a decoder, which applies fallible `f` to values decoded by `decoder`
+    pub fn new(decoder: C, f: F) -> Self {
+        Self { dec: decoder, f }
+    }
+
+    /// Returns the wrapped decoder
+    pub fn into_inner(self) -> C {
+        self.dec
+    }
+}
+
+impl<C, F, T, E> Decoder for TryMapDecoder<C, F>
+where
+    C: Decoder,
+    C::Error: From<std::io::Error>,
+    F: FnMut(C::Item) -> Result<T, E>,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Item = T;
+    type Error = C::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(v) = self.dec.decode(src)? else {
+            return Ok(None);
+        };
+        let v =
+            (self.f)(v).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        Ok(Some(v))
+    }
+}
+
+impl<C: SkipValue, F> SkipValue for TryMapDecoder<C, F> {
+    fn value_len(&self, src: &[u8]) -> std::io::Result<Option<usize>> {
+        self.dec.value_len(src)
+    }
+
+    fn min_value_len(&self, src: &[u8]) -> usize {
+        self.dec.min_value_len(src)
+    }
+}
+
+/// Combinator extensions for [`Encoder`]
+pub trait EncoderExt<T>: Encoder<T> + Sized {
+    /// Returns an encoder, which applies `f` to values before encoding them with `self`.
+    ///
+    /// `f` is required to be [`Fn`], so that the returned encoder implements [`EncodedLen`] and
+    /// [`WasmEncode`], use [`MapEncoder::new`] for [`FnMut`] closures.
+    fn map_encode<F, U>(self, f: F) -> MapEncoder<Self, F>
+    where
+        F: Fn(U) -> T,
+    {
+        MapEncoder::new(self, f)
+    }
+}
+
+impl<T, C: Encoder<T>> EncoderExt<T> for C {}
+
+/// Combinator extensions for [`Decoder`]
+pub trait DecoderExt: Decoder + Sized {
+    /// Returns a decoder, which applies `f` to values decoded by `self`
+    fn map<F, T>(self, f: F) -> MapDecoder<Self, F>
+    where
+        F: FnMut(Self::Item) -> T,
+    {
+        MapDecoder::new(self, f)
+    }
+
+    /// Returns a decoder, which applies fallible `f` to values decoded by `self`
+    fn try_map<F, T, E>(self, f: F) -> TryMapDecoder<Self, F>
+    where
+        F: FnMut(Self::Item) -> Result<T, E>,
+    {
+        TryMapDecoder::new(self, f)
+    }
+}
+
+impl<C: Decoder> DecoderExt for C {}
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroU32;
+
+    use crate::cm::{OptionDecoder, OptionEncoder, TupleDecoder, TupleEncoder, U32Codec, U64Codec};
+    use crate::{CoreNameDecoder, CoreNameEncoder, CoreVecEncoder};
+
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct UserId(u64);
+
+    #[test_log::test]
+    fn map() {
+        let mut buf = BytesMut::default();
+        TupleEncoder((
+            U64Codec.map_encode(|UserId(id)| id),
+            OptionEncoder(U32Codec.map_encode(NonZeroU32::get)),
+            CoreNameEncoder.map_encode(|s: &str| s.to_uppercase()),
+        ))
+        .encode((UserId(42), NonZeroU32::new(7), "foo"), &mut buf)
+        .expect("failed to encode tuple");
+        assert_eq!(buf.as_ref(), b"\x2a\x01\x07\x03FOO");
+
+        let v = TupleDecoder::<(_, _, _), _>::new((
+            U64Codec.map(UserId),
+            OptionDecoder::new(U32Codec.try_map(NonZeroU32::try_from)),
+            CoreNameDecoder::default().map(|s| s.len()),
+        ))
+        .decode(&mut buf)
+        .expect("failed to decode tuple");
+        assert_eq!(v, Some((UserId(42), NonZeroU32::new(7), 3)));
+
+        U32Codec
+            .try_map(NonZeroU32::try_from)
+            .decode(&mut BytesMut::from(b"\0".as_slice()))
+            .expect_err("decode should have failed, since 0 is not a valid `NonZeroU32`");
+    }
+
+    #[test_log::test]
+    fn map_vec() {
+        let ids = vec![UserId(1), UserId(300)];
+        let mut enc = CoreVecEncoder(U64Codec.map_encode(|UserId(id)| id));
+        assert_eq!(enc.encoded_len(&ids), 4);
+
+        let mut buf = BytesMut::default();
+        enc.encode(ids.clone(), &mut buf)
+            .expect("failed to encode user IDs");
+        assert_eq!(buf.as_ref(), b"\x02\x01\xac\x02");
+
+        let mut dst = [0; 4];
+        enc.encode_buf(ids, &mut dst.as_mut_slice())
+            .expect("failed to encode user IDs");
+        assert_eq!(dst.as_slice(), buf.as_ref());
+    }
+}
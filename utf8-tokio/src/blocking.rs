@@ -0,0 +1,59 @@
+//! Blocking extensions for [`std::io`] readers and writers
+
+use crate::{invalid_utf8, BufUtf8Ext as _};
+
+/// Blocking UTF-8 extensions for [`std::io::Read`], producing values identical to
+/// [`AsyncReadUtf8`](crate::AsyncReadUtf8)
+pub trait ReadUtf8: std::io::Read {
+    /// Reads a UTF-8 [`char`]
+    fn read_char_utf8(&mut self) -> std::io::Result<char> {
+        let mut buf = [0; 4];
+        for i in 0..4 {
+            self.read_exact(&mut buf[i..=i])?;
+            if let Some((c, _)) = (&buf[..=i]).peek_char_utf8()? {
+                return Ok(c);
+            }
+        }
+        Err(invalid_utf8())
+    }
+}
+
+impl<T: std::io::Read + ?Sized> ReadUtf8 for T {}
+
+/// Blocking UTF-8 extensions for [`std::io::Write`], producing bytes identical to
+/// [`AsyncWriteUtf8`](crate::AsyncWriteUtf8)
+pub trait WriteUtf8: std::io::Write {
+    /// Writes a UTF-8 [`char`]
+    fn write_char_utf8(&mut self, x: char) -> std::io::Result<()> {
+        self.write_all(x.encode_utf8(&mut [0; 4]).as_bytes())
+    }
+}
+
+impl<T: std::io::Write + ?Sized> WriteUtf8 for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocking() {
+        let mut buf = vec![];
+        for c in ['a', 'ƒ', 'Ő', '𐍈'] {
+            buf.write_char_utf8(c).expect("failed to write char");
+        }
+        assert_eq!(buf, "aƒŐ𐍈".as_bytes());
+
+        let mut r = buf.as_slice();
+        for c in ['a', 'ƒ', 'Ő', '𐍈'] {
+            assert_eq!(r.read_char_utf8().expect("failed to read char"), c);
+        }
+        [0xff]
+            .as_slice()
+            .read_char_utf8()
+            .expect_err("reading invalid UTF-8 should have failed");
+        [0xf0, 0x90]
+            .as_slice()
+            .read_char_utf8()
+            .expect_err("reading truncated UTF-8 should have failed");
+    }
+}
@@ -0,0 +1,78 @@
+//! UTF-8 extensions for [`futures_io`] readers and writers
+
+use ::core::future::Future;
+
+use leb128_tokio::futures_io::{read_exact, write_all};
+
+use crate::{invalid_utf8, BufUtf8Ext as _};
+
+/// UTF-8 extensions for [`futures_io::AsyncRead`], producing values
+/// identical to [`ReadUtf8`](crate::blocking::ReadUtf8)
+///
+/// The methods of this trait are not cancellation safe, see
+/// [`AsyncReadUtf8`](crate::AsyncReadUtf8#cancel-safety).
+pub trait AsyncReadUtf8: ::futures_io::AsyncRead {
+    /// Reads a UTF-8 [`char`]
+    ///
+    /// This method is not cancellation safe, see
+    /// [`AsyncReadUtf8`](crate::AsyncReadUtf8#cancel-safety).
+    fn read_char_utf8(&mut self) -> impl Future<Output = std::io::Result<char>>
+    where
+        Self: Unpin,
+    {
+        async move {
+            let mut buf = [0; 4];
+            for i in 0..4 {
+                read_exact(self, &mut buf[i..=i]).await?;
+                if let Some((c, _)) = (&buf[..=i]).peek_char_utf8()? {
+                    return Ok(c);
+                }
+            }
+            Err(invalid_utf8())
+        }
+    }
+}
+
+impl<T: ::futures_io::AsyncRead + ?Sized> AsyncReadUtf8 for T {}
+
+/// UTF-8 extensions for [`futures_io::AsyncWrite`], producing bytes
+/// identical to [`WriteUtf8`](crate::blocking::WriteUtf8)
+pub trait AsyncWriteUtf8: ::futures_io::AsyncWrite {
+    /// Writes a UTF-8 [`char`]
+    fn write_char_utf8(&mut self, x: char) -> impl Future<Output = std::io::Result<()>>
+    where
+        Self: Unpin,
+    {
+        async move { write_all(self, x.encode_utf8(&mut [0; 4]).as_bytes()).await }
+    }
+}
+
+impl<T: ::futures_io::AsyncWrite + ?Sized> AsyncWriteUtf8 for T {}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::io::Cursor;
+
+    use super::*;
+
+    #[test_log::test]
+    fn futures_io() {
+        block_on(async {
+            let mut w = Cursor::new(vec![]);
+            w.write_char_utf8('ƒ').await.expect("failed to write char");
+            w.write_char_utf8('𐍈').await.expect("failed to write char");
+            let mut r = Cursor::new(w.into_inner());
+            assert_eq!(r.read_char_utf8().await.expect("failed to read char"), 'ƒ');
+            assert_eq!(r.read_char_utf8().await.expect("failed to read char"), '𐍈');
+            Cursor::new([0xff])
+                .read_char_utf8()
+                .await
+                .expect_err("reading invalid UTF-8 should have failed");
+            Cursor::new([0xf0, 0x90])
+                .read_char_utf8()
+                .await
+                .expect_err("reading truncated UTF-8 should have failed");
+        });
+    }
+}
@@ -1,14 +1,98 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+pub mod blocking;
+
+mod codec;
+
+#[cfg(feature = "futures-io")]
+pub mod futures_io;
+
+pub use codec::*;
+
+use ::core::fmt::Display;
+#[cfg(feature = "tokio")]
 use ::core::future::Future;
+use ::core::str;
+
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+
+use bytes::{Buf, BufMut};
+#[cfg(feature = "tokio")]
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, AsyncWrite,
+    AsyncWriteExt as _,
+};
+
+/// Error returned for invalid UTF-8 input
+#[derive(Debug)]
+pub struct InvalidUtf8;
+
+impl Display for InvalidUtf8 {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "value is not valid UTF8")
+    }
+}
 
-use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
-use tokio_util::bytes::{Buf as _, BytesMut};
-use tokio_util::codec::{Decoder, Encoder};
+impl ::core::error::Error for InvalidUtf8 {}
 
+#[cfg(feature = "std")]
+impl From<InvalidUtf8> for std::io::Error {
+    fn from(err: InvalidUtf8) -> Self {
+        Self::new(std::io::ErrorKind::InvalidInput, err)
+    }
+}
+
+#[cfg(feature = "std")]
 fn invalid_utf8() -> std::io::Error {
-    std::io::Error::new(std::io::ErrorKind::InvalidInput, "value is not valid UTF8")
+    InvalidUtf8.into()
+}
+
+/// Decodes a UTF-8 [`char`] at the start of `src`.
+/// Returns the value and its encoded length or [`None`] if more data is needed.
+pub fn decode_char_utf8(src: &[u8]) -> Result<Option<(char, usize)>, InvalidUtf8> {
+    let Some(b) = src.first() else {
+        return Ok(None);
+    };
+    let len = char_len_utf8(*b)?;
+    let Some(buf) = src.get(..len) else {
+        return Ok(None);
+    };
+    let c = str::from_utf8(buf)
+        .map_err(|_| InvalidUtf8)?
+        .chars()
+        .next()
+        .ok_or(InvalidUtf8)?;
+    Ok(Some((c, len)))
 }
 
+/// Returns the encoded length of a UTF-8 [`char`] starting with byte `b`
+fn char_len_utf8(b: u8) -> Result<usize, InvalidUtf8> {
+    if b & 0x80 == 0 {
+        Ok(1)
+    } else if b & 0b1110_0000 == 0b1100_0000 {
+        Ok(2)
+    } else if b & 0b1111_0000 == 0b1110_0000 {
+        Ok(3)
+    } else if b & 0b1111_1000 == 0b1111_0000 {
+        Ok(4)
+    } else {
+        Err(InvalidUtf8)
+    }
+}
+
+#[cfg(feature = "tokio")]
+/// UTF-8 extensions for [`AsyncRead`]
+///
+/// # Cancel safety
+///
+/// The methods of this trait are not cancellation safe. If a future returned by them is dropped
+/// before completion, bytes read so far are lost and the stream is left desynchronized.
 pub trait AsyncReadUtf8: AsyncRead {
+    /// Reads a UTF-8 [`char`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadUtf8`](AsyncReadUtf8#cancel-safety).
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", ret, skip_all)
@@ -58,8 +142,39 @@ pub trait AsyncReadUtf8: AsyncRead {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<T: AsyncRead> AsyncReadUtf8 for T {}
 
+#[cfg(feature = "tokio")]
+/// UTF-8 reads from [`AsyncBufRead`] decoding values straight out of the internal buffer
+pub trait AsyncBufReadUtf8: AsyncBufRead {
+    /// Reads a UTF-8 [`char`] directly from the internal buffer, if it contains the whole value,
+    /// otherwise falls back to [`AsyncReadUtf8::read_char_utf8`]
+    ///
+    /// This method is not cancellation safe, see [`AsyncReadUtf8`](AsyncReadUtf8#cancel-safety).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", ret, skip_all)
+    )]
+    fn read_char_utf8_buffered(&mut self) -> impl Future<Output = std::io::Result<char>>
+    where
+        Self: Unpin + Sized,
+    {
+        async move {
+            let buf = self.fill_buf().await?;
+            if let Some((c, n)) = buf.peek_char_utf8()? {
+                self.consume(n);
+                return Ok(c);
+            }
+            self.read_char_utf8().await
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: AsyncBufRead> AsyncBufReadUtf8 for T {}
+
+#[cfg(feature = "tokio")]
 pub trait AsyncWriteUtf8: AsyncWrite {
     #[cfg_attr(
         feature = "tracing",
@@ -73,110 +188,90 @@ pub trait AsyncWriteUtf8: AsyncWrite {
     }
 }
 
+#[cfg(feature = "tokio")]
 impl<T: AsyncWrite> AsyncWriteUtf8 for T {}
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
-pub struct Utf8Codec;
-
-impl Decoder for Utf8Codec {
-    type Item = char;
-    type Error = std::io::Error;
-
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let Some(b) = src.first().copied() else {
-            src.reserve(1);
-            return Ok(None);
-        };
-        let i = if b & 0x80 == 0 {
-            src.advance(1);
-            u32::from(b)
-        } else if b & 0b1110_0000 == 0b1100_0000 {
-            let Some(b2) = src.get(1).copied() else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if b2 & 0b1100_0000 != 0b1000_0000 {
-                return Err(invalid_utf8());
-            }
-            src.advance(2);
-            u32::from(b & 0b0001_1111) << 6 | u32::from(b2 & 0b0011_1111)
-        } else if b & 0b1111_0000 == 0b1110_0000 {
-            let Some(b2) = src.get(1).copied() else {
-                src.reserve(2);
-                return Ok(None);
-            };
-            let Some(b3) = src.get(2).copied() else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if b2 & 0b1100_0000 != 0b1000_0000 || b3 & 0b1100_0000 != 0b1000_0000 {
-                return Err(invalid_utf8());
-            }
-            src.advance(3);
-            u32::from(b & 0b0000_1111) << 12
-                | u32::from(b2 & 0b0011_1111) << 6
-                | u32::from(b3 & 0b0011_1111)
-        } else if b & 0b1111_1000 == 0b1111_0000 {
-            let Some(b2) = src.get(1).copied() else {
-                src.reserve(3);
-                return Ok(None);
-            };
-            let Some(b3) = src.get(2).copied() else {
-                src.reserve(2);
-                return Ok(None);
-            };
-            let Some(b4) = src.get(3).copied() else {
-                src.reserve(1);
-                return Ok(None);
-            };
-            if b2 & 0b1100_0000 != 0b1000_0000
-                || b3 & 0b1100_0000 != 0b1000_0000
-                || b4 & 0b1100_0000 != 0b1000_0000
-            {
-                return Err(invalid_utf8());
-            }
-            src.advance(4);
-            u32::from(b & 0b0000_0111) << 18
-                | u32::from(b2 & 0b0011_1111) << 12
-                | u32::from(b3 & 0b0011_1111) << 6
-                | u32::from(b4 & 0b0011_1111)
-        } else {
-            return Err(invalid_utf8());
-        };
-        let c = i
-            .try_into()
-            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
-        Ok(Some(c))
+/// Copies up to 4 leading bytes of `src` into `dst` without advancing `src`
+#[cfg(feature = "std")]
+fn peek(src: &(impl Buf + ?Sized), dst: &mut [u8; 4]) -> usize {
+    let mut chunks = [IoSlice::new(&[]); 4];
+    let k = src.chunks_vectored(&mut chunks);
+    let mut n = 0;
+    for chunk in &chunks[..k] {
+        let m = chunk.len().min(4 - n);
+        dst[n..n + m].copy_from_slice(&chunk[..m]);
+        n += m;
+        if n == 4 {
+            break;
+        }
     }
+    n
 }
 
-impl Encoder<char> for Utf8Codec {
-    type Error = std::io::Error;
-
-    fn encode(&mut self, x: char, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        dst.extend_from_slice(x.encode_utf8(&mut [0; 4]).as_bytes());
-        Ok(())
-    }
+/// Copies up to 4 leading bytes of `src` into `dst` without advancing `src`.
+/// `Buf::chunks_vectored` is not available without `std`, so only [`Buf::chunk`] is visible.
+#[cfg(not(feature = "std"))]
+fn peek(src: &(impl Buf + ?Sized), dst: &mut [u8; 4]) -> usize {
+    let chunk = src.chunk();
+    let n = chunk.len().min(4);
+    dst[..n].copy_from_slice(&chunk[..n]);
+    n
 }
 
-impl Encoder<&char> for Utf8Codec {
-    type Error = std::io::Error;
+/// UTF-8 extensions for [`Buf`].
+///
+/// [`peek_char_utf8`](Self::peek_char_utf8) only inspects the bytes exposed by
+/// [`Buf::chunks_vectored`], or just [`Buf::chunk`] without `std`, which may be fewer than
+/// [`Buf::remaining`], e.g. for a [`Take`](bytes::buf::Take).
+/// [`get_char_utf8`](Self::get_char_utf8) determines the length of the value from its first
+/// byte and copies such buffers once they hold all of it.
+pub trait BufUtf8Ext: Buf {
+    /// Decodes a UTF-8 [`char`] at the start of the buffer without advancing it.
+    /// Returns the value and its encoded length or [`None`] if more data is needed.
+    fn peek_char_utf8(&self) -> Result<Option<(char, usize)>, InvalidUtf8> {
+        let mut buf = [0; 4];
+        let n = peek(self, &mut buf);
+        decode_char_utf8(&buf[..n])
+    }
 
-    fn encode(&mut self, x: &char, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        self.encode(*x, dst)
+    /// Gets a UTF-8 [`char`] from the buffer, advancing it past the value.
+    /// Returns [`None`] and leaves the buffer untouched if more data is needed.
+    fn get_char_utf8(&mut self) -> Result<Option<char>, InvalidUtf8> {
+        let mut buf = [0; 4];
+        let n = peek(self, &mut buf);
+        if let Some((c, n)) = decode_char_utf8(&buf[..n])? {
+            self.advance(n);
+            return Ok(Some(c));
+        }
+        let Some(b) = buf[..n].first() else {
+            return Ok(None);
+        };
+        let n = char_len_utf8(*b)?;
+        if self.remaining() < n {
+            return Ok(None);
+        }
+        self.copy_to_slice(&mut buf[..n]);
+        let (c, _) = decode_char_utf8(&buf[..n])?.expect("buffer contains a complete value");
+        Ok(Some(c))
     }
 }
 
-impl Encoder<&&char> for Utf8Codec {
-    type Error = std::io::Error;
+impl<T: Buf + ?Sized> BufUtf8Ext for T {}
 
-    fn encode(&mut self, x: &&char, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        self.encode(**x, dst)
+/// UTF-8 extensions for [`BufMut`]
+pub trait BufMutUtf8Ext: BufMut {
+    /// Puts a UTF-8 [`char`] into the buffer
+    fn put_char_utf8(&mut self, x: char) {
+        self.put_slice(x.encode_utf8(&mut [0; 4]).as_bytes());
     }
 }
 
-#[cfg(test)]
+impl<T: BufMut + ?Sized> BufMutUtf8Ext for T {}
+
+#[cfg(all(test, feature = "tokio"))]
 mod tests {
+    use tokio::io::BufReader;
+
     use super::*;
 
     #[test_log::test(tokio::test)]
@@ -237,4 +332,57 @@ mod tests {
             .expect("failed to read `𐍈`");
         assert_eq!(v, '𐍈');
     }
+
+    #[test_log::test(tokio::test)]
+    async fn buffered() {
+        let mut r = BufReader::with_capacity(3, "$И𐍈".as_bytes());
+        let v = r
+            .read_char_utf8_buffered()
+            .await
+            .expect("failed to read `$`");
+        assert_eq!(v, '$');
+        let v = r
+            .read_char_utf8_buffered()
+            .await
+            .expect("failed to read `И`");
+        assert_eq!(v, 'И');
+        let v = r
+            .read_char_utf8_buffered()
+            .await
+            .expect("failed to read `𐍈`");
+        assert_eq!(v, '𐍈');
+        r.read_char_utf8_buffered()
+            .await
+            .expect_err("read should have failed, since the reader is empty");
+    }
+
+    #[test]
+    fn buf() {
+        let mut buf = vec![];
+        buf.put_char_utf8('$');
+        buf.put_char_utf8('И');
+        buf.put_char_utf8('𐍈');
+        assert_eq!(buf, "$И𐍈".as_bytes());
+
+        let mut buf = buf.as_slice();
+        assert_eq!(buf.get_char_utf8().unwrap(), Some('$'));
+        assert_eq!(buf.peek_char_utf8().unwrap(), Some(('И', 2)));
+        assert_eq!(buf.get_char_utf8().unwrap(), Some('И'));
+        assert_eq!(buf[..3].as_ref().get_char_utf8().unwrap(), None);
+        let (a, b) = buf.split_at(1);
+        assert_eq!(Buf::chain(a, b).get_char_utf8().unwrap(), Some('𐍈'));
+        let mut take = Buf::chain(a, b).take(4);
+        assert_eq!(take.peek_char_utf8().unwrap(), None);
+        assert_eq!(take.get_char_utf8().unwrap(), Some('𐍈'));
+        let mut take = Buf::chain(a, b).take(3);
+        assert_eq!(take.get_char_utf8().unwrap(), None);
+        assert_eq!(take.remaining(), 3);
+        assert_eq!(buf.get_char_utf8().unwrap(), Some('𐍈'));
+        assert_eq!(buf.get_char_utf8().unwrap(), None);
+
+        [0xc0, 0x41]
+            .as_slice()
+            .get_char_utf8()
+            .expect_err("get should have failed, since it is not valid UTF-8");
+    }
 }